@@ -1,10 +1,12 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::Duration;
 
 /// 记忆模式（隐藏文本模式）
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 pub enum MemoryMode {
     /// 关闭 - 正常显示所有文本
+    #[default]
     Off,
     /// 部分隐藏 - 隐藏部分单元
     Partial(PartialLevel),
@@ -35,12 +37,6 @@ impl MemoryMode {
     }
 }
 
-impl Default for MemoryMode {
-    fn default() -> Self {
-        Self::Off
-    }
-}
-
 /// 课程类型
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum LessonType {
@@ -66,6 +62,29 @@ pub struct LessonMeta {
     #[serde(with = "duration_serde")]
     pub estimated_time: Duration,
     pub prerequisite_ids: Vec<u32>,
+    /// 作者设定的目标速度（WPM，可选）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_wpm: Option<f64>,
+    /// 作者设定的目标准确率（0.0 ~ 1.0，可选）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_accuracy: Option<f64>,
+}
+
+impl LessonMeta {
+    /// 是否设置了练习目标
+    pub fn has_goal(&self) -> bool {
+        self.target_wpm.is_some() || self.target_accuracy.is_some()
+    }
+
+    /// 判断成绩是否达到作者设定的目标（未设置目标时返回 None）
+    pub fn goal_met(&self, wpm: f64, accuracy: f64) -> Option<bool> {
+        if !self.has_goal() {
+            return None;
+        }
+        let wpm_ok = self.target_wpm.is_none_or(|target| wpm >= target);
+        let accuracy_ok = self.target_accuracy.is_none_or(|target| accuracy >= target);
+        Some(wpm_ok && accuracy_ok)
+    }
 }
 
 /// 练习单元
@@ -76,6 +95,16 @@ pub struct Exercise {
     /// 说明/提示（可选，支持跨语言说明）
     /// 例如：英文练习可以用中文说明
     pub hint: Option<String>,
+    /// 完形填空：需要挖空输入的单词序号（按空白分词，0-based）
+    /// 为空时是普通练习；非空时只有这些单词需要输入，其余文本作为上下文直接显示
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cloze_words: Vec<usize>,
+    /// 该练习使用的输入模式（None 使用会话设置）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_mode: Option<InputMode>,
+    /// 该练习使用的记忆模式（None 使用会话设置）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mode: Option<MemoryMode>,
 }
 
 impl Exercise {
@@ -83,6 +112,9 @@ impl Exercise {
         Self {
             content: content.into(),
             hint: None,
+            cloze_words: Vec::new(),
+            input_mode: None,
+            memory_mode: None,
         }
     }
 
@@ -90,10 +122,87 @@ impl Exercise {
         Self {
             content: content.into(),
             hint: Some(hint.into()),
+            cloze_words: Vec::new(),
+            input_mode: None,
+            memory_mode: None,
+        }
+    }
+
+    /// 创建完形填空练习
+    pub fn cloze(content: impl Into<String>, cloze_words: Vec<usize>) -> Self {
+        Self {
+            content: content.into(),
+            hint: None,
+            cloze_words,
+            input_mode: None,
+            memory_mode: None,
+        }
+    }
+
+    /// 设置该练习专用的输入模式
+    pub fn with_input_mode(mut self, input_mode: InputMode) -> Self {
+        self.input_mode = Some(input_mode);
+        self
+    }
+
+    /// 设置该练习专用的记忆模式
+    pub fn with_memory_mode(mut self, memory_mode: MemoryMode) -> Self {
+        self.memory_mode = Some(memory_mode);
+        self
+    }
+
+    /// 是否为完形填空练习
+    pub fn is_cloze(&self) -> bool {
+        !self.cloze_words.is_empty()
+    }
+
+    /// 每个字符是否需要输入（普通练习全部为 true，完形填空只有挖空单词为 true）
+    pub fn input_mask(&self) -> Vec<bool> {
+        if !self.is_cloze() {
+            return vec![true; self.content.chars().count()];
+        }
+
+        let mut mask = Vec::with_capacity(self.content.len());
+        let mut word_index = 0;
+        let mut in_word = false;
+        for ch in self.content.chars() {
+            if ch.is_whitespace() {
+                if in_word {
+                    word_index += 1;
+                    in_word = false;
+                }
+                mask.push(false);
+            } else {
+                in_word = true;
+                mask.push(self.cloze_words.contains(&word_index));
+            }
         }
+        mask
     }
 }
 
+/// 错误分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorCategory {
+    /// 句首大小写错误（字母正确，大小写错误）
+    Capitalization,
+    /// 其他错误
+    Other,
+}
+
+/// 手指（用于按手指统计错误）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+}
+
 /// 单个练习的统计
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExerciseStats {
@@ -104,6 +213,33 @@ pub struct ExerciseStats {
     pub total_keystrokes: usize,
     pub error_count: usize,
     pub duration_secs: u64,
+    /// 按分类统计的错误按键数
+    #[serde(default)]
+    pub error_categories: HashMap<ErrorCategory, usize>,
+    /// 大小写错误的按键数（字母正确、大小写错误，不限于句首）
+    #[serde(default)]
+    pub capitalization_errors: usize,
+    /// 完美练习模式下达到无错所用的尝试次数（未开启时为 None）
+    #[serde(default)]
+    pub attempts_to_perfect: Option<usize>,
+    /// 按全部按键（含输错的）计算的 WPM；`wpm` 只计正确按键
+    #[serde(default)]
+    pub raw_wpm: f64,
+    /// 最快的 5 秒爆发 WPM
+    #[serde(default)]
+    pub peak_wpm: f64,
+    /// 停顿时长（按键间隔超过停顿阈值的部分，不计入 `duration_secs`）
+    #[serde(default)]
+    pub idle_secs: u64,
+    /// 按手指统计的（错误数, 总数），只统计字母
+    #[serde(default)]
+    pub finger_stats: HashMap<Finger, (usize, usize)>,
+    /// CJK 只计汉字时不计入速度的正确按键数（标点、空格等；未开启时为 0）
+    #[serde(default)]
+    pub excluded_from_wpm: usize,
+    /// 输对的按键数（退格纠正过的错误按键也不计入）
+    #[serde(default)]
+    pub correct_keystrokes: usize,
 }
 
 impl ExerciseStats {
@@ -130,8 +266,22 @@ impl ExerciseStats {
             total_keystrokes: keystrokes,
             error_count: errors,
             duration_secs: duration.as_secs(),
+            error_categories: HashMap::new(),
+            capitalization_errors: 0,
+            attempts_to_perfect: None,
+            raw_wpm: 0.0,
+            peak_wpm: 0.0,
+            idle_secs: 0,
+            finger_stats: HashMap::new(),
+            excluded_from_wpm: 0,
+            correct_keystrokes: keystrokes.saturating_sub(errors),
         }
     }
+
+    /// 只计正确按键的 WPM（即 `wpm`）
+    pub fn net_wpm(&self) -> f64 {
+        self.wpm
+    }
 }
 
 /// 课程定义（修改：从 source_text 改为 exercises）
@@ -145,34 +295,165 @@ pub struct Lesson {
     /// 练习列表，用户将逐一完成
     pub exercises: Vec<Exercise>,
     pub meta: LessonMeta,
+    /// 是否用 ↵ 标出换行位置（诗歌、格式化文本），只影响显示，换行始终需要精确输入
+    #[serde(default, alias = "enforce_newlines")]
+    pub mark_newlines: bool,
+    /// 重新开始练习时按该规则重新生成内容（None 时内容固定）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regenerate_on_restart: Option<ExerciseGenerator>,
+}
+
+impl Lesson {
+    /// 全部练习内容的纯文本（练习之间插入 `separator`），用于分享或打印
+    /// `include_hints` 时在有提示的练习前加一行 `# 提示`
+    pub fn to_plain_text(&self, separator: &str, include_hints: bool) -> String {
+        self.exercises
+            .iter()
+            .map(|exercise| match &exercise.hint {
+                Some(hint) if include_hints => format!("# {}\n{}", hint, exercise.content),
+                _ => exercise.content.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
+
+/// 练习内容生成规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ExerciseGenerator {
+    /// 从单词表中随机抽取 `count` 个不重复的单词，以空格连接
+    SampleWords { words: Vec<String>, count: usize },
 }
 
 /// 输入模式
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 pub enum InputMode {
-    Strict,    // 必须纠正错误
+    Strict, // 必须纠正错误
+    #[default]
     Forgiving, // 标记错误但可继续（默认）
     Invisible, // 不显示错误（盲打）
 }
 
-impl Default for InputMode {
-    fn default() -> Self {
-        Self::Forgiving
+/// 严格模式下输错后的恢复方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum StrictRecovery {
+    #[default]
+    RetypeExact, // 必须输入正确字符才能继续（默认）
+    AnyKey, // 任意一次按键解除阻塞，然后重新输入该字符
+}
+
+/// 完成练习后的跳转方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum AutoAdvance {
+    #[default]
+    OnPerfect, // 无错完成后自动进入下一个练习（默认）
+    Manual, // 从不自动跳转，按 Enter 继续
+}
+
+/// 目标文本中连续空格的匹配方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum WhitespacePolicy {
+    Exact,    // 必须输入相同数量的空格（代码）
+    Collapse, // 连续的多个空格输入一个或全部都可以（散文）
+}
+
+impl WhitespacePolicy {
+    /// 课程类型的默认策略：代码和特殊符号保留精确空格，其余宽松
+    pub fn for_lesson_type(lesson_type: LessonType) -> Self {
+        match lesson_type {
+            LessonType::Code | LessonType::SpecialChars => WhitespacePolicy::Exact,
+            LessonType::Prose | LessonType::Chinese => WhitespacePolicy::Collapse,
+        }
     }
 }
 
-/// 练习模式
+/// 是否为 CJK 表意文字（汉字）
+pub fn is_cjk_char(ch: char) -> bool {
+    matches!(ch,
+        '\u{4E00}'..='\u{9FFF}' |  // CJK 统一表意文字
+        '\u{3400}'..='\u{4DBF}' |  // CJK 扩展 A
+        '\u{20000}'..='\u{2A6DF}' | // CJK 扩展 B
+        '\u{2A700}'..='\u{2B73F}' | // CJK 扩展 C
+        '\u{2B740}'..='\u{2B81F}' | // CJK 扩展 D
+        '\u{2B820}'..='\u{2CEAF}' | // CJK 扩展 E
+        '\u{F900}'..='\u{FAFF}' |   // CJK 兼容表意文字
+        '\u{2F800}'..='\u{2FA1F}'   // CJK 兼容表意文字补充
+    )
+}
+
+/// WordCompleted 事件的单词边界
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum WordBoundary {
+    Whitespace,  // 空格或换行结束一个单词（拉丁文字）
+    Punctuation, // 空白或标点结束一个单词（中文按短语）
+    EachChar,    // 每个汉字算一个单词（中文逐字，标点不计）
+}
+
+impl WordBoundary {
+    /// 课程类型的默认边界：中文逐字，其余按空白
+    pub fn for_lesson_type(lesson_type: LessonType) -> Self {
+        match lesson_type {
+            LessonType::Chinese => WordBoundary::EachChar,
+            LessonType::Prose | LessonType::Code | LessonType::SpecialChars => {
+                WordBoundary::Whitespace
+            }
+        }
+    }
+
+    /// 正确输入 `ch` 后是否完成了一个单词
+    pub fn ends_word(self, ch: char) -> bool {
+        match self {
+            WordBoundary::Whitespace => ch == ' ' || ch == '\n',
+            WordBoundary::Punctuation => {
+                ch.is_whitespace()
+                    || ch.is_ascii_punctuation()
+                    || matches!(ch,
+                        '\u{2010}'..='\u{2027}' | // 破折号、引号、省略号等
+                        '\u{3000}'..='\u{303F}' | // CJK 标点（、。「」等）
+                        '\u{FF01}'..='\u{FF0F}' | // 全角标点（！，．等）
+                        '\u{FF1A}'..='\u{FF20}' | // 全角标点（：；？等）
+                        '\u{FF3B}'..='\u{FF40}' |
+                        '\u{FF5B}'..='\u{FF65}'
+                    )
+            }
+            WordBoundary::EachChar => is_cjk_char(ch),
+        }
+    }
+}
+
+/// 练习计时的起点
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum TimerStart {
+    #[default]
+    OnFirstKey, // 首次按键时开始计时（默认）
+    OnShow, // 练习展示后（调用 mark_shown）开始计时，此前的按键被忽略
+}
+
+/// 练习模式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 pub enum PracticeMode {
-    Zen,     // 禅意模式（无时间压力）
+    #[default]
+    Zen, // 禅意模式（无时间压力）
     Timed,   // 限时挑战
     Endless, // 无限模式
 }
 
-impl Default for PracticeMode {
-    fn default() -> Self {
-        Self::Zen
-    }
+/// 练习顺序
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum ExerciseOrder {
+    #[default]
+    Sequential, // 按课程顺序
+    Adaptive, // 根据历史表现加权，薄弱练习优先
+}
+
+/// 课程列表排序方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum LessonSort {
+    #[default]
+    Id, // 按课程 ID
+    Title,         // 按标题字母顺序
+    Difficulty,    // 按难度从低到高
+    LastPracticed, // 最近练习的在前，没有练习记录的在后
 }
 
 /// 打字事件（用于 UI 反馈）
@@ -189,6 +470,11 @@ pub enum TypingEvent {
     MilestoneReached {
         progress: f32, // 0.0 - 1.0
     },
+    /// 完成课程中的一个练习（index 为课程中的练习索引）
+    ExerciseCompleted {
+        index: usize,
+        stats: ExerciseStats,
+    },
     SessionCompleted {
         stats: SessionStats,
     },
@@ -216,6 +502,7 @@ impl UnitType {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         match s {
             "word" => UnitType::Word,
@@ -227,7 +514,7 @@ impl UnitType {
 }
 
 /// 薄弱单元
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WeakUnit {
     pub content: String,     // 内容（可以是单字符或多字符）
     pub unit_type: UnitType, // 单元类型
@@ -256,6 +543,24 @@ impl WeakUnit {
     }
 }
 
+/// 薄弱单元的筛选条件
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WeakUnitConfig {
+    pub min_occurrences: usize, // 至少出现的次数
+    pub min_error_rate: f32,    // 错误率需高于该值
+    pub max_results: usize,     // 最多保留的单元数（按错误率从高到低）
+}
+
+impl Default for WeakUnitConfig {
+    fn default() -> Self {
+        Self {
+            min_occurrences: 3,
+            min_error_rate: 0.15,
+            max_results: 10,
+        }
+    }
+}
+
 /// 会话统计数据（修改：支持多练习）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionStats {
@@ -273,6 +578,93 @@ pub struct SessionStats {
     pub duration_secs: u64,
     pub timestamp: i64,
     pub weak_units: Vec<WeakUnit>,
+    /// 按分类汇总的错误按键数
+    #[serde(default)]
+    pub error_categories: HashMap<ErrorCategory, usize>,
+    /// 相对目标速度的节奏偏差（未设置目标速度时为 None）
+    #[serde(default)]
+    pub pace_deviation: Option<PaceDeviation>,
+    /// 每个字符的按键次数（含输错的按键，不含退格）
+    #[serde(default)]
+    pub char_counts: HashMap<char, u64>,
+    /// 平均延迟最长的拉丁字母双字母（如 "th"），按延迟降序
+    #[serde(default)]
+    pub slow_bigrams: Vec<(String, Duration)>,
+    /// 每个拉丁字母双字母的（双字母, 总延迟, 次数），不限数量，用于跨会话按次数汇总
+    #[serde(default)]
+    pub bigram_latency: Vec<(String, Duration, u32)>,
+    /// 节奏稳定度（0-1，按键间隔越均匀越高；按键太少时为 None）
+    #[serde(default)]
+    pub consistency: Option<f64>,
+    /// 最快的 5 秒爆发 WPM
+    #[serde(default)]
+    pub peak_wpm: f64,
+    /// 按全部按键（含输错的）计算的 WPM；`overall_wpm` 只计正确按键
+    #[serde(default)]
+    pub raw_wpm: f64,
+    /// 按手指汇总的（错误数, 总数），只统计字母
+    #[serde(default)]
+    pub finger_stats: HashMap<Finger, (usize, usize)>,
+    /// 最长的心流时长（速度持续高于平均且准确率较高）
+    #[serde(default)]
+    pub longest_flow: Duration,
+    /// 按键间隔的百分位（按键太少时为 None）
+    #[serde(default)]
+    pub latency: Option<LatencyProfile>,
+}
+
+impl SessionStats {
+    /// 只计正确按键的 WPM（即 `overall_wpm`）
+    pub fn net_wpm(&self) -> f64 {
+        self.overall_wpm
+    }
+}
+
+/// 按键间隔的百分位分布（不含练习开始前的阅读停顿和中途停顿）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatencyProfile {
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+impl LatencyProfile {
+    /// 按最近秩法计算百分位（没有按键间隔时返回 None）
+    pub fn from_intervals(intervals: &[Duration]) -> Option<Self> {
+        if intervals.is_empty() {
+            return None;
+        }
+        let mut sorted = intervals.to_vec();
+        sorted.sort_unstable();
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+
+        Some(Self {
+            p50: percentile(50.0),
+            p95: percentile(95.0),
+            p99: percentile(99.0),
+        })
+    }
+}
+
+/// 节奏偏差（单位：字符，正数表示领先于目标节奏）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PaceDeviation {
+    /// 平均偏差（带符号）
+    pub mean: f64,
+    /// 平均绝对偏差
+    pub mean_abs: f64,
+}
+
+/// 课程中单个练习在本次会话中的状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ExerciseStatus {
+    NotStarted,
+    Current,
+    Completed(f64), // 最近一次完成的准确率
+    Skipped,        // 被跳过（手动跳到后面的练习，或不在练习顺序中）
 }
 
 // Duration 序列化辅助模块
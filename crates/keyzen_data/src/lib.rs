@@ -1,12 +1,21 @@
 use anyhow::{Context, Result};
-use keyzen_core::{Lesson, LessonType};
-use log::debug;
+use keyzen_core::{
+    is_cjk_char, Difficulty, Exercise, ExerciseGenerator, Lesson, LessonMeta, LessonType,
+};
+use log::{debug, warn};
 use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use rand::seq::SliceRandom;
+use rand::Rng;
 use rust_embed::RustEmbed;
 use std::collections::HashMap;
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::channel;
+use std::time::Duration;
+
+/// 导入课程的起始 ID（避免与内置课程冲突）
+const IMPORTED_LESSON_ID_START: u32 = 10000;
 
 /// 嵌入式课程资源
 #[derive(RustEmbed)]
@@ -16,12 +25,38 @@ struct EmbeddedLessons;
 
 pub struct LessonLoader {
     user_data_dir: PathBuf,
+    /// 额外的课程目录（如共享课程文件夹），按添加顺序加载
+    source_dirs: Vec<PathBuf>,
     watcher: Option<RecommendedWatcher>,
 }
 
+/// 课程来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LessonSource {
+    /// 内置课程
+    Builtin,
+    /// 用户课程目录或额外添加的课程目录
+    Dir(PathBuf),
+}
+
+/// 课程 ID 冲突：后加载的课程覆盖了先加载的同 ID 课程
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LessonIdConflict {
+    pub id: u32,
+    /// 被覆盖的课程来源
+    pub overridden: LessonSource,
+    /// 最终生效的课程来源
+    pub winner: LessonSource,
+}
+
 impl LessonLoader {
     pub fn new(_lessons_dir: impl Into<PathBuf>) -> Result<Self> {
-        let user_data_dir = Self::get_user_data_dir()?;
+        Self::with_user_data_dir(Self::get_user_data_dir()?)
+    }
+
+    /// 使用指定的用户课程目录创建加载器
+    pub fn with_user_data_dir(user_data_dir: impl Into<PathBuf>) -> Result<Self> {
+        let user_data_dir = user_data_dir.into();
 
         // 确保用户数据目录存在
         if !user_data_dir.exists() {
@@ -32,10 +67,18 @@ impl LessonLoader {
 
         Ok(Self {
             user_data_dir,
+            source_dirs: Vec::new(),
             watcher: None,
         })
     }
 
+    /// 添加额外的课程目录（后添加的目录覆盖先前目录中同 ID 的课程）
+    pub fn add_source_dir(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        debug!("📁 添加课程目录: {:?}", path);
+        self.source_dirs.push(path);
+    }
+
     /// 获取系统数据目录
     fn get_user_data_dir() -> Result<PathBuf> {
         #[cfg(target_os = "macos")]
@@ -53,34 +96,55 @@ impl LessonLoader {
         Ok(base.join("Keyzen").join("lessons"))
     }
 
-    /// 加载所有课程（用户目录覆盖内置资源）
+    /// 加载所有课程（用户目录覆盖内置资源，额外目录依次覆盖）
     pub fn load_all(&self) -> Result<Vec<Lesson>> {
-        let mut lessons_map: HashMap<u32, Lesson> = HashMap::new();
+        Ok(self.load_all_with_conflicts()?.0)
+    }
+
+    /// 加载所有课程，同时返回课程 ID 冲突
+    ///
+    /// 加载顺序：内置课程 → 用户课程目录 → `add_source_dir` 添加的目录，
+    /// 后加载的课程覆盖先加载的同 ID 课程
+    pub fn load_all_with_conflicts(&self) -> Result<(Vec<Lesson>, Vec<LessonIdConflict>)> {
+        let mut lessons_map: HashMap<u32, (Lesson, LessonSource)> = HashMap::new();
+        let mut conflicts = Vec::new();
 
         // 1. 先加载嵌入式内置课程
         let builtin = self.load_embedded_lessons()?;
         debug!("📚 加载嵌入式课程: {} 个", builtin.len());
         for lesson in builtin {
-            lessons_map.insert(lesson.id, lesson);
+            lessons_map.insert(lesson.id, (lesson, LessonSource::Builtin));
         }
 
-        // 2. 再加载用户课程（覆盖同 ID 的内置课程）
-        let mut user_lessons = Vec::new();
-        self.load_from_dir_recursive(&self.user_data_dir, &mut user_lessons)?;
-        if !user_lessons.is_empty() {
-            debug!("📚 加载用户课程: {} 个", user_lessons.len());
-        }
-        for lesson in user_lessons {
-            if lessons_map.contains_key(&lesson.id) {
-                debug!("🔄 用户课程覆盖内置课程 ID: {}", lesson.id);
+        // 2. 再依次加载用户课程和额外目录（覆盖同 ID 的课程）
+        for dir in std::iter::once(&self.user_data_dir).chain(&self.source_dirs) {
+            let mut dir_lessons = Vec::new();
+            self.load_from_dir_recursive(dir, &mut dir_lessons)?;
+            if !dir_lessons.is_empty() {
+                debug!("📚 加载课程目录 {:?}: {} 个", dir, dir_lessons.len());
+            }
+
+            for lesson in dir_lessons {
+                let id = lesson.id;
+                let source = LessonSource::Dir(dir.clone());
+                if let Some((_, overridden)) = lessons_map.insert(id, (lesson, source.clone())) {
+                    debug!("🔄 课程 ID {} 被 {:?} 覆盖", id, dir);
+                    conflicts.push(LessonIdConflict {
+                        id,
+                        overridden,
+                        winner: source,
+                    });
+                }
             }
-            lessons_map.insert(lesson.id, lesson);
         }
 
         // 3. 排序返回
-        let mut lessons: Vec<_> = lessons_map.into_values().collect();
+        let mut lessons: Vec<_> = lessons_map
+            .into_values()
+            .map(|(lesson, _)| lesson)
+            .collect();
         lessons.sort_by_key(|l| l.id);
-        Ok(lessons)
+        Ok((lessons, conflicts))
     }
 
     /// 从嵌入式资源加载课程
@@ -161,7 +225,7 @@ impl LessonLoader {
             .collect())
     }
 
-    /// 启动文件系统监听，自动检测课程变化（仅监听用户数据目录）
+    /// 启动文件系统监听，自动检测课程变化（仅监听用户数据目录和额外目录）
     pub fn start_watching<F>(&mut self, callback: F) -> Result<()>
     where
         F: Fn() + Send + 'static,
@@ -183,8 +247,13 @@ impl LessonLoader {
             }
         })?;
 
-        // 只监听用户数据目录（内置目录编译到二进制，无需监听）
+        // 只监听用户数据目录和额外目录（内置目录编译到二进制，无需监听）
         watcher.watch(&self.user_data_dir, RecursiveMode::Recursive)?;
+        for dir in &self.source_dirs {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::Recursive) {
+                warn!("⚠️  无法监听课程目录 {:?}: {}", dir, e);
+            }
+        }
 
         debug!("👀 开始监听用户课程目录: {:?}", self.user_data_dir);
 
@@ -203,6 +272,255 @@ impl LessonLoader {
     pub fn get_user_data_dir_path() -> Result<PathBuf> {
         Self::get_user_data_dir()
     }
+
+    /// 将文本导入为用户课程（写入用户数据目录），返回课程文件路径
+    pub fn import_text(&self, title: &str, text: &str) -> Result<PathBuf> {
+        let id = self.next_imported_id()?;
+        let lesson =
+            lesson_from_text(id, title, text).ok_or_else(|| anyhow::anyhow!("导入的文本为空"))?;
+
+        let content = ron::ser::to_string_pretty(&lesson, ron::ser::PrettyConfig::default())?;
+        let path = self.user_data_dir.join(format!("imported_{}.ron", id));
+        fs::write(&path, content).with_context(|| format!("Failed to write lesson: {:?}", path))?;
+        debug!("✅ 导入课程: {:?}", path);
+
+        Ok(path)
+    }
+
+    /// 将单词表文件（换行或空格分隔）导入为速度练习课程：单词随机打乱后每 N 个组成一个练习
+    /// 课程 ID 与 import_text 相同方式分配，标题取文件名
+    pub fn import_word_list(
+        &self,
+        path: impl AsRef<Path>,
+        language: &str,
+        words_per_exercise: usize,
+    ) -> Result<Lesson> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read word list: {:?}", path))?;
+        let title = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let lesson = lesson_from_word_list(
+            self.next_imported_id()?,
+            &title,
+            language,
+            &text,
+            words_per_exercise,
+            &mut rand::thread_rng(),
+        )
+        .ok_or_else(|| anyhow::anyhow!("单词表为空或每个练习的单词数为 0"))?;
+        debug!(
+            "✅ 导入单词表: {:?}（{} 个练习）",
+            path,
+            lesson.exercises.len()
+        );
+
+        Ok(lesson)
+    }
+
+    /// 下一个可用的导入课程 ID
+    fn next_imported_id(&self) -> Result<u32> {
+        Ok(self
+            .load_all()?
+            .iter()
+            .map(|l| l.id + 1)
+            .max()
+            .unwrap_or(0)
+            .max(IMPORTED_LESSON_ID_START))
+    }
+
+    /// 导出所有用户课程到目录（保留子目录结构，不含内置课程），返回导出的文件数
+    pub fn export_all(&self, dest: impl AsRef<Path>) -> Result<usize> {
+        let dest = dest.as_ref();
+        fs::create_dir_all(dest)
+            .with_context(|| format!("Failed to create export dir: {:?}", dest))?;
+
+        let count = copy_lessons_recursive(&self.user_data_dir, dest)?;
+        debug!("✅ 导出 {} 个用户课程到: {:?}", count, dest);
+        Ok(count)
+    }
+
+    /// 导出课程的纯文本（含提示，练习之间空一行）到课程目录旁的 exports 目录，返回文件路径
+    pub fn export_lesson_text(&self, lesson: &Lesson) -> Result<PathBuf> {
+        let dest = self
+            .user_data_dir
+            .parent()
+            .unwrap_or(&self.user_data_dir)
+            .join("exports");
+        fs::create_dir_all(&dest)
+            .with_context(|| format!("Failed to create export dir: {:?}", dest))?;
+
+        let path = dest.join(format!("lesson_{}.txt", lesson.id));
+        let mut text = lesson.to_plain_text("\n\n", true);
+        text.push('\n');
+        fs::write(&path, text).with_context(|| format!("Failed to export: {:?}", path))?;
+        debug!("✅ 导出课程文本: {:?}", path);
+
+        Ok(path)
+    }
+}
+
+/// 递归复制目录中的 .ron 课程文件
+fn copy_lessons_recursive(src: &Path, dest: &Path) -> Result<usize> {
+    let mut count = 0;
+
+    for entry in
+        fs::read_dir(src).with_context(|| format!("Failed to read directory: {:?}", src))?
+    {
+        let entry = entry?;
+        let path = entry.path();
+        let target = dest.join(entry.file_name());
+
+        if path.is_dir() {
+            fs::create_dir_all(&target)?;
+            count += copy_lessons_recursive(&path, &target)?;
+        } else if path.extension().and_then(|s| s.to_str()) == Some("ron") {
+            fs::copy(&path, &target).with_context(|| format!("Failed to export: {:?}", path))?;
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// 由文本生成课程：每个非空行作为一个练习，含中文时按中文课程处理
+pub fn lesson_from_text(id: u32, title: &str, text: &str) -> Option<Lesson> {
+    let exercises: Vec<Exercise> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(Exercise::new)
+        .collect();
+    if exercises.is_empty() {
+        return None;
+    }
+
+    let is_chinese = text.chars().any(is_cjk_char);
+    let language = if is_chinese { "zh-CN" } else { "en-US" };
+
+    Some(imported_lesson(id, title, language, exercises))
+}
+
+/// 读取管道等输入流生成一次性课程（不写入用户数据目录）；输入为空时返回 None
+pub fn lesson_from_reader(id: u32, title: &str, mut reader: impl Read) -> Result<Option<Lesson>> {
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .context("Failed to read lesson text")?;
+    Ok(lesson_from_text(id, title, &text))
+}
+
+/// 由单词表生成课程：单词随机打乱后每 `words_per_exercise` 个组成一个练习（最后一个可能不足）
+pub fn lesson_from_word_list<R: Rng + ?Sized>(
+    id: u32,
+    title: &str,
+    language: &str,
+    text: &str,
+    words_per_exercise: usize,
+    rng: &mut R,
+) -> Option<Lesson> {
+    let mut words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() || words_per_exercise == 0 {
+        return None;
+    }
+    words.shuffle(rng);
+
+    let exercises = words
+        .chunks(words_per_exercise)
+        .map(|chunk| Exercise::new(chunk.join(" ")))
+        .collect();
+    let mut lesson = imported_lesson(id, title, language, exercises);
+    lesson.meta.tags.push("word_list".to_string());
+    Some(lesson)
+}
+
+/// 由多个课程中挑选的练习组成复习课程（`picks` 为课程 ID 和练习索引，找不到的跳过）
+/// 语言取第一个练习所在的课程，其他语言的练习跳过；没有可用练习时返回 None
+pub fn review_lesson(
+    id: u32,
+    title: &str,
+    lessons: &[Lesson],
+    picks: &[(u32, usize)],
+) -> Option<Lesson> {
+    let mut language = None;
+    let exercises: Vec<Exercise> = picks
+        .iter()
+        .filter_map(|&(lesson_id, index)| {
+            let lesson = lessons.iter().find(|lesson| lesson.id == lesson_id)?;
+            let exercise = lesson.exercises.get(index)?;
+            if *language.get_or_insert(lesson.language.as_str()) != lesson.language {
+                return None;
+            }
+            Some(exercise.clone())
+        })
+        .collect();
+    let language = language?;
+
+    let mut lesson = imported_lesson(id, title, language, exercises);
+    lesson.meta.tags = vec!["review".to_string()];
+    Some(lesson)
+}
+
+/// 按生成规则生成一个练习；单词表为空或抽取数为 0 时返回 None
+pub fn generate_exercise<R: Rng + ?Sized>(
+    generator: &ExerciseGenerator,
+    rng: &mut R,
+) -> Option<Exercise> {
+    match generator {
+        ExerciseGenerator::SampleWords { words, count } => {
+            if words.is_empty() || *count == 0 {
+                return None;
+            }
+            let sample: Vec<&str> = words
+                .choose_multiple(rng, *count)
+                .map(String::as_str)
+                .collect();
+            Some(Exercise::new(sample.join(" ")))
+        }
+    }
+}
+
+/// 按课程的重新生成规则重新生成所有练习的内容（未设置规则时不变）
+pub fn regenerate_lesson<R: Rng + ?Sized>(lesson: &mut Lesson, rng: &mut R) {
+    let Some(generator) = &lesson.regenerate_on_restart else {
+        return;
+    };
+    for exercise in &mut lesson.exercises {
+        if let Some(generated) = generate_exercise(generator, rng) {
+            exercise.content = generated.content;
+        }
+    }
+}
+
+/// 导入课程的公共部分（中文语言按中文课程处理）
+fn imported_lesson(id: u32, title: &str, language: &str, exercises: Vec<Exercise>) -> Lesson {
+    let lesson_type = if language.starts_with("zh") {
+        LessonType::Chinese
+    } else {
+        LessonType::Prose
+    };
+
+    Lesson {
+        id,
+        lesson_type,
+        language: language.to_string(),
+        title: title.to_string(),
+        description: String::new(),
+        meta: LessonMeta {
+            difficulty: Difficulty::Intermediate,
+            tags: vec!["imported".to_string()],
+            estimated_time: Duration::from_secs(exercises.len() as u64 * 30),
+            prerequisite_ids: Vec::new(),
+            target_wpm: None,
+            target_accuracy: None,
+        },
+        exercises,
+        mark_newlines: false,
+        regenerate_on_restart: None,
+    }
 }
 
 #[cfg(test)]
@@ -223,4 +541,195 @@ mod tests {
             .expect("Failed to load embedded lessons");
         assert!(!lessons.is_empty(), "Should load embedded lessons");
     }
+
+    #[test]
+    fn test_lesson_from_text() {
+        let lesson = lesson_from_text(10000, "Imported", "hello world\n\n  second line  \n")
+            .expect("Should build lesson");
+        assert_eq!(lesson.exercises.len(), 2);
+        assert_eq!(lesson.exercises[1].content, "second line");
+        assert_eq!(lesson.language, "en-US");
+
+        let chinese = lesson_from_text(10001, "导入", "你好世界").unwrap();
+        assert_eq!(chinese.lesson_type, LessonType::Chinese);
+
+        assert!(lesson_from_text(10002, "Empty", "  \n\n").is_none());
+    }
+
+    #[test]
+    fn test_lesson_from_reader() {
+        let input = std::io::Cursor::new("first line\nsecond line\n");
+        let lesson = lesson_from_reader(10000, "stdin", input).unwrap().unwrap();
+        assert_eq!(lesson.exercises.len(), 2);
+        assert_eq!(lesson.exercises[0].content, "first line");
+
+        assert!(lesson_from_reader(10000, "stdin", std::io::empty())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_review_lesson() {
+        let lessons = [
+            lesson_from_text(1, "One", "a1\na2\na3").unwrap(),
+            lesson_from_text(2, "Two", "b1\nb2").unwrap(),
+            lesson_from_text(3, "Three", "你好").unwrap(),
+        ];
+        // 不同语言的练习不混在一起
+        let review = review_lesson(
+            99,
+            "Review",
+            &lessons,
+            &[(2, 1), (3, 0), (1, 0), (4, 0), (1, 9)],
+        )
+        .expect("Should build review");
+        let contents: Vec<&str> = review
+            .exercises
+            .iter()
+            .map(|e| e.content.as_str())
+            .collect();
+        assert_eq!(contents, ["b2", "a1"]);
+        assert_eq!(review.meta.tags, ["review"]);
+
+        assert_eq!(review.language, "en-US");
+        assert!(review_lesson(99, "Review", &lessons, &[(4, 0)]).is_none());
+    }
+
+    #[test]
+    fn test_export_all_user_lessons() {
+        let user_dir = tempfile::tempdir().unwrap();
+        let export_dir = tempfile::tempdir().unwrap();
+        let loader = LessonLoader::with_user_data_dir(user_dir.path()).unwrap();
+
+        loader.import_text("First", "hello world").unwrap();
+        loader.import_text("Second", "second lesson").unwrap();
+
+        let count = loader.export_all(export_dir.path()).unwrap();
+        assert_eq!(count, 2);
+
+        let mut exported: Vec<String> = fs::read_dir(export_dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        exported.sort();
+        assert_eq!(exported, vec!["imported_10000.ron", "imported_10001.ron"]);
+    }
+
+    #[test]
+    fn test_export_lesson_text() {
+        let mut lesson = lesson_from_text(10000, "Text", "first\nsecond\nthird").unwrap();
+        lesson.exercises[1].hint = Some("two".to_string());
+        assert_eq!(lesson.to_plain_text(" | ", false), "first | second | third");
+        assert_eq!(
+            lesson.to_plain_text("\n---\n", true),
+            "first\n---\n# two\nsecond\n---\nthird"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let loader = LessonLoader::with_user_data_dir(dir.path().join("lessons")).unwrap();
+        let path = loader.export_lesson_text(&lesson).unwrap();
+        assert_eq!(path, dir.path().join("exports").join("lesson_10000.txt"));
+        assert_eq!(
+            fs::read_to_string(path).unwrap(),
+            "first\n\n# two\nsecond\n\nthird\n"
+        );
+    }
+
+    #[test]
+    fn test_source_dirs_override_in_order() {
+        let user_dir = tempfile::tempdir().unwrap();
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+
+        let write = |dir: &Path, title: &str| {
+            let lesson = lesson_from_text(20000, title, "shared lesson").unwrap();
+            let content =
+                ron::ser::to_string_pretty(&lesson, ron::ser::PrettyConfig::default()).unwrap();
+            fs::write(dir.join("shared.ron"), content).unwrap();
+        };
+        write(first.path(), "First");
+        write(second.path(), "Second");
+
+        let mut loader = LessonLoader::with_user_data_dir(user_dir.path()).unwrap();
+        loader.add_source_dir(first.path());
+        loader.add_source_dir(second.path());
+
+        let (lessons, conflicts) = loader.load_all_with_conflicts().unwrap();
+        let shared = lessons.iter().find(|l| l.id == 20000).unwrap();
+        assert_eq!(shared.title, "Second");
+
+        assert_eq!(
+            conflicts,
+            vec![LessonIdConflict {
+                id: 20000,
+                overridden: LessonSource::Dir(first.path().to_path_buf()),
+                winner: LessonSource::Dir(second.path().to_path_buf()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_import_word_list() {
+        let user_dir = tempfile::tempdir().unwrap();
+        let loader = LessonLoader::with_user_data_dir(user_dir.path()).unwrap();
+
+        // 换行与空格混合分隔
+        let words: Vec<String> = (0..100).map(|i| format!("word{}", i)).collect();
+        let path = user_dir.path().join("top100.txt");
+        fs::write(
+            &path,
+            format!("{}\n{}", words[..50].join("\n"), words[50..].join(" ")),
+        )
+        .unwrap();
+
+        let lesson = loader.import_word_list(&path, "en-US", 20).unwrap();
+        assert_eq!(lesson.exercises.len(), 5);
+        assert_eq!(lesson.title, "top100");
+        assert_eq!(lesson.language, "en-US");
+        assert!(lesson.id >= IMPORTED_LESSON_ID_START);
+
+        let mut imported: Vec<&str> = lesson
+            .exercises
+            .iter()
+            .flat_map(|e| e.content.split(' '))
+            .collect();
+        assert!(lesson
+            .exercises
+            .iter()
+            .all(|e| e.content.split(' ').count() == 20));
+        imported.sort();
+        let mut expected: Vec<&str> = words.iter().map(String::as_str).collect();
+        expected.sort();
+        assert_eq!(imported, expected);
+
+        assert!(loader.import_word_list(&path, "en-US", 0).is_err());
+    }
+
+    #[test]
+    fn test_generate_exercise_samples_words() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let words: Vec<String> = (0..100).map(|i| format!("word{}", i)).collect();
+        let generator = ExerciseGenerator::SampleWords {
+            words: words.clone(),
+            count: 20,
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let first = generate_exercise(&generator, &mut rng).unwrap();
+        let second = generate_exercise(&generator, &mut rng).unwrap();
+        assert_ne!(first.content, second.content);
+        for exercise in [&first, &second] {
+            let sampled: Vec<&str> = exercise.content.split(' ').collect();
+            assert_eq!(sampled.len(), 20);
+            assert!(sampled.iter().all(|w| words.iter().any(|word| word == w)));
+        }
+
+        let empty = ExerciseGenerator::SampleWords {
+            words: Vec::new(),
+            count: 20,
+        };
+        assert!(generate_exercise(&empty, &mut rng).is_none());
+    }
 }
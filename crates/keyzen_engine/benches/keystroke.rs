@@ -0,0 +1,83 @@
+//! 输入热路径基准测试
+//!
+//! 每次迭代新建一个 `TypingSession`，把整段练习文本逐字符送入
+//! `handle_keystroke`（约几千次按键，含少量错误和退格）。
+//! 报告的指标是每次迭代的耗时，并通过 `Throughput::Elements`
+//! 换算成每秒处理的按键数（elem/s），用于发现新功能引入的逐键分配。
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use keyzen_core::*;
+use keyzen_engine::TypingSession;
+use std::time::Duration;
+
+const LATIN_TEXT: &str = "The quick brown fox jumps over the lazy dog. ";
+const CJK_TEXT: &str = "键盘练习很有趣，每天都要练习打字。";
+
+/// 构造一个由重复文本组成的单练习课程
+fn build_lesson(language: &str, lesson_type: LessonType, unit: &str, repeat: usize) -> Lesson {
+    Lesson {
+        id: 1,
+        lesson_type,
+        language: language.to_string(),
+        title: "Bench".to_string(),
+        description: "Benchmark lesson".to_string(),
+        exercises: vec![Exercise::new(unit.repeat(repeat))],
+        meta: LessonMeta {
+            difficulty: Difficulty::Beginner,
+            tags: vec![],
+            estimated_time: Duration::from_secs(60),
+            prerequisite_ids: vec![],
+            target_wpm: None,
+            target_accuracy: None,
+        },
+        mark_newlines: false,
+        regenerate_on_restart: None,
+    }
+}
+
+/// 生成按键序列：每 50 个字符插入一次错误 + 退格
+fn build_keystrokes(text: &str) -> Vec<char> {
+    let mut keys = Vec::new();
+    for (i, ch) in text.chars().enumerate() {
+        if i % 50 == 49 {
+            keys.push('#');
+            keys.push('\u{0008}');
+        }
+        keys.push(ch);
+    }
+    keys
+}
+
+fn bench_keystrokes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("handle_keystroke");
+
+    let cases = [
+        (
+            "latin",
+            build_lesson("en-US", LessonType::Prose, LATIN_TEXT, 100),
+        ),
+        (
+            "cjk",
+            build_lesson("zh-CN", LessonType::Chinese, CJK_TEXT, 200),
+        ),
+    ];
+
+    for (name, lesson) in cases {
+        let keys = build_keystrokes(&lesson.exercises[0].content);
+        group.throughput(Throughput::Elements(keys.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &keys, |b, keys| {
+            b.iter(|| {
+                let mut session = TypingSession::new(lesson.clone(), PracticeMode::Zen, None);
+                for &ch in keys {
+                    session.handle_keystroke(black_box(ch));
+                }
+                black_box(session.get_snapshot())
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_keystrokes);
+criterion_main!(benches);
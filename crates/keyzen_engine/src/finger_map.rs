@@ -0,0 +1,51 @@
+//! 字符到手指的映射（用于按手指统计错误）
+
+use crate::keyboard::{KeyId, KeyboardLayout};
+use keyzen_core::Finger;
+
+/// 标准指法下字符键每一列由哪根手指负责（左小指 … 右小指）
+const COLUMN_FINGERS: [Finger; 13] = [
+    Finger::LeftPinky,
+    Finger::LeftRing,
+    Finger::LeftMiddle,
+    Finger::LeftIndex,
+    Finger::LeftIndex,
+    Finger::RightIndex,
+    Finger::RightIndex,
+    Finger::RightMiddle,
+    Finger::RightRing,
+    Finger::RightPinky,
+    Finger::RightPinky,
+    Finger::RightPinky,
+    Finger::RightPinky,
+];
+
+/// 查找输入字母时使用的手指（非字母或该布局上无法输入时返回 None）
+pub fn finger_for(layout: KeyboardLayout, ch: char) -> Option<Finger> {
+    if !ch.is_ascii_alphabetic() {
+        return None;
+    }
+    match layout {
+        KeyboardLayout::Qwerty => match layout.locate(ch)?.key {
+            KeyId::Char { col, .. } => COLUMN_FINGERS.get(col).copied(),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qwerty_fingers() {
+        let layout = KeyboardLayout::Qwerty;
+        assert_eq!(finger_for(layout, 'a'), Some(Finger::LeftPinky));
+        assert_eq!(finger_for(layout, 'T'), Some(Finger::LeftIndex));
+        assert_eq!(finger_for(layout, 'h'), Some(Finger::RightIndex));
+        assert_eq!(finger_for(layout, 'p'), Some(Finger::RightPinky));
+        assert_eq!(finger_for(layout, ';'), None);
+        assert_eq!(finger_for(layout, ' '), None);
+        assert_eq!(finger_for(layout, '你'), None);
+    }
+}
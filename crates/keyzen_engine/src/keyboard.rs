@@ -0,0 +1,182 @@
+//! 键盘布局数据（供屏幕键盘高亮等功能复用）
+
+use keyzen_core::is_cjk_char;
+
+/// 键盘布局
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyboardLayout {
+    #[default]
+    Qwerty,
+}
+
+/// 键帽：未按 Shift / 按下 Shift 时输出的字符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyCap {
+    pub normal: char,
+    pub shifted: char,
+}
+
+const fn key(normal: char, shifted: char) -> KeyCap {
+    KeyCap { normal, shifted }
+}
+
+/// QWERTY 字符键（从数字行到底行，不含 Tab/Enter/Shift/空格等功能键）
+const QWERTY_ROWS: [&[KeyCap]; 4] = [
+    &[
+        key('`', '~'),
+        key('1', '!'),
+        key('2', '@'),
+        key('3', '#'),
+        key('4', '$'),
+        key('5', '%'),
+        key('6', '^'),
+        key('7', '&'),
+        key('8', '*'),
+        key('9', '('),
+        key('0', ')'),
+        key('-', '_'),
+        key('=', '+'),
+    ],
+    &[
+        key('q', 'Q'),
+        key('w', 'W'),
+        key('e', 'E'),
+        key('r', 'R'),
+        key('t', 'T'),
+        key('y', 'Y'),
+        key('u', 'U'),
+        key('i', 'I'),
+        key('o', 'O'),
+        key('p', 'P'),
+        key('[', '{'),
+        key(']', '}'),
+        key('\\', '|'),
+    ],
+    &[
+        key('a', 'A'),
+        key('s', 'S'),
+        key('d', 'D'),
+        key('f', 'F'),
+        key('g', 'G'),
+        key('h', 'H'),
+        key('j', 'J'),
+        key('k', 'K'),
+        key('l', 'L'),
+        key(';', ':'),
+        key('\'', '"'),
+    ],
+    &[
+        key('z', 'Z'),
+        key('x', 'X'),
+        key('c', 'C'),
+        key('v', 'V'),
+        key('b', 'B'),
+        key('n', 'N'),
+        key('m', 'M'),
+        key(',', '<'),
+        key('.', '>'),
+        key('/', '?'),
+    ],
+];
+
+/// 按键标识
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyId {
+    /// 字符键（行、列索引，对应 `KeyboardLayout::rows`）
+    Char {
+        row: usize,
+        col: usize,
+    },
+    Space,
+    Enter,
+    Tab,
+}
+
+/// 输入某个字符需要按下的按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPress {
+    pub key: KeyId,
+    /// 是否需要同时按住 Shift
+    pub shift: bool,
+}
+
+impl KeyboardLayout {
+    /// 获取字符键的各行
+    pub fn rows(&self) -> &'static [&'static [KeyCap]] {
+        match self {
+            Self::Qwerty => &QWERTY_ROWS,
+        }
+    }
+
+    /// 查找输入字符所需的按键（无法在该布局上直接输入时返回 None）
+    pub fn locate(&self, ch: char) -> Option<KeyPress> {
+        let special = match ch {
+            ' ' => Some(KeyId::Space),
+            '\n' => Some(KeyId::Enter),
+            '\t' => Some(KeyId::Tab),
+            _ => None,
+        };
+        if let Some(key) = special {
+            return Some(KeyPress { key, shift: false });
+        }
+
+        self.rows().iter().enumerate().find_map(|(row, keys)| {
+            keys.iter().enumerate().find_map(|(col, cap)| {
+                let key = KeyId::Char { row, col };
+                if cap.normal == ch {
+                    Some(KeyPress { key, shift: false })
+                } else if cap.shifted == ch {
+                    Some(KeyPress { key, shift: true })
+                } else {
+                    None
+                }
+            })
+        })
+    }
+
+    /// 能否在该布局上直接输入该字符
+    pub fn can_type(&self, ch: char) -> bool {
+        self.locate(ch).is_some()
+    }
+}
+
+/// 是否为可通过系统输入法输入的字符（中文汉字及全角标点）
+pub fn is_ime_char(ch: char) -> bool {
+    is_cjk_char(ch)
+        || matches!(ch,
+            '\u{3000}'..='\u{303F}' |  // CJK 符号和标点
+            '\u{FF00}'..='\u{FFEF}' |  // 全角字符
+            '\u{2018}'..='\u{201D}' |  // 中文引号
+            '\u{2014}' | '\u{2026}' |   // 破折号、省略号
+            '\u{00B7}'                  // 间隔号
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_letters_and_shift() {
+        let layout = KeyboardLayout::Qwerty;
+
+        let lower = layout.locate('a').unwrap();
+        let upper = layout.locate('A').unwrap();
+        assert_eq!(lower.key, KeyId::Char { row: 2, col: 0 });
+        assert!(!lower.shift);
+        assert_eq!(upper.key, lower.key);
+        assert!(upper.shift);
+
+        let bang = layout.locate('!').unwrap();
+        assert_eq!(bang.key, layout.locate('1').unwrap().key);
+        assert!(bang.shift);
+    }
+
+    #[test]
+    fn test_locate_special_and_unknown() {
+        let layout = KeyboardLayout::Qwerty;
+        assert_eq!(layout.locate(' ').unwrap().key, KeyId::Space);
+        assert_eq!(layout.locate('\n').unwrap().key, KeyId::Enter);
+        assert_eq!(layout.locate('你'), None);
+    }
+}
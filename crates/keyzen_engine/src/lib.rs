@@ -1,34 +1,176 @@
+pub mod finger_map;
+pub mod keyboard;
+pub mod sample_buffer;
+
+use keyboard::KeyboardLayout;
 use keyzen_core::*;
 use log::debug;
+use sample_buffer::SampleBuffer;
+use serde::Serialize;
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::Range;
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// 窗口准确率统计的按键数
+const ACCURACY_WINDOW: usize = 10;
+/// 准确率序列最多保留的采样数
+const ACCURACY_SERIES_CAPACITY: usize = 200;
+/// 实时 WPM 序列的默认采样容量
+pub const DEFAULT_WPM_SERIES_CAPACITY: usize = 300;
+/// 计算节奏稳定度至少需要的按键间隔数
+const CONSISTENCY_MIN_INTERVALS: usize = 10;
+/// 超过该时长的按键间隔视为停顿，不计入节奏稳定度
+const CONSISTENCY_MAX_INTERVAL: Duration = Duration::from_secs(2);
+/// 默认实时 WPM 统计窗口
+pub const DEFAULT_WPM_WINDOW: Duration = Duration::from_secs(10);
+/// 默认停顿阈值：按键间隔超过该值的部分不计入用时
+pub const DEFAULT_DEAD_TIME_THRESHOLD: Duration = Duration::from_secs(3);
+/// 峰值（爆发）WPM 的滑动窗口
+const PEAK_WPM_WINDOW: Duration = Duration::from_secs(5);
+/// 短于该跨度的窗口不计入峰值 WPM（避免开头几次按键的虚高）
+const PEAK_WPM_MIN_SPAN: Duration = Duration::from_millis(500);
+/// 超过该时长的相邻按键视为停顿，不计入双字母延迟
+const BIGRAM_MAX_GAP: Duration = Duration::from_secs(2);
+/// 会话统计中保留的最慢双字母数
+const SLOW_BIGRAM_COUNT: usize = 10;
+/// 心流状态要求的最低窗口准确率
+const FLOW_MIN_ACCURACY: f64 = 0.95;
+/// 删除到单词开头的控制字符（Ctrl+Backspace）
+pub const DELETE_WORD: char = '\u{0017}';
+/// 字符字面量中转义序列的最大长度（`\u{10FFFF}`）
+const CHAR_ESCAPE_MAX_LEN: usize = 10;
 
 #[cfg(feature = "persistence")]
 use keyzen_persistence::Database;
 
+/// 回放中的一次按键（需开启 replay feature）
+#[cfg(feature = "replay")]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ReplayEvent {
+    pub offset: Duration, // 距会话首次按键的时长（不含暂停）
+    pub ch: char,         // 按下的字符（退格为 \u{8}）
+    pub correct: bool,    // 是否输入正确（退格、未凑齐的字素簇为 false）
+    pub exercise: usize,  // 按键所在练习在课程中的索引
+    pub position: usize,  // 按键所在练习在练习顺序中的位置
+    pub completed: usize, // 按键时已完成的练习数（含渐进隐藏的重复和无限模式的循环）
+    pub restarts: usize,  // 按键前手动跳转或重来练习的次数
+}
+
 pub struct TypingSession {
     // 课程数据
     lesson: Lesson,
     mode: PracticeMode,
     input_mode: InputMode,
-    language: String, // 课程语言，用于统计计算
+    strict_recovery: StrictRecovery,
+    strict_blocked: bool,    // 严格模式任意键重试：输错后等待一次按键解除阻塞
+    allow_backspace: bool,   // 关闭时退格被忽略（硬核模式）
+    strict_completion: bool, // 最后一个字符必须输对才算完成（不受输入模式影响）
+    perfect_practice: bool,  // 完美练习：练习有错时自动重来，直到无错完成
+    tab_stop: Option<usize>, // Tab 跳到下一个制表位（目标以空格缩进时），None 时 Tab 只匹配 \t
+    whitespace_override: Option<WhitespacePolicy>, // 覆盖课程类型默认的空格匹配方式
+    word_boundary_override: Option<WordBoundary>, // 覆盖课程类型默认的单词边界
+    keyboard_layout: KeyboardLayout, // 按手指统计错误时使用的键盘布局
+    attempt: usize,          // 当前练习的第几次尝试（从 1 开始）
+    auto_advance: AutoAdvance,
+    timer_start: TimerStart,
+    shown: bool,             // 当前练习是否已展示（OnShow 模式下展示后才接受输入）
+    lesson_finished: bool,   // 手动模式下最后一个练习完成后已确认结束
+    session_completed: bool, // 已发送 SessionCompleted（每个会话只发送一次）
+    language: String,        // 课程语言，用于统计计算
 
     // 新增：练习进度管理
-    current_exercise_index: usize,      // 当前练习索引 (0-based)
+    current_exercise_index: usize, // 当前练习索引 (0-based，指向 exercise_order)
+    exercise_order: Vec<usize>,    // 练习顺序（课程中的练习索引）
+    loops: usize,                  // 无限模式下已完整循环的次数
     exercise_stats: Vec<ExerciseStats>, // 已完成练习的统计
+    completed_inputs: Vec<(usize, String)>, // 已完成练习的最终输入（课程练习索引, 输入文本）
+    char_counts: HashMap<char, u64>, // 整个会话中每个字符的按键次数
+    bigram_latency: HashMap<(char, char), (Duration, u32)>, // 拉丁字母相邻正确按键的总延迟和次数
 
     // 当前练习的输入状态
-    target_chars: Vec<char>,
-    input_chars: Vec<char>,
+    // 位置均按字素簇计（组合附加符号、emoji 序列算一个位置）
+    target_graphemes: Vec<String>,
+    target_chars: Vec<char>, // 每个字素簇的首字符，用于按字符判断的逻辑
+    input_mask: Vec<bool>,   // 每个位置是否需要输入（完形填空时上下文字符为 false）
+    input_graphemes: Vec<String>,
+    pending_cluster: String, // 多字符字素簇已输入的前缀（凑齐后作为一次按键判定）
     current_position: usize,
     error_positions: HashSet<usize>,
+    missed_positions: HashSet<usize>, // 当前练习中曾经输错过的位置（纠正后也保留）
+    error_categories: HashMap<ErrorCategory, usize>, // 当前练习按分类统计的错误按键
+    capitalization_errors: usize,     // 当前练习中大小写错误的按键（不影响 error_positions）
 
     // 当前练习的统计数据
     exercise_start_time: Option<Instant>,
+    exercise_end_time: Option<Instant>, // 当前练习完成的时刻（完成后用时不再增长）
+    last_keystroke_time: Option<Instant>,
     total_keystrokes: usize,
     correct_keystrokes: usize,
+    excluded_from_wpm: usize, // 当前练习中不计入速度的正确按键（CJK 只计汉字时）
     keystroke_history: VecDeque<(Instant, char, bool)>,
+    wpm_window: Duration,                // 实时 WPM 统计最近多长时间的按键
+    cjk_net_wpm: bool,                   // CJK 课程的速度只计汉字
+    weak_unit_config: WeakUnitConfig,    // 薄弱单元的筛选条件
+    recent_results: VecDeque<bool>,      // 最近按键是否正确（窗口准确率）
+    accuracy_series: VecDeque<f64>,      // 每次按键后的窗口准确率（整个会话）
+    session_start_time: Option<Instant>, // 整个会话的首次按键时间
+    wpm_series: SampleBuffer,            // 实时 WPM 曲线（会话秒数, WPM）
+    flow_accuracy_series: SampleBuffer,  // 与 wpm_series 同步采样的窗口准确率（会话秒数, 准确率）
+    peak_wpm: f64,                       // 整个会话 5 秒爆发 WPM 的最高值
+    exercise_peak_wpm: f64,              // 当前练习 5 秒爆发 WPM 的最高值
+
+    // 节奏练习：相对目标速度的偏差累计（整个会话）
+    pace_target_wpm: Option<f64>,
+    pace_deviation_sum: f64,
+    pace_deviation_abs_sum: f64,
+    pace_samples: usize,
+
+    // 节奏稳定度：按键间隔的均值和方差累计（整个会话，Welford 算法）
+    interval_count: usize,
+    interval_mean: f64,
+    interval_m2: f64,
+    keystroke_intervals: Vec<Duration>, // 同一批按键间隔，用于计算百分位
+
+    #[cfg(feature = "replay")]
+    replay: Vec<ReplayEvent>, // 整个会话的按键回放
+    #[cfg(feature = "replay")]
+    replay_restarts: usize, // 手动跳转或重来练习的次数，回放据此重置练习
+    #[cfg(feature = "replay")]
+    replay_stale: bool, // 重来时重新生成了已有按键的练习内容，回放无法还原
+
+    // 超过该时长没有按键视为暂停（None 不检测）
+    idle_threshold: Option<Duration>,
+
+    // 手动暂停：暂停开始时间和当前练习累计的暂停时长（不计入用时）
+    paused_at: Option<Instant>,
+    paused_duration: Duration,
+
+    // 停顿：按键间隔超过阈值的部分不计入 WPM 用时（None 不扣除）
+    dead_time_threshold: Option<Duration>,
+    dead_time: Duration, // 当前练习累计的停顿时长
+
+    // 限时挑战：从首次按键开始计时，到时后不再接受输入
+    time_limit: Option<Duration>,
+    time_up: bool,
+
+    // 短于该时长的会话不保存（误触开始的练习）
+    min_session_duration: Duration,
+
+    // 渐进隐藏：以不低于阈值的准确率完成后，提高该练习的隐藏级别并重复练习
+    memory_ramp_threshold: Option<f64>,
+    ramp_levels: HashMap<usize, MemoryMode>, // 课程练习索引 -> 当前隐藏级别
+    ramp_repeats: usize,                     // 因渐进隐藏重复练习的次数（不计入进度）
+    memory_seed: u64, // 部分隐藏的随机种子（每个练习重新生成，保证渲染间隐藏位置不变）
+
+    // 渲染快照缓存（输入或切换练习时失效）
+    snapshot_cache: RefCell<Option<SessionSnapshot>>,
+    // 显示文本缓存（按实际使用的记忆模式；切换练习、记忆模式或重新随机时失效）
+    memory_mode: MemoryMode,
+    display_cache: RefCell<Option<(MemoryMode, String)>>,
 
     // 事件发布
     event_tx: Option<mpsc::Sender<TypingEvent>>,
@@ -46,41 +188,684 @@ impl TypingSession {
             "Lesson must have at least one exercise"
         );
         let first_exercise = &lesson.exercises[0];
-        let target_chars: Vec<char> = first_exercise.content.chars().collect();
+        let (target_graphemes, target_chars, input_mask) = split_target(first_exercise);
         let language = lesson.language.clone();
+        let exercise_order = (0..lesson.exercises.len()).collect();
 
-        Self {
+        let mut session = Self {
             lesson,
             mode,
             input_mode: InputMode::default(),
+            strict_recovery: StrictRecovery::default(),
+            strict_blocked: false,
+            allow_backspace: true,
+            strict_completion: false,
+            perfect_practice: false,
+            tab_stop: None,
+            whitespace_override: None,
+            word_boundary_override: None,
+            keyboard_layout: KeyboardLayout::default(),
+            attempt: 1,
+            auto_advance: AutoAdvance::default(),
+            timer_start: TimerStart::default(),
+            shown: false,
+            lesson_finished: false,
+            session_completed: false,
             language,
             current_exercise_index: 0,
+            exercise_order,
+            loops: 0,
             exercise_stats: Vec::new(),
+            completed_inputs: Vec::new(),
+            char_counts: HashMap::new(),
+            bigram_latency: HashMap::new(),
+            interval_count: 0,
+            interval_mean: 0.0,
+            interval_m2: 0.0,
+            keystroke_intervals: Vec::new(),
+            #[cfg(feature = "replay")]
+            replay: Vec::new(),
+            #[cfg(feature = "replay")]
+            replay_restarts: 0,
+            #[cfg(feature = "replay")]
+            replay_stale: false,
+            target_graphemes,
             target_chars,
-            input_chars: Vec::new(),
+            input_mask,
+            input_graphemes: Vec::new(),
+            pending_cluster: String::new(),
             current_position: 0,
             error_positions: HashSet::new(),
+            missed_positions: HashSet::new(),
+            error_categories: HashMap::new(),
+            capitalization_errors: 0,
             exercise_start_time: None,
+            exercise_end_time: None,
+            last_keystroke_time: None,
             total_keystrokes: 0,
             correct_keystrokes: 0,
+            excluded_from_wpm: 0,
             keystroke_history: VecDeque::new(),
+            wpm_window: DEFAULT_WPM_WINDOW,
+            cjk_net_wpm: false,
+            weak_unit_config: WeakUnitConfig::default(),
+            recent_results: VecDeque::new(),
+            accuracy_series: VecDeque::new(),
+            session_start_time: None,
+            wpm_series: SampleBuffer::new(DEFAULT_WPM_SERIES_CAPACITY),
+            flow_accuracy_series: SampleBuffer::new(DEFAULT_WPM_SERIES_CAPACITY),
+            peak_wpm: 0.0,
+            exercise_peak_wpm: 0.0,
+            pace_target_wpm: None,
+            pace_deviation_sum: 0.0,
+            pace_deviation_abs_sum: 0.0,
+            pace_samples: 0,
+            idle_threshold: None,
+            paused_at: None,
+            paused_duration: Duration::ZERO,
+            dead_time_threshold: Some(DEFAULT_DEAD_TIME_THRESHOLD),
+            dead_time: Duration::ZERO,
+            time_limit: None,
+            time_up: false,
+            min_session_duration: Duration::ZERO,
+            memory_ramp_threshold: None,
+            ramp_levels: HashMap::new(),
+            ramp_repeats: 0,
+            memory_seed: rand::random(),
+            snapshot_cache: RefCell::new(None),
+            memory_mode: MemoryMode::Off,
+            display_cache: RefCell::new(None),
             event_tx,
-        }
+        };
+        session.skip_context_chars();
+        session
     }
 
     /// 获取当前练习
     pub fn get_current_exercise(&self) -> &Exercise {
-        &self.lesson.exercises[self.current_exercise_index]
+        &self.lesson.exercises[self.current_lesson_exercise_index()]
+    }
+
+    /// 当前练习在课程中的索引（考虑练习顺序）
+    pub fn current_lesson_exercise_index(&self) -> usize {
+        self.exercise_order[self.current_exercise_index]
+    }
+
+    /// 设置练习顺序（课程中的练习索引列表），并从头开始
+    /// 无效索引会被忽略；结果为空时保持原顺序
+    pub fn set_exercise_order(&mut self, order: Vec<usize>) {
+        let total = self.lesson.exercises.len();
+        let order: Vec<usize> = order.into_iter().filter(|&i| i < total).collect();
+        if order.is_empty() {
+            return;
+        }
+
+        self.exercise_order = order;
+        self.current_exercise_index = 0;
+        self.exercise_stats.clear();
+        self.completed_inputs.clear();
+        self.char_counts.clear();
+        self.bigram_latency.clear();
+        self.interval_count = 0;
+        self.interval_mean = 0.0;
+        self.interval_m2 = 0.0;
+        self.keystroke_intervals.clear();
+        #[cfg(feature = "replay")]
+        {
+            self.replay.clear();
+            self.replay_restarts = 0;
+            self.replay_stale = false;
+        }
+        self.peak_wpm = 0.0;
+        self.ramp_repeats = 0;
+        self.lesson_finished = false;
+        self.session_completed = false;
+        self.reset_pace_deviation();
+        self.reset_for_current_exercise();
+    }
+
+    /// 跳过已掌握的练习（课程中的练习索引），并从头开始
+    /// 全部已掌握时保持原顺序；返回是否跳过了练习
+    pub fn skip_mastered_exercises(&mut self, mastered: &HashSet<usize>) -> bool {
+        let order: Vec<usize> = self
+            .exercise_order
+            .iter()
+            .copied()
+            .filter(|i| !mastered.contains(i))
+            .collect();
+        if order.is_empty() || order.len() == self.exercise_order.len() {
+            return false;
+        }
+
+        self.set_exercise_order(order);
+        true
+    }
+
+    /// 设置输入模式
+    pub fn set_input_mode(&mut self, input_mode: InputMode) {
+        self.input_mode = input_mode;
+    }
+
+    pub fn get_input_mode(&self) -> InputMode {
+        self.input_mode
+    }
+
+    /// 设置严格模式下输错后的恢复方式
+    pub fn set_strict_recovery(&mut self, recovery: StrictRecovery) {
+        self.strict_recovery = recovery;
+        self.strict_blocked = false;
+    }
+
+    pub fn strict_recovery(&self) -> StrictRecovery {
+        self.strict_recovery
+    }
+
+    /// 当前练习实际使用的输入模式（练习未指定时使用会话设置）
+    pub fn current_input_mode(&self) -> InputMode {
+        self.get_current_exercise()
+            .input_mode
+            .unwrap_or(self.input_mode)
+    }
+
+    /// 设置是否允许退格（关闭后错误无法纠正，建议配合宽容或隐形模式）
+    pub fn set_allow_backspace(&mut self, allow: bool) {
+        self.allow_backspace = allow;
+    }
+
+    pub fn allows_backspace(&self) -> bool {
+        self.allow_backspace
+    }
+
+    /// 设置严格完成：宽容和隐形模式下最后一个字符输错时停在原位，直到输对
+    pub fn set_strict_completion(&mut self, strict: bool) {
+        self.strict_completion = strict;
+    }
+
+    pub fn is_strict_completion(&self) -> bool {
+        self.strict_completion
+    }
+
+    /// 设置完美练习：有错完成时自动重来当前练习，直到无错完成
+    pub fn set_perfect_practice(&mut self, enabled: bool) {
+        self.perfect_practice = enabled;
+    }
+
+    pub fn is_perfect_practice(&self) -> bool {
+        self.perfect_practice
+    }
+
+    /// 当前练习的第几次尝试（从 1 开始）
+    pub fn current_attempt(&self) -> usize {
+        self.attempt
+    }
+
+    /// 设置完成练习后的跳转方式
+    pub fn set_auto_advance(&mut self, auto_advance: AutoAdvance) {
+        self.auto_advance = auto_advance;
+    }
+
+    pub fn get_auto_advance(&self) -> AutoAdvance {
+        self.auto_advance
+    }
+
+    /// 设置按手指统计错误时使用的键盘布局
+    pub fn set_keyboard_layout(&mut self, layout: KeyboardLayout) {
+        self.keyboard_layout = layout;
+    }
+
+    pub fn keyboard_layout(&self) -> KeyboardLayout {
+        self.keyboard_layout
+    }
+
+    /// 设置制表位宽度：Tab 键越过目标中的空格直到下一个制表位（None 关闭）
+    pub fn set_tab_stop(&mut self, width: Option<usize>) {
+        self.tab_stop = width.filter(|&w| w > 0);
+    }
+
+    pub fn tab_stop(&self) -> Option<usize> {
+        self.tab_stop
+    }
+
+    /// Tab 键按制表位应越过的空格数（不适用时为 0）
+    fn tab_stop_run(&self, ch: char) -> usize {
+        let Some(width) = self.tab_stop.filter(|_| ch == '\t') else {
+            return 0;
+        };
+
+        let (_, col) = self.cursor_line_col();
+        let max_run = width - col % width;
+        (self.current_position..self.target_chars.len())
+            .take(max_run)
+            .take_while(|&i| self.target_chars[i] == ' ' && self.input_mask[i])
+            .count()
+    }
+
+    /// 全局覆盖空格匹配方式（None 使用课程类型的默认策略）
+    pub fn set_whitespace_policy(&mut self, policy: Option<WhitespacePolicy>) {
+        self.whitespace_override = policy;
+    }
+
+    /// 当前生效的空格匹配方式
+    pub fn whitespace_policy(&self) -> WhitespacePolicy {
+        self.whitespace_override
+            .unwrap_or_else(|| WhitespacePolicy::for_lesson_type(self.lesson.lesson_type))
+    }
+
+    /// 全局覆盖 WordCompleted 事件的单词边界（None 使用课程类型的默认边界）
+    pub fn set_word_boundary(&mut self, boundary: Option<WordBoundary>) {
+        self.word_boundary_override = boundary;
+    }
+
+    /// 当前生效的单词边界
+    pub fn word_boundary(&self) -> WordBoundary {
+        self.word_boundary_override
+            .unwrap_or_else(|| WordBoundary::for_lesson_type(self.lesson.lesson_type))
+    }
+
+    /// 宽松空格：在目标的连续空格中已输入至少一个空格后，直接输入空格之后的字符时越过剩余的空格
+    /// 按原样输入全部空格同样正确
+    fn skip_collapsed_spaces(&mut self, input: &str) {
+        let position = self.current_position;
+        let in_space_run = self.whitespace_policy() == WhitespacePolicy::Collapse
+            && input != " "
+            && self.target_chars.get(position) == Some(&' ')
+            && position > 0
+            && self.target_chars[position - 1] == ' '
+            && self
+                .input_graphemes
+                .get(position - 1)
+                .is_some_and(|g| g == " ");
+        if !in_space_run {
+            return;
+        }
+
+        let run_end = (position..self.target_chars.len())
+            .find(|&i| self.target_chars[i] != ' ' || !self.input_mask[i])
+            .unwrap_or(self.target_chars.len());
+        let next_matches = self
+            .target_graphemes
+            .get(run_end)
+            .is_some_and(|target| self.graphemes_match(target, input));
+        if !next_matches {
+            return;
+        }
+
+        for i in position..run_end {
+            self.input_graphemes.push(self.target_graphemes[i].clone());
+            self.error_positions.remove(&i);
+        }
+        self.current_position = run_end;
+    }
+
+    /// 设置练习计时的起点
+    pub fn set_timer_start(&mut self, timer_start: TimerStart) {
+        self.timer_start = timer_start;
+    }
+
+    pub fn get_timer_start(&self) -> TimerStart {
+        self.timer_start
+    }
+
+    /// 标记当前练习已展示给用户；OnShow 模式下从此时开始计时
+    pub fn mark_shown(&mut self) {
+        if self.shown {
+            return;
+        }
+        self.shown = true;
+        if self.timer_start == TimerStart::OnShow && self.exercise_start_time.is_none() {
+            self.exercise_start_time = Some(Instant::now());
+        }
+    }
+
+    /// OnShow 模式下当前练习尚未展示（等待用户开始）
+    pub fn is_awaiting_start(&self) -> bool {
+        self.timer_start == TimerStart::OnShow && !self.shown
+    }
+
+    /// 当前练习完成后是否应自动跳转（自动模式、无错完成且还有下一个练习或需要重复）
+    pub fn should_auto_advance(&self) -> bool {
+        self.auto_advance == AutoAdvance::OnPerfect
+            && self.is_current_exercise_complete()
+            && !self.current_exercise_has_errors()
+            && self.will_continue()
+    }
+
+    /// 当前练习之后是否还有练习（下一个练习、渐进隐藏重复，或无限模式循环）
+    pub fn will_continue(&self) -> bool {
+        self.has_next_exercise() || self.ramp_will_repeat() || self.mode == PracticeMode::Endless
+    }
+
+    /// 无限模式下已完整循环课程的次数
+    pub fn total_loops(&self) -> usize {
+        self.loops
+    }
+
+    /// 手动继续：当前练习完成后进入下一个练习，最后一个练习则结束课程
+    /// 当前练习未完成时返回 false
+    pub fn advance_or_finish(&mut self) -> bool {
+        if !self.is_current_exercise_complete() || self.lesson_finished {
+            return false;
+        }
+
+        if self.will_continue() {
+            self.advance_to_next_exercise();
+        } else {
+            self.lesson_finished = true;
+            self.complete_session();
+        }
+        true
+    }
+
+    /// 手动模式下课程是否已确认结束
+    pub fn is_lesson_finished(&self) -> bool {
+        self.lesson_finished
+    }
+
+    /// 首次正确率：已到达的字符中第一次就输对的比例
+    /// 与按键准确率不同，输错后纠正的字符不计为正确；练习完成时分母即为需要输入的字符数
+    pub fn first_try_accuracy(&self) -> f64 {
+        let reached: Vec<usize> = (0..self.current_position.min(self.target_chars.len()))
+            .filter(|&i| self.input_mask[i])
+            .collect();
+        if reached.is_empty() {
+            return 1.0;
+        }
+
+        let first_try = reached
+            .iter()
+            .filter(|i| !self.missed_positions.contains(i))
+            .count();
+        first_try as f64 / reached.len() as f64
+    }
+
+    /// 设置节奏练习的目标速度（None 关闭），并清空已累计的偏差
+    pub fn set_pace_target_wpm(&mut self, target_wpm: Option<f64>) {
+        self.pace_target_wpm = target_wpm.filter(|wpm| *wpm > 0.0);
+        self.reset_pace_deviation();
+        self.invalidate_snapshot();
+    }
+
+    fn reset_pace_deviation(&mut self) {
+        self.pace_deviation_sum = 0.0;
+        self.pace_deviation_abs_sum = 0.0;
+        self.pace_samples = 0;
+    }
+
+    /// 相对目标速度的平均节奏偏差（未设置目标速度或尚无按键时返回 None）
+    pub fn pace_deviation(&self) -> Option<PaceDeviation> {
+        if self.pace_target_wpm.is_none() || self.pace_samples == 0 {
+            return None;
+        }
+
+        let samples = self.pace_samples as f64;
+        Some(PaceDeviation {
+            mean: self.pace_deviation_sum / samples,
+            mean_abs: self.pace_deviation_abs_sum / samples,
+        })
+    }
+
+    /// 记录一次节奏偏差采样（实际位置 - 目标节奏位置）
+    fn sample_pace_deviation(&mut self) {
+        let (Some(target_wpm), Some(elapsed)) = (self.pace_target_wpm, self.exercise_elapsed())
+        else {
+            return;
+        };
+
+        let expected = self
+            .expected_position_for_wpm(target_wpm, elapsed)
+            .min(self.target_chars.len() as f64);
+        let deviation = self.current_position as f64 - expected;
+        self.pace_deviation_sum += deviation;
+        self.pace_deviation_abs_sum += deviation.abs();
+        self.pace_samples += 1;
+    }
+
+    /// 设置渐进隐藏的准确率阈值（None 关闭）
+    /// 开启后练习从不隐藏开始，每次达到阈值就提高隐藏级别并重复该练习，直到完全隐藏
+    pub fn set_memory_ramp(&mut self, threshold: Option<f64>) {
+        self.memory_ramp_threshold = threshold;
+        self.ramp_levels.clear();
+        self.invalidate_snapshot();
+    }
+
+    /// 当前练习的渐进隐藏级别（未开启时返回 None）
+    pub fn ramp_level(&self) -> Option<MemoryMode> {
+        self.memory_ramp_threshold?;
+        Some(
+            self.ramp_levels
+                .get(&self.current_lesson_exercise_index())
+                .copied()
+                .unwrap_or(MemoryMode::Off),
+        )
+    }
+
+    /// 当前练习完成后是否会因渐进隐藏而重复（达到阈值且尚未完全隐藏）
+    pub fn ramp_will_repeat(&self) -> bool {
+        let (Some(threshold), Some(level)) = (self.memory_ramp_threshold, self.ramp_level()) else {
+            return false;
+        };
+        self.is_current_exercise_complete()
+            && next_ramp_level(level).is_some()
+            && self.finalize_current_exercise().accuracy >= threshold
+    }
+
+    /// 设置暂停检测的时长（None 关闭）
+    pub fn set_idle_threshold(&mut self, threshold: Option<Duration>) {
+        self.idle_threshold = threshold.filter(|t| !t.is_zero());
+    }
+
+    pub fn idle_threshold(&self) -> Option<Duration> {
+        self.idle_threshold
+    }
+
+    /// 练习进行中且超过暂停检测时长没有按键（尚未开始、已完成或手动暂停时不算）
+    pub fn is_idle(&self) -> bool {
+        match (self.idle_threshold, self.last_keystroke_time) {
+            (Some(threshold), Some(last)) => {
+                !self.is_current_exercise_complete()
+                    && !self.is_paused()
+                    && last.elapsed() >= threshold
+            }
+            _ => false,
+        }
+    }
+
+    /// 暂停计时，暂停期间忽略按键
+    pub fn pause(&mut self) {
+        self.pause_at(Instant::now());
+    }
+
+    fn pause_at(&mut self, now: Instant) {
+        if self.paused_at.is_none() {
+            debug!("⏸️  暂停练习");
+            self.paused_at = Some(now);
+            self.invalidate_snapshot();
+        }
+    }
+
+    /// 继续计时；暂停时长不计入练习用时，限时挑战的计时同样顺延
+    pub fn resume(&mut self) {
+        self.resume_at(Instant::now());
+    }
+
+    fn resume_at(&mut self, now: Instant) {
+        let Some(paused_at) = self.paused_at.take() else {
+            return;
+        };
+        debug!("▶️  继续练习");
+        let paused = now.duration_since(paused_at);
+        // 只扣除练习进行中的暂停；首次按键前或完成页上的暂停不影响练习用时
+        if self.exercise_start_time.is_some() && self.exercise_end_time.is_none() {
+            self.paused_duration += paused;
+        }
+
+        // 按键时间整体顺延，避免继续后实时 WPM 骤降、节奏统计出现长间隔
+        for (ts, _, _) in self.keystroke_history.iter_mut() {
+            *ts += paused;
+        }
+        if let Some(last) = self.last_keystroke_time.as_mut() {
+            *last += paused;
+        }
+        if let Some(start) = self.session_start_time.as_mut() {
+            *start += paused;
+        }
+        self.invalidate_snapshot();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// 设置停顿阈值（None 不扣除停顿），按键间隔超过阈值的部分不计入 WPM 用时
+    /// 已累计的停顿时长不会按新阈值重新计算
+    pub fn set_dead_time_threshold(&mut self, threshold: Option<Duration>) {
+        self.dead_time_threshold = threshold;
+        self.invalidate_snapshot();
+    }
+
+    pub fn dead_time_threshold(&self) -> Option<Duration> {
+        self.dead_time_threshold
+    }
+
+    /// 一段按键间隔中计为停顿的时长
+    fn dead_time_of(&self, gap: Duration) -> Duration {
+        self.dead_time_threshold
+            .map_or(Duration::ZERO, |threshold| gap.saturating_sub(threshold))
+    }
+
+    /// 当前练习的停顿时长（含最后一次按键之后正在进行的停顿，练习完成后不再增长）
+    fn exercise_dead_time(&self) -> Duration {
+        let end = self.exercise_end_time.unwrap_or_else(|| self.clock());
+        let ongoing = self.last_keystroke_time.map_or(Duration::ZERO, |last| {
+            self.dead_time_of(end.saturating_duration_since(last))
+        });
+        self.dead_time + ongoing
+    }
+
+    /// 计时用的当前时间（暂停期间停在暂停时刻）
+    fn clock(&self) -> Instant {
+        self.paused_at.unwrap_or_else(Instant::now)
+    }
+
+    /// 设置限时挑战的时长（None 不限时），从会话的首次按键开始计时
+    pub fn set_time_limit(&mut self, limit: Option<Duration>) {
+        self.time_limit = limit.filter(|l| !l.is_zero());
+    }
+
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    /// 限时挑战的剩余时间（未限时返回 None，尚未开始时为完整时长）
+    pub fn time_remaining(&self) -> Option<Duration> {
+        let limit = self.time_limit?;
+        Some(match self.session_start_time {
+            Some(start) => limit.saturating_sub(self.clock().duration_since(start)),
+            None => limit,
+        })
+    }
+
+    /// 限时挑战是否已到时
+    pub fn is_time_up(&self) -> bool {
+        self.time_up || self.time_remaining().is_some_and(|t| t.is_zero())
+    }
+
+    /// 检查限时：刚到时时结束会话并发送 SessionCompleted，返回是否已到时
+    /// 到时前已完成全部练习的会话正常结束，不受影响
+    pub fn check_time_limit(&mut self) -> bool {
+        if self.time_up {
+            return true;
+        }
+        if !self.is_time_up() || self.is_lesson_complete() {
+            return false;
+        }
+
+        debug!("⏰ 限时挑战到时");
+        self.time_up = true;
+        self.invalidate_snapshot();
+        self.complete_session();
+        true
+    }
+
+    /// 会话结束时发送 SessionCompleted（附带与 save_to_database 相同的统计），只发送一次
+    fn complete_session(&mut self) {
+        if self.session_completed {
+            return;
+        }
+        self.session_completed = true;
+        debug!("🏁 会话完成");
+        self.send_event(TypingEvent::SessionCompleted {
+            stats: self.finalize_session(),
+        });
+    }
+
+    /// 当前练习完成后会话是否随之结束（无限模式不会结束，手动模式需确认）
+    fn ends_with_current_exercise(&self) -> bool {
+        match (self.mode, self.auto_advance) {
+            (PracticeMode::Endless, _) => false,
+            (_, AutoAdvance::Manual) => self.lesson_finished,
+            _ => self.is_current_exercise_complete() && !self.will_continue(),
+        }
+    }
+
+    /// 所有练习是否都已完成
+    fn is_lesson_complete(&self) -> bool {
+        self.lesson_finished || (self.is_current_exercise_complete() && !self.has_next_exercise())
+    }
+
+    /// 设置最短保存时长：已完成练习的总时长短于该值时 save_to_database 不保存
+    pub fn set_min_session_duration(&mut self, duration: Duration) {
+        self.min_session_duration = duration;
+    }
+
+    pub fn min_session_duration(&self) -> Duration {
+        self.min_session_duration
+    }
+
+    /// 课程中每个练习的状态（按课程练习索引），用于渲染练习总览
+    pub fn exercise_statuses(&self) -> Vec<ExerciseStatus> {
+        let current = self.current_lesson_exercise_index();
+        (0..self.lesson.exercises.len())
+            .map(|index| {
+                if index == current {
+                    // 当前练习已完成但尚未跳转时按完成显示
+                    return if self.is_current_exercise_complete() {
+                        ExerciseStatus::Completed(self.finalize_current_exercise().accuracy)
+                    } else {
+                        ExerciseStatus::Current
+                    };
+                }
+                if let Some(stats) = self
+                    .exercise_stats
+                    .iter()
+                    .rev()
+                    .find(|s| s.exercise_index == index)
+                {
+                    return ExerciseStatus::Completed(stats.accuracy);
+                }
+                match self.exercise_order.iter().position(|&i| i == index) {
+                    Some(position) if position >= self.current_exercise_index => {
+                        ExerciseStatus::NotStarted
+                    }
+                    _ => ExerciseStatus::Skipped,
+                }
+            })
+            .collect()
+    }
+
+    /// 获取练习顺序
+    pub fn get_exercise_order(&self) -> &[usize] {
+        &self.exercise_order
     }
 
     /// 获取进度 (当前索引, 总数)
     pub fn get_progress(&self) -> (usize, usize) {
-        (self.current_exercise_index, self.lesson.exercises.len())
+        (self.current_exercise_index, self.exercise_order.len())
     }
 
     /// 是否还有下一个练习
     pub fn has_next_exercise(&self) -> bool {
-        self.current_exercise_index + 1 < self.lesson.exercises.len()
+        self.current_exercise_index + 1 < self.exercise_order.len()
     }
 
     /// 是否有上一个练习
@@ -88,11 +873,43 @@ impl TypingSession {
         self.current_exercise_index > 0
     }
 
+    /// 跳转到课程中的指定练习（按课程练习索引），不在练习顺序中时返回 false
+    pub fn go_to_exercise(&mut self, lesson_index: usize) -> bool {
+        match self.exercise_order.iter().position(|&i| i == lesson_index) {
+            Some(position) => {
+                self.current_exercise_index = position;
+                self.reset_for_current_exercise();
+                self.record_restart();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 重新开始当前练习（不保存统计）；课程设置了重新生成规则时先生成新的内容
+    pub fn restart_current_exercise(&mut self) {
+        if let Some(generator) = &self.lesson.regenerate_on_restart {
+            if let Some(generated) =
+                keyzen_data::generate_exercise(generator, &mut rand::thread_rng())
+            {
+                let index = self.current_lesson_exercise_index();
+                self.lesson.exercises[index].content = generated.content;
+                #[cfg(feature = "replay")]
+                if self.replay.iter().any(|event| event.exercise == index) {
+                    self.replay_stale = true;
+                }
+            }
+        }
+        self.reset_for_current_exercise();
+        self.record_restart();
+    }
+
     /// 跳转到上一个练习
     pub fn go_to_previous_exercise(&mut self) -> bool {
         if self.has_previous_exercise() {
             self.current_exercise_index -= 1;
             self.reset_for_current_exercise();
+            self.record_restart();
             true
         } else {
             false
@@ -104,6 +921,7 @@ impl TypingSession {
         if self.has_next_exercise() {
             self.current_exercise_index += 1;
             self.reset_for_current_exercise();
+            self.record_restart();
             true
         } else {
             false
@@ -122,9 +940,27 @@ impl TypingSession {
 
     /// 完成当前练习，进入下一个
     pub fn advance_to_next_exercise(&mut self) -> bool {
+        self.invalidate_snapshot();
+
+        let ramp_repeat = self.ramp_will_repeat();
+
         // 1. 生成当前练习的统计
         let stats = self.finalize_current_exercise();
         self.exercise_stats.push(stats);
+        self.completed_inputs
+            .push((self.current_lesson_exercise_index(), self.get_input_text()));
+
+        // 渐进隐藏：达到阈值时提高隐藏级别，重复当前练习
+        if ramp_repeat {
+            if let Some(next_level) = self.ramp_level().and_then(next_ramp_level) {
+                debug!("🙈 渐进隐藏: 提高到 {:?}", next_level);
+                self.ramp_levels
+                    .insert(self.current_lesson_exercise_index(), next_level);
+                self.ramp_repeats += 1;
+                self.reset_for_next_exercise();
+                return true;
+            }
+        }
 
         // 2. 检查是否还有下一个
         if self.has_next_exercise() {
@@ -132,6 +968,13 @@ impl TypingSession {
             self.current_exercise_index += 1;
             self.reset_for_next_exercise();
             true
+        } else if self.mode == PracticeMode::Endless {
+            // 无限模式：回到第一个练习，统计继续累计
+            self.current_exercise_index = 0;
+            self.loops += 1;
+            debug!("🔁 无限模式: 开始第 {} 轮", self.loops + 1);
+            self.reset_for_next_exercise();
+            true
         } else {
             // 所有练习完成
             false
@@ -140,76 +983,178 @@ impl TypingSession {
 
     /// 重置状态以开始下一个练习
     fn reset_for_next_exercise(&mut self) {
-        let exercise = self.get_current_exercise();
-        self.target_chars = exercise.content.chars().collect();
-        self.input_chars.clear();
+        let (target_graphemes, target_chars, input_mask) =
+            split_target(self.get_current_exercise());
+        self.target_graphemes = target_graphemes;
+        self.target_chars = target_chars;
+        self.input_mask = input_mask;
+        self.input_graphemes.clear();
+        self.pending_cluster.clear();
         self.current_position = 0;
         self.error_positions.clear();
+        self.missed_positions.clear();
+        self.error_categories.clear();
+        self.capitalization_errors = 0;
         self.exercise_start_time = None;
+        self.exercise_end_time = None;
+        self.last_keystroke_time = None;
         self.total_keystrokes = 0;
         self.correct_keystrokes = 0;
+        self.excluded_from_wpm = 0;
         self.keystroke_history.clear();
+        self.exercise_peak_wpm = 0.0;
+        self.paused_at = None;
+        self.paused_duration = Duration::ZERO;
+        self.dead_time = Duration::ZERO;
+        self.strict_blocked = false;
+        self.attempt = 1;
+        self.shown = false;
+        self.memory_seed = rand::random();
+        self.display_cache.borrow_mut().take();
+        self.invalidate_snapshot();
+        self.skip_context_chars();
     }
 
     /// 重置当前练习（用于手动跳转练习时）
     pub fn reset_for_current_exercise(&mut self) {
-        let exercise = self.get_current_exercise();
-        self.target_chars = exercise.content.chars().collect();
-        self.input_chars.clear();
+        let (target_graphemes, target_chars, input_mask) =
+            split_target(self.get_current_exercise());
+        self.target_graphemes = target_graphemes;
+        self.target_chars = target_chars;
+        self.input_mask = input_mask;
+        self.input_graphemes.clear();
+        self.pending_cluster.clear();
         self.current_position = 0;
         self.error_positions.clear();
+        self.missed_positions.clear();
+        self.error_categories.clear();
+        self.capitalization_errors = 0;
         self.exercise_start_time = None;
+        self.exercise_end_time = None;
+        self.last_keystroke_time = None;
         self.total_keystrokes = 0;
         self.correct_keystrokes = 0;
+        self.excluded_from_wpm = 0;
         self.keystroke_history.clear();
+        self.exercise_peak_wpm = 0.0;
+        self.paused_at = None;
+        self.paused_duration = Duration::ZERO;
+        self.dead_time = Duration::ZERO;
+        self.strict_blocked = false;
+        self.attempt = 1;
+        self.shown = false;
+        self.memory_seed = rand::random();
+        self.display_cache.borrow_mut().take();
+        self.invalidate_snapshot();
+        self.skip_context_chars();
     }
 
-    /// 根据记忆模式生成显示文本
-    pub fn generate_display_text(&self, mode: MemoryMode) -> String {
-        match mode {
-            MemoryMode::Off => self.get_target_text().to_string(),
-            MemoryMode::Complete => self.hide_complete(),
-            MemoryMode::FirstLetter => self.hide_first_letter_only(),
-            MemoryMode::Partial(level) => self.hide_partial(level),
+    /// 完形填空：跳过光标处不需要输入的上下文字符
+    fn skip_context_chars(&mut self) {
+        while let Some(&false) = self.input_mask.get(self.current_position) {
+            self.input_graphemes
+                .push(self.target_graphemes[self.current_position].clone());
+            self.current_position += 1;
         }
     }
 
-    /// 完全隐藏：保留空格和标点，其他用 _ 替代
-    fn hide_complete(&self) -> String {
-        self.get_target_text()
-            .chars()
-            .map(|ch| {
-                if ch.is_whitespace()
-                    || ch.is_ascii_punctuation()
-                    || "，。！？；：\"\"''（）【】《》、".contains(ch)
-                {
-                    ch
-                } else {
-                    '_'
-                }
-            })
-            .collect()
+    /// 重新随机部分隐藏的位置（同一练习内默认保持不变）
+    pub fn reshuffle_memory_mask(&mut self) {
+        self.memory_seed = rand::random();
+        self.display_cache.borrow_mut().take();
+        self.invalidate_snapshot();
     }
 
-    /// 首字母提示模式
-    fn hide_first_letter_only(&self) -> String {
-        let text = self.get_target_text();
-
-        if self.is_cjk_language() {
-            // 中文：每个词显示第一个字
-            self.hide_chinese_first_char(text)
-        } else {
-            // 英文：每个单词只显示首字母
-            self.hide_english_first_letter(text)
+    /// 设置界面选择的记忆模式（清除显示文本缓存）
+    pub fn set_memory_mode(&mut self, mode: MemoryMode) {
+        if self.memory_mode != mode {
+            self.memory_mode = mode;
+            self.display_cache.borrow_mut().take();
         }
     }
 
-    /// 部分隐藏模式
-    fn hide_partial(&self, level: PartialLevel) -> String {
-        let ratio = match level {
-            PartialLevel::Low => 0.3,
-            PartialLevel::Medium => 0.5,
-            PartialLevel::High => 0.7,
+    pub fn memory_mode(&self) -> MemoryMode {
+        self.memory_mode
+    }
+
+    /// 根据记忆模式生成显示文本
+    /// 练习指定了记忆模式时优先使用；开启渐进隐藏时忽略 `mode`，使用当前练习的隐藏级别
+    /// 完形填空练习始终显示上下文、隐藏挖空单词，不受记忆模式影响
+    /// 同一练习内以相同模式重复调用时返回缓存
+    pub fn generate_display_text(&self, mode: MemoryMode) -> String {
+        // 开启渐进隐藏时使用当前练习的隐藏级别
+        let mode = self
+            .get_current_exercise()
+            .memory_mode
+            .or(self.ramp_level())
+            .unwrap_or(mode);
+
+        if let Some((cached_mode, text)) = self.display_cache.borrow().as_ref() {
+            if *cached_mode == mode {
+                return text.clone();
+            }
+        }
+
+        let text = if self.get_current_exercise().is_cloze() {
+            self.hide_cloze_words()
+        } else {
+            match mode {
+                MemoryMode::Off => self.get_target_text().to_string(),
+                MemoryMode::Complete => self.hide_complete(),
+                MemoryMode::FirstLetter => self.hide_first_letter_only(),
+                MemoryMode::Partial(level) => self.hide_partial(level),
+            }
+        };
+        *self.display_cache.borrow_mut() = Some((mode, text.clone()));
+        text
+    }
+
+    /// 完形填空：挖空单词用 _ 替代
+    fn hide_cloze_words(&self) -> String {
+        self.target_graphemes
+            .iter()
+            .zip(&self.input_mask)
+            .map(|(grapheme, &required)| if required { "_" } else { grapheme })
+            .collect()
+    }
+
+    /// 按字素簇生成隐藏文本：`hide` 根据位置和首字符判断是否替换为 _
+    /// 保证显示文本与目标文本的字素簇一一对应
+    fn mask_graphemes(&self, hide: impl Fn(usize, char) -> bool) -> String {
+        self.target_graphemes
+            .iter()
+            .zip(&self.target_chars)
+            .enumerate()
+            .map(|(i, (grapheme, &ch))| if hide(i, ch) { "_" } else { grapheme })
+            .collect()
+    }
+
+    /// 完全隐藏：保留空格和标点，其他用 _ 替代
+    fn hide_complete(&self) -> String {
+        self.mask_graphemes(|_, ch| {
+            !(ch.is_whitespace()
+                || ch.is_ascii_punctuation()
+                || "，。！？；：\"\"''（）【】《》、".contains(ch))
+        })
+    }
+
+    /// 首字母提示模式
+    fn hide_first_letter_only(&self) -> String {
+        if self.is_cjk_language() {
+            // 中文：每个词显示第一个字
+            self.hide_chinese_first_char()
+        } else {
+            // 英文：每个单词只显示首字母
+            self.hide_english_first_letter()
+        }
+    }
+
+    /// 部分隐藏模式
+    fn hide_partial(&self, level: PartialLevel) -> String {
+        let ratio = match level {
+            PartialLevel::Low => 0.3,
+            PartialLevel::Medium => 0.5,
+            PartialLevel::High => 0.7,
         };
 
         if self.is_cjk_language() {
@@ -223,17 +1168,16 @@ impl TypingSession {
 
     /// 隐藏中文字符
     fn hide_chinese_chars(&self, ratio: f32) -> String {
+        use rand::rngs::StdRng;
         use rand::seq::SliceRandom;
-        use rand::thread_rng;
-
-        let text = self.get_target_text();
-        let chars: Vec<char> = text.chars().collect();
+        use rand::SeedableRng;
 
         // 找出所有中文字符的索引
-        let cjk_indices: Vec<usize> = chars
+        let cjk_indices: Vec<usize> = self
+            .target_chars
             .iter()
             .enumerate()
-            .filter(|(_, &ch)| self.is_cjk_char(ch))
+            .filter(|(_, &ch)| is_cjk_char(ch))
             .map(|(i, _)| i)
             .collect();
 
@@ -241,31 +1185,27 @@ impl TypingSession {
         let hide_count = (cjk_indices.len() as f32 * ratio).round() as usize;
 
         // 随机选择要隐藏的索引
-        let mut rng = thread_rng();
+        let mut rng = StdRng::seed_from_u64(self.memory_seed);
         let mut hide_indices: Vec<usize> = cjk_indices;
         hide_indices.shuffle(&mut rng);
         let hide_set: HashSet<usize> = hide_indices.into_iter().take(hide_count).collect();
 
         // 生成隐藏后的文本
-        chars
-            .iter()
-            .enumerate()
-            .map(|(i, &ch)| if hide_set.contains(&i) { '_' } else { ch })
-            .collect()
+        self.mask_graphemes(|i, _| hide_set.contains(&i))
     }
 
     /// 隐藏英文单词
     fn hide_english_words(&self, ratio: f32) -> String {
+        use rand::rngs::StdRng;
         use rand::seq::SliceRandom;
-        use rand::thread_rng;
+        use rand::SeedableRng;
 
-        let text = self.get_target_text();
         let mut words = Vec::new();
         let mut in_word = false;
         let mut start_idx = 0;
 
         // 提取所有单词的起始和结束位置
-        for (i, ch) in text.chars().enumerate() {
+        for (i, &ch) in self.target_chars.iter().enumerate() {
             if ch.is_alphanumeric() {
                 if !in_word {
                     in_word = true;
@@ -279,133 +1219,197 @@ impl TypingSession {
             }
         }
         if in_word {
-            words.push((start_idx, text.len()));
+            words.push((start_idx, self.target_chars.len()));
         }
 
         // 随机选择要隐藏的单词
         let hide_count = (words.len() as f32 * ratio).round() as usize;
-        let mut rng = thread_rng();
+        let mut rng = StdRng::seed_from_u64(self.memory_seed);
         words.shuffle(&mut rng);
         let hide_words: HashSet<(usize, usize)> = words.into_iter().take(hide_count).collect();
 
         // 生成隐藏后的文本
-        let chars: Vec<char> = text.chars().collect();
-        let mut result = String::new();
-
-        for (i, ch) in chars.iter().enumerate() {
-            let should_hide = hide_words.iter().any(|&(start, end)| i >= start && i < end);
-            if should_hide && ch.is_alphanumeric() {
-                result.push('_');
-            } else {
-                result.push(*ch);
-            }
-        }
-        result
+        self.mask_graphemes(|i, ch| {
+            ch.is_alphanumeric() && hide_words.iter().any(|&(start, end)| i >= start && i < end)
+        })
     }
 
     /// 英文首字母提示
-    fn hide_english_first_letter(&self, text: &str) -> String {
-        let mut result = String::new();
-        let mut in_word = false;
-        let mut is_first = false;
-
-        for ch in text.chars() {
-            if ch.is_alphanumeric() {
-                if !in_word {
-                    in_word = true;
-                    is_first = true;
-                }
-                if is_first {
-                    result.push(ch);
-                    is_first = false;
-                } else {
-                    result.push('_');
-                }
-            } else {
-                in_word = false;
-                result.push(ch);
-            }
-        }
-        result
+    fn hide_english_first_letter(&self) -> String {
+        // 单词中首字母之后的字母数字隐藏
+        self.mask_graphemes(|i, ch| {
+            ch.is_alphanumeric() && i > 0 && self.target_chars[i - 1].is_alphanumeric()
+        })
     }
 
     /// 中文首字提示：每个词显示第一个字
-    fn hide_chinese_first_char(&self, text: &str) -> String {
-        let mut result = String::new();
+    fn hide_chinese_first_char(&self) -> String {
+        let mut hidden = vec![false; self.target_chars.len()];
         let mut show_next = true;
 
-        for ch in text.chars() {
-            if self.is_cjk_char(ch) {
-                if show_next {
-                    result.push(ch);
-                    show_next = false;
-                } else {
-                    result.push('_');
-                }
+        for (i, &ch) in self.target_chars.iter().enumerate() {
+            if is_cjk_char(ch) {
+                hidden[i] = !show_next;
+                show_next = false;
             } else {
-                result.push(ch);
                 show_next = !ch.is_alphanumeric(); // 遇到标点/空格后重置
             }
         }
-        result
-    }
-
-    /// 判断是否为 CJK 字符
-    fn is_cjk_char(&self, ch: char) -> bool {
-        matches!(ch,
-            '\u{4E00}'..='\u{9FFF}' |  // CJK 统一表意文字
-            '\u{3400}'..='\u{4DBF}' |  // CJK 扩展 A
-            '\u{20000}'..='\u{2A6DF}' | // CJK 扩展 B
-            '\u{2A700}'..='\u{2B73F}' | // CJK 扩展 C
-            '\u{2B740}'..='\u{2B81F}' | // CJK 扩展 D
-            '\u{2B820}'..='\u{2CEAF}' | // CJK 扩展 E
-            '\u{F900}'..='\u{FAFF}' |   // CJK 兼容表意文字
-            '\u{2F800}'..='\u{2FA1F}'   // CJK 兼容表意文字补充
-        )
+        self.mask_graphemes(|i, _| hidden[i])
     }
 
     /// 核心方法：处理按键
     pub fn handle_keystroke(&mut self, ch: char) {
+        self.handle_keystroke_at(ch, Instant::now());
+    }
+
+    /// 处理在 `now` 时刻的按键
+    fn handle_keystroke_at(&mut self, ch: char, now: Instant) {
         debug!(
             "🟢 Engine::handle_keystroke 收到字符: {:?} (U+{:04X})",
             ch, ch as u32
         );
 
+        // 禁止退格时直接忽略，不计入按键
+        if matches!(ch, '\u{0008}' | DELETE_WORD) && !self.allow_backspace {
+            debug!("  ↳ 已禁止退格，忽略");
+            return;
+        }
+
+        // 练习尚未展示时忽略按键，不计入统计
+        if self.is_awaiting_start() {
+            debug!("  ↳ 练习尚未开始，忽略");
+            return;
+        }
+
+        if self.is_paused() {
+            debug!("  ↳ 练习已暂停，忽略");
+            return;
+        }
+
+        // 严格模式任意键重试：输错后的下一次按键只解除阻塞，不计入统计
+        if self.strict_blocked {
+            debug!("  ↳ 解除阻塞，重新输入");
+            self.strict_blocked = false;
+            self.invalidate_snapshot();
+            return;
+        }
+
+        // 限时挑战到时后不再接受输入
+        if self.check_time_limit() {
+            debug!("  ↳ 限时已到，忽略");
+            return;
+        }
+
         // 首次按键启动计时
         if self.exercise_start_time.is_none() {
-            self.exercise_start_time = Some(Instant::now());
+            self.exercise_start_time = Some(now);
+        }
+        self.session_start_time.get_or_insert(now);
+        if let Some(last) = self.last_keystroke_time {
+            self.record_keystroke_interval(now.duration_since(last));
+            self.dead_time += self.dead_time_of(now.duration_since(last));
         }
+        self.last_keystroke_time = Some(now);
 
-        let now = Instant::now();
-        self.total_keystrokes += 1;
+        // 多字符字素簇（组合附加符号、emoji 序列）：凑齐前只缓存，不计入按键
+        if !self.pending_cluster.is_empty() && matches!(ch, '\u{0008}' | DELETE_WORD) {
+            debug!("  ↳ 清除未完成的字素簇");
+            self.pending_cluster.clear();
+            self.record_replay(ch, false, now);
+            return;
+        }
+        let candidate = format!("{}{}", self.pending_cluster, ch);
+        // 按分解形式比较前缀，预组合的目标字符也可以用组合附加符号分步输入
+        let target: String = self
+            .target_graphemes
+            .get(self.current_position)
+            .map_or("", String::as_str)
+            .nfd()
+            .collect();
+        let decomposed: String = candidate.nfd().collect();
+        if decomposed.len() < target.len() && target.starts_with(&decomposed) {
+            debug!("  ↳ 字素簇未完成，等待后续字符");
+            self.pending_cluster = candidate;
+            self.record_replay(ch, false, now);
+            return;
+        }
+        self.pending_cluster.clear();
 
         // 所有语言使用系统输入法，直接处理字符
-        self.handle_char_input(ch, now);
+        self.handle_char_input(&candidate, now);
+        self.sample_pace_deviation();
     }
 
-    /// 处理字符输入
-    fn handle_char_input(&mut self, ch: char, now: Instant) {
+    /// 处理一个字素簇的输入（按字符判断的逻辑使用其首字符）
+    fn handle_char_input(&mut self, input: &str, now: Instant) {
+        self.invalidate_snapshot();
+        let Some(ch) = input.chars().next() else {
+            return;
+        };
+
         // 处理退格键
         if ch == '\u{0008}' {
             debug!("  ↳ 处理退格键");
+            self.record_replay(ch, false, now);
             self.handle_backspace();
             return;
         }
+        if ch == DELETE_WORD {
+            debug!("  ↳ 删除到单词开头");
+            self.record_replay(ch, false, now);
+            self.handle_word_delete();
+            return;
+        }
+
+        // 退格和删除单词不计入按键数（纠错不应拉低准确率）
+        self.total_keystrokes += 1;
+        let was_complete = self.is_current_exercise_complete();
+        *self.char_counts.entry(ch).or_insert(0) += 1;
+        // 多字符字素簇的前缀已在缓存时记录，这里只记录最后一个字符
+        let last_char = input.chars().last().unwrap_or(ch);
+
+        self.skip_collapsed_spaces(input);
 
         // 检查是否正确
-        let target_char = self.target_chars.get(self.current_position);
-        let is_correct = target_char == Some(&ch);
+        let target_char = self.target_chars.get(self.current_position).copied();
+        // 制表位模式下 Tab 一次越过到下一个制表位的空格
+        let tab_run = self.tab_stop_run(ch);
+        let matched = if tab_run > 0 {
+            target_char
+        } else {
+            target_char.filter(|_| {
+                let target = &self.target_graphemes[self.current_position];
+                self.graphemes_match(target, input)
+            })
+        };
+        let is_correct = matched.is_some();
+        // 一次按键越过的目标字符数
+        let run = tab_run.max(1);
 
         debug!(
             "  ↳ 位置 {}: 目标={:?}, 输入={:?}, 正确={}",
-            self.current_position, target_char, ch, is_correct
+            self.current_position,
+            self.target_graphemes.get(self.current_position),
+            input,
+            is_correct
         );
 
-        if is_correct {
-            self.correct_keystrokes += 1;
-            self.input_chars.push(ch);
-            self.error_positions.remove(&self.current_position);
-            self.current_position += 1;
+        if let Some(target) = matched {
+            // 制表位跳转按越过的字符数计入按键数和速度，与逐个输入这些空格一致
+            self.total_keystrokes += run - 1;
+            self.correct_keystrokes += run;
+            if !self.counts_for_wpm(target) {
+                self.excluded_from_wpm += run;
+            }
+            self.record_bigram_latency(target, now);
+            // 记录目标字素簇
+            for _ in 0..run {
+                self.input_graphemes
+                    .push(self.target_graphemes[self.current_position].clone());
+                self.error_positions.remove(&self.current_position);
+                self.current_position += 1;
+            }
 
             // 发送事件
             self.send_event(TypingEvent::KeyPressed {
@@ -413,29 +1417,49 @@ impl TypingSession {
                 correct: true,
                 position: self.current_position - 1,
             });
+            self.skip_context_chars();
 
             // 检查是否完成单词
-            if ch == ' ' || ch == '\n' {
+            if self.word_boundary().ends_word(target) {
                 let wpm = self.calculate_current_wpm();
                 self.send_event(TypingEvent::WordCompleted { wpm });
             }
         } else {
+            // 错误分类
+            let category = self.categorize_error(target_char, ch);
+            *self.error_categories.entry(category).or_insert(0) += 1;
+            if target_char.is_some_and(|target| is_case_mismatch(target, ch)) {
+                self.capitalization_errors += 1;
+            }
+            if target_char.is_some() {
+                self.missed_positions.insert(self.current_position);
+            }
+
+            // 严格完成：最后一个字符输错时不前进
+            let hold_final =
+                self.strict_completion && self.current_position + 1 == self.target_chars.len();
+
             // 错误处理
-            match self.input_mode {
+            match self.current_input_mode() {
                 InputMode::Strict => {
                     // 严格模式：不允许继续
                     self.error_positions.insert(self.current_position);
+                    self.strict_blocked = self.strict_recovery == StrictRecovery::AnyKey;
                 }
                 InputMode::Forgiving => {
                     // 宽容模式：标记但继续
                     self.error_positions.insert(self.current_position);
-                    self.input_chars.push(ch);
-                    self.current_position += 1;
+                    if !hold_final {
+                        self.input_graphemes.push(input.to_string());
+                        self.current_position += 1;
+                    }
                 }
                 InputMode::Invisible => {
                     // 隐形模式：不显示错误
-                    self.input_chars.push(ch);
-                    self.current_position += 1;
+                    if !hold_final {
+                        self.input_graphemes.push(input.to_string());
+                        self.current_position += 1;
+                    }
                 }
             }
 
@@ -448,14 +1472,48 @@ impl TypingSession {
                     0
                 },
             });
+            self.skip_context_chars();
         }
 
         // 记录历史（用于 WPM 计算）
-        self.keystroke_history.push_back((now, ch, is_correct));
+        for _ in 0..run {
+            self.keystroke_history.push_back((now, ch, is_correct));
+        }
+        self.record_replay(last_char, is_correct, now);
+        if is_correct {
+            self.update_peak_wpm(now);
+        }
+        self.record_accuracy_sample(is_correct);
+        self.record_wpm_sample(now);
+
+        // 完美练习：有错完成时重来当前练习
+        if !was_complete
+            && self.is_current_exercise_complete()
+            && self.perfect_practice
+            && !self.missed_positions.is_empty()
+        {
+            let attempt = self.attempt + 1;
+            debug!("🔁 完美练习: 有错误，开始第 {} 次尝试", attempt);
+            self.reset_for_current_exercise();
+            self.attempt = attempt;
+        }
+
+        // 当前练习刚完成时通知前端，最后一个练习完成时会话随之结束
+        if !was_complete && self.is_current_exercise_complete() {
+            self.exercise_end_time = Some(now);
+            self.send_event(TypingEvent::ExerciseCompleted {
+                index: self.current_lesson_exercise_index(),
+                stats: self.finalize_current_exercise(),
+            });
+            if self.ends_with_current_exercise() {
+                self.complete_session();
+            }
+        }
 
-        // 只保留最近 10 秒的历史
+        // 只保留实时 WPM 和峰值 WPM 窗口内的历史
+        let keep = self.wpm_window.max(PEAK_WPM_WINDOW);
         while let Some((ts, _, _)) = self.keystroke_history.front() {
-            if now.duration_since(*ts) > Duration::from_secs(10) {
+            if now.duration_since(*ts) > keep {
                 self.keystroke_history.pop_front();
             } else {
                 break;
@@ -463,304 +1521,659 @@ impl TypingSession {
         }
     }
 
-    fn handle_backspace(&mut self) {
-        if self.current_position > 0 {
-            self.current_position -= 1;
-            self.input_chars.pop();
+    /// 记录窗口准确率采样（最近 ACCURACY_WINDOW 次按键）
+    fn record_accuracy_sample(&mut self, is_correct: bool) {
+        self.recent_results.push_back(is_correct);
+        if self.recent_results.len() > ACCURACY_WINDOW {
+            self.recent_results.pop_front();
+        }
 
-            if self.error_positions.remove(&self.current_position) {
-                self.send_event(TypingEvent::ErrorCorrected {
-                    position: self.current_position,
-                });
-            }
+        let correct = self.recent_results.iter().filter(|&&c| c).count();
+        self.accuracy_series
+            .push_back(correct as f64 / self.recent_results.len() as f64);
+        if self.accuracy_series.len() > ACCURACY_SERIES_CAPACITY {
+            self.accuracy_series.pop_front();
         }
     }
 
-    /// 计算当前 WPM（基于最近 10 秒）
-    fn calculate_current_wpm(&self) -> f64 {
-        if self.keystroke_history.is_empty() {
-            return 0.0;
-        }
+    /// 记录一次实时 WPM 采样（时间为会话开始后的秒数）
+    fn record_wpm_sample(&mut self, now: Instant) {
+        let start = *self.session_start_time.get_or_insert(now);
+        let wpm = self.wpm_at(now);
+        let secs = now.duration_since(start).as_secs_f64();
+        self.wpm_series.push(secs, wpm);
+        let accuracy = self.accuracy_series.back().copied().unwrap_or(1.0);
+        self.flow_accuracy_series.push(secs, accuracy);
+    }
 
-        let now = Instant::now();
+    /// 以 `now` 结束的 5 秒窗口内的爆发 WPM（窗口跨度不足 0.5 秒时返回 None）
+    fn burst_wpm_at(&self, now: Instant) -> Option<f64> {
         let recent: Vec<_> = self
             .keystroke_history
             .iter()
-            .filter(|(ts, _, correct)| *correct && now.duration_since(*ts).as_secs() <= 10)
+            .filter(|(ts, ch, correct)| {
+                *correct && now.duration_since(*ts) <= PEAK_WPM_WINDOW && self.counts_for_wpm(*ch)
+            })
             .collect();
-
-        if recent.is_empty() {
-            return 0.0;
+        let span = now.duration_since(recent.first()?.0);
+        if span < PEAK_WPM_MIN_SPAN {
+            return None;
         }
 
-        let first_ts = recent.first().unwrap().0;
-        let duration = now.duration_since(first_ts).as_secs_f64();
+        // n 次按键之间只有 n - 1 个间隔
+        let intervals = (recent.len() - 1) as f64;
+        Some(self.cpm_to_wpm(intervals / span.as_secs_f64() * 60.0))
+    }
 
-        if duration < 0.1 {
-            return 0.0;
+    /// 正确按键后更新练习和会话的峰值 WPM
+    fn update_peak_wpm(&mut self, now: Instant) {
+        if let Some(wpm) = self.burst_wpm_at(now) {
+            self.exercise_peak_wpm = self.exercise_peak_wpm.max(wpm);
+            self.peak_wpm = self.peak_wpm.max(wpm);
         }
+    }
 
-        let chars = recent.len() as f64;
-        let cpm = (chars / duration) * 60.0;
+    /// 本次会话最快的 5 秒爆发 WPM
+    pub fn peak_wpm(&self) -> f64 {
+        self.peak_wpm
+    }
 
-        // 根据语言调整 WPM 计算
-        if self.is_cjk_language() {
-            // CJK 语言: 1 个字符 = 1 个"词"
-            cpm
-        } else {
-            // 拉丁字母语言: 平均 5 个字符 = 1 个词
-            cpm / 5.0
+    /// 记录一次按键间隔（停顿不计入）
+    fn record_keystroke_interval(&mut self, interval: Duration) {
+        if interval > CONSISTENCY_MAX_INTERVAL {
+            return;
         }
-    }
 
-    /// 判断是否为 CJK（中日韩）语言
-    fn is_cjk_language(&self) -> bool {
-        self.language.starts_with("zh-") // 中文
-            || self.language.starts_with("ja-") // 日文
-            || self.language.starts_with("ko-") // 韩文
+        let secs = interval.as_secs_f64();
+        self.interval_count += 1;
+        let delta = secs - self.interval_mean;
+        self.interval_mean += delta / self.interval_count as f64;
+        self.interval_m2 += delta * (secs - self.interval_mean);
+        self.keystroke_intervals.push(interval);
     }
 
-    /// 完成当前练习并生成统计
-    fn finalize_current_exercise(&self) -> ExerciseStats {
-        let duration = self
-            .exercise_start_time
-            .map(|t| t.elapsed())
-            .unwrap_or(Duration::ZERO);
-
-        let accuracy = if self.total_keystrokes > 0 {
-            self.correct_keystrokes as f64 / self.total_keystrokes as f64
-        } else {
-            0.0
-        };
+    /// 按键间隔的百分位（每个练习的首次按键和停顿不计入）
+    pub fn latency_profile(&self) -> Option<LatencyProfile> {
+        LatencyProfile::from_intervals(&self.keystroke_intervals)
+    }
 
-        let cpm = if duration.as_secs() > 0 {
-            (self.correct_keystrokes as f64 / duration.as_secs_f64()) * 60.0
-        } else {
-            0.0
-        };
+    /// 记录一次按键到回放（未开启 replay feature 时为空操作）
+    #[cfg_attr(not(feature = "replay"), allow(unused_variables))]
+    fn record_replay(&mut self, ch: char, correct: bool, now: Instant) {
+        #[cfg(feature = "replay")]
+        {
+            let start = self.session_start_time.unwrap_or(now);
+            self.replay.push(ReplayEvent {
+                offset: now.saturating_duration_since(start),
+                ch,
+                correct,
+                exercise: self.current_lesson_exercise_index(),
+                position: self.current_exercise_index,
+                completed: self.exercise_stats.len(),
+                restarts: self.replay_restarts,
+            });
+        }
+    }
 
-        // 根据语言调整 WPM 计算
-        let wpm = if self.is_cjk_language() {
-            cpm
-        } else {
-            cpm / 5.0
-        };
+    /// 记录一次手动跳转或重来练习，回放时在同一位置重置练习
+    fn record_restart(&mut self) {
+        #[cfg(feature = "replay")]
+        {
+            self.replay_restarts += 1;
+        }
+    }
 
-        let exercise = self.get_current_exercise();
-        ExerciseStats::from_exercise(
-            exercise,
-            self.current_exercise_index,
-            wpm,
-            accuracy,
-            self.total_keystrokes,
-            self.error_positions.len(),
-            duration,
-        )
+    /// 回放能否还原本会话（重来时重新生成过已输入的练习内容则不能）
+    #[cfg(feature = "replay")]
+    pub fn is_replayable(&self) -> bool {
+        !self.replay_stale
     }
 
-    /// 完成会话并生成统计（汇总所有练习）
-    fn finalize_session(&self) -> SessionStats {
-        // 构建所有练习的统计（包括已完成和当前的）
-        let mut all_exercise_stats = self.exercise_stats.clone();
+    /// 导出整个会话的按键回放
+    #[cfg(feature = "replay")]
+    pub fn export_replay(&self) -> Vec<ReplayEvent> {
+        self.replay.clone()
+    }
 
-        // 如果当前练习已完成但还没添加到 exercise_stats，添加它
-        if self.is_current_exercise_complete() {
-            let current_stats = self.finalize_current_exercise();
-            all_exercise_stats.push(current_stats);
+    /// 按回放重新输入一遍，重建错误位置和统计
+    /// 需要与录制时相同的课程和设置；暂停不在回放中，重放时不会发生
+    #[cfg(feature = "replay")]
+    pub fn apply_replay(&mut self, events: &[ReplayEvent]) {
+        let Some(last) = events.last() else {
+            return;
+        };
+        let now = Instant::now();
+        let start = now.checked_sub(last.offset).unwrap_or(now);
+        for event in events {
+            if !self.replay_event(event, start) {
+                return;
+            }
         }
+    }
 
-        // 汇总所有练习的数据
-        let total_duration_secs: u64 = all_exercise_stats.iter().map(|s| s.duration_secs).sum();
-        let total_keystrokes: usize = all_exercise_stats.iter().map(|s| s.total_keystrokes).sum();
-        let total_errors: usize = all_exercise_stats.iter().map(|s| s.error_count).sum();
+    /// 重放一次按键（`start` 对应回放偏移 0），需要时先跳到按键所在的练习
+    /// 无法跳到该练习时返回 false
+    #[cfg(feature = "replay")]
+    fn replay_event(&mut self, event: &ReplayEvent, start: Instant) -> bool {
+        if !self.seek_replay_event(event) {
+            return false;
+        }
+        self.handle_keystroke_at(event.ch, start + event.offset);
+        true
+    }
 
-        let overall_accuracy = if total_keystrokes > 0 {
-            (total_keystrokes - total_errors) as f64 / total_keystrokes as f64
-        } else {
-            0.0
-        };
+    /// 跳到按键录制时所在的练习，录制时在按键前跳转或重来过则重置练习
+    #[cfg(feature = "replay")]
+    fn seek_replay_event(&mut self, event: &ReplayEvent) -> bool {
+        // 按录制时的次数完成练习（含渐进隐藏的重复和无限模式的循环）
+        while self.exercise_stats.len() < event.completed {
+            if !self.advance_to_next_exercise() {
+                return false;
+            }
+        }
 
-        let overall_cpm = if total_duration_secs > 0 {
-            ((total_keystrokes - total_errors) as f64 / total_duration_secs as f64) * 60.0
+        // 录制时手动跳转过练习：按练习顺序中的位置跳转，顺序不同时按课程中的索引
+        let position = if self.exercise_order.get(event.position) == Some(&event.exercise) {
+            Some(event.position)
         } else {
-            0.0
+            self.exercise_order
+                .iter()
+                .position(|&i| i == event.exercise)
         };
-
-        let overall_wpm = if self.is_cjk_language() {
-            overall_cpm
-        } else {
-            overall_cpm / 5.0
+        let Some(position) = position else {
+            return false;
         };
+        if position != self.current_exercise_index || event.restarts != self.replay_restarts {
+            self.current_exercise_index = position;
+            self.reset_for_current_exercise();
+            self.replay_restarts = event.restarts;
+        }
+        true
+    }
 
-        // 提取薄弱单元（基于所有练习）
-        let weak_units = self.extract_weak_units();
+    /// 按相同的课程、设置和练习顺序新建一个尚未输入的会话，用于重放本会话的回放
+    #[cfg(feature = "replay")]
+    pub fn replay_session(&self) -> Self {
+        let mut session = Self::new(self.lesson.clone(), self.mode, None);
+        session.input_mode = self.input_mode;
+        session.strict_recovery = self.strict_recovery;
+        session.allow_backspace = self.allow_backspace;
+        session.strict_completion = self.strict_completion;
+        session.perfect_practice = self.perfect_practice;
+        session.tab_stop = self.tab_stop;
+        session.whitespace_override = self.whitespace_override;
+        session.word_boundary_override = self.word_boundary_override;
+        session.keyboard_layout = self.keyboard_layout;
+        session.auto_advance = self.auto_advance;
+        session.wpm_window = self.wpm_window;
+        session.cjk_net_wpm = self.cjk_net_wpm;
+        session.weak_unit_config = self.weak_unit_config;
+        session.pace_target_wpm = self.pace_target_wpm;
+        session.memory_ramp_threshold = self.memory_ramp_threshold;
+        session.memory_mode = self.memory_mode;
+        session.set_exercise_order(self.exercise_order.clone());
+        session
+    }
 
-        SessionStats {
-            lesson_id: self.lesson.id,
-            exercise_stats: all_exercise_stats,
-            overall_wpm,
-            overall_cpm,
-            overall_accuracy,
-            total_keystrokes,
-            error_count: total_errors,
-            duration_secs: total_duration_secs,
-            timestamp: chrono::Utc::now().timestamp(),
-            weak_units,
-        }
+    /// 从回放重建会话（使用默认设置）
+    #[cfg(feature = "replay")]
+    pub fn from_replay(lesson: Lesson, mode: PracticeMode, events: &[ReplayEvent]) -> Self {
+        let mut session = Self::new(lesson, mode, None);
+        session.apply_replay(events);
+        session
     }
 
-    /// 根据课程语言类型提取薄弱单元
-    fn extract_weak_units(&self) -> Vec<WeakUnit> {
-        match self.language.as_str() {
-            lang if lang.starts_with("zh-") => self.extract_chinese_weak_units(),
-            lang if lang.starts_with("en-") => self.extract_english_weak_units(),
-            "rust" | "python" | "javascript" => self.extract_code_weak_units(),
-            _ => self.extract_character_weak_units(), // 默认字符级别
+    /// 节奏稳定度：1 - 按键间隔的变异系数（0-1，按键间隔越均匀越高）
+    /// 按键间隔少于 CONSISTENCY_MIN_INTERVALS 时返回 None
+    pub fn consistency(&self) -> Option<f64> {
+        if self.interval_count < CONSISTENCY_MIN_INTERVALS || self.interval_mean <= 0.0 {
+            return None;
         }
+
+        let std_dev = (self.interval_m2 / self.interval_count as f64).sqrt();
+        Some((1.0 - std_dev / self.interval_mean).clamp(0.0, 1.0))
     }
 
-    /// 中文：提取单字符（汉字）和常见双字词组
-    fn extract_chinese_weak_units(&self) -> Vec<WeakUnit> {
-        let mut unit_stats: HashMap<String, (usize, usize, UnitType)> = HashMap::new();
+    /// 设置实时 WPM 序列的采样容量（会清空已有采样）
+    pub fn set_wpm_series_capacity(&mut self, capacity: usize) {
+        self.wpm_series = SampleBuffer::new(capacity);
+        self.flow_accuracy_series = SampleBuffer::new(capacity);
+    }
 
-        // 1. 单字符统计
-        for (i, &target_char) in self.target_chars.iter().enumerate() {
-            let key = target_char.to_string();
-            let entry = unit_stats.entry(key).or_insert((0, 0, UnitType::Character));
-            entry.0 += 1; // 总次数
-            if self.error_positions.contains(&i) {
-                entry.1 += 1; // 错误次数
-            }
-        }
+    /// 整个会话的实时 WPM 曲线（会话秒数, WPM），长会话中旧采样会被合并而不是丢弃
+    pub fn get_wpm_series(&self) -> &[(f64, f64)] {
+        self.wpm_series.samples()
+    }
 
-        // 2. 双字词组统计（可选）
-        for i in 0..self.target_chars.len().saturating_sub(1) {
-            let c1 = self.target_chars[i];
-            let c2 = self.target_chars[i + 1];
+    /// 本次会话最长的心流时长（WPM 持续高于会话平均且准确率保持较高的连续时段）
+    pub fn flow_duration(&self) -> Duration {
+        longest_flow(
+            self.wpm_series.samples(),
+            self.flow_accuracy_series.samples(),
+        )
+    }
 
-            // 只统计双汉字组合
-            if c1.is_ascii() || c2.is_ascii() || c1.is_whitespace() || c2.is_whitespace() {
-                continue;
-            }
+    /// 获取最近 `len` 个窗口准确率采样（从旧到新，用于准确率趋势图）
+    pub fn get_recent_accuracy_series(&self, len: usize) -> Vec<f64> {
+        let skip = self.accuracy_series.len().saturating_sub(len);
+        self.accuracy_series.iter().skip(skip).copied().collect()
+    }
 
-            let phrase = format!("{}{}", c1, c2);
-            let has_error =
-                self.error_positions.contains(&i) || self.error_positions.contains(&(i + 1));
+    /// 判断错误类型：句首处字母正确但大小写错误算作大小写错误
+    fn categorize_error(&self, target: Option<char>, input: char) -> ErrorCategory {
+        let case_mismatch = target.is_some_and(|t| is_case_mismatch(t, input));
 
-            let entry = unit_stats.entry(phrase).or_insert((0, 0, UnitType::Phrase));
-            entry.0 += 1;
-            if has_error {
-                entry.1 += 1;
-            }
+        if case_mismatch && self.is_sentence_start(self.current_position) {
+            ErrorCategory::Capitalization
+        } else {
+            ErrorCategory::Other
         }
-
-        self.build_weak_units_from_stats(unit_stats)
     }
 
-    /// 英文：提取单词级别
-    fn extract_english_weak_units(&self) -> Vec<WeakUnit> {
-        let mut unit_stats: HashMap<String, (usize, usize, UnitType)> = HashMap::new();
+    /// 位置是否为句首（文本开头，或句末标点后的第一个非空白字符）
+    fn is_sentence_start(&self, position: usize) -> bool {
+        self.target_chars[..position]
+            .iter()
+            .rev()
+            .find(|ch| !ch.is_whitespace())
+            .is_none_or(|&ch| matches!(ch, '.' | '!' | '?'))
+    }
 
-        // 分词逻辑
-        let target_text = self.target_chars.iter().collect::<String>();
-        let words: Vec<&str> = target_text.split_whitespace().collect();
+    /// 判断输入的字素簇是否匹配目标字素簇（按 NFC 规范化后比较，换行与空格不可互换）
+    fn graphemes_match(&self, target: &str, input: &str) -> bool {
+        target == input || target.nfc().eq(input.nfc())
+    }
 
-        let mut char_offset = 0;
-        for word in words {
-            let word_start = char_offset;
-            let word_end = char_offset + word.len();
+    fn handle_backspace(&mut self) {
+        self.invalidate_snapshot();
+        self.exercise_end_time = None;
 
-            // 检查该单词是否有错误
-            let has_error = (word_start..word_end).any(|i| self.error_positions.contains(&i));
+        // 完形填空：先退过上下文字符，再删除上一个输入的字符
+        while self.current_position > 0 && !self.input_mask[self.current_position - 1] {
+            self.current_position -= 1;
+            self.input_graphemes.pop();
+        }
 
-            let entry = unit_stats
-                .entry(word.to_string())
-                .or_insert((0, 0, UnitType::Word));
-            entry.0 += 1;
-            if has_error {
-                entry.1 += 1;
-            }
+        if self.current_position > 0 {
+            self.current_position -= 1;
+            self.input_graphemes.pop();
 
-            // 跳过单词和后面的空格
-            char_offset = word_end;
-            // 查找下一个非空白字符的位置
-            while char_offset < self.target_chars.len()
-                && self.target_chars[char_offset].is_whitespace()
-            {
-                char_offset += 1;
+            if self.error_positions.remove(&self.current_position) {
+                self.send_event(TypingEvent::ErrorCorrected {
+                    position: self.current_position,
+                });
             }
         }
 
-        // 同时也统计字符级别（用于特殊字符和标点）
-        for (i, &target_char) in self.target_chars.iter().enumerate() {
-            // 只统计非字母数字的字符
-            if !target_char.is_alphanumeric() && !target_char.is_whitespace() {
-                let key = target_char.to_string();
-                let entry = unit_stats.entry(key).or_insert((0, 0, UnitType::Character));
-                entry.0 += 1;
-                if self.error_positions.contains(&i) {
-                    entry.1 += 1;
-                }
+        // 已退到开头时重新跳过开头的上下文
+        self.skip_context_chars();
+    }
+
+    /// 删除到当前单词开头（先退过光标前的空白，再退到前一个空白之后）
+    /// 光标前是没有空格分隔的 CJK 文本时只删除一个字符
+    fn handle_word_delete(&mut self) {
+        let cjk_before = self
+            .current_position
+            .checked_sub(1)
+            .is_some_and(|pos| is_cjk_char(self.target_chars[pos]));
+        if cjk_before {
+            self.handle_backspace();
+            return;
+        }
+
+        let mut stop = self.current_position;
+        while stop > 0 && self.target_chars[stop - 1].is_whitespace() {
+            stop -= 1;
+        }
+        while stop > 0 && !self.target_chars[stop - 1].is_whitespace() {
+            stop -= 1;
+        }
+
+        // 逐个退格，每个纠正的位置都会发送 ErrorCorrected
+        while self.current_position > stop {
+            let before = self.current_position;
+            self.handle_backspace();
+            // 开头的上下文会被重新跳过，不再后退时停止
+            if self.current_position >= before {
+                break;
             }
         }
+    }
 
-        self.build_weak_units_from_stats(unit_stats)
+    /// 设置薄弱单元的筛选条件（短课程可降低最少出现次数）
+    pub fn set_weak_unit_config(&mut self, config: WeakUnitConfig) {
+        self.weak_unit_config = config;
     }
 
-    /// 代码：提取字符级别（可扩展为 token 级别）
-    fn extract_code_weak_units(&self) -> Vec<WeakUnit> {
-        // 暂时使用字符级别，后续可扩展为 token 级别
-        self.extract_character_weak_units()
+    pub fn weak_unit_config(&self) -> WeakUnitConfig {
+        self.weak_unit_config
     }
 
-    /// 默认：字符级别统计
-    fn extract_character_weak_units(&self) -> Vec<WeakUnit> {
-        let mut unit_stats: HashMap<String, (usize, usize, UnitType)> = HashMap::new();
+    /// 设置实时 WPM 的统计窗口（越短越快反映速度变化，也越不稳定）
+    pub fn set_wpm_window(&mut self, window: Duration) {
+        self.wpm_window = window;
+        self.invalidate_snapshot();
+    }
 
-        for (i, &target_char) in self.target_chars.iter().enumerate() {
-            let key = target_char.to_string();
-            let entry = unit_stats.entry(key).or_insert((0, 0, UnitType::Character));
-            entry.0 += 1;
-            if self.error_positions.contains(&i) {
-                entry.1 += 1;
+    pub fn wpm_window(&self) -> Duration {
+        self.wpm_window
+    }
+
+    /// 计算当前 WPM（基于统计窗口内的按键）
+    fn calculate_current_wpm(&self) -> f64 {
+        self.wpm_at(self.clock())
+    }
+
+    /// 统计窗口内正确按键的时间
+    fn recent_correct_keystrokes(&self, now: Instant) -> impl Iterator<Item = Instant> + '_ {
+        self.keystroke_history
+            .iter()
+            .filter(move |(ts, ch, correct)| {
+                *correct && now.duration_since(*ts) <= self.wpm_window && self.counts_for_wpm(*ch)
+            })
+            .map(|(ts, _, _)| *ts)
+    }
+
+    /// 计算 `now` 时刻的 WPM（基于统计窗口内的按键）
+    fn wpm_at(&self, now: Instant) -> f64 {
+        let recent: Vec<_> = self.recent_correct_keystrokes(now).collect();
+
+        let Some(&first_ts) = recent.first() else {
+            return 0.0;
+        };
+        // 窗口内的停顿不计入用时
+        let mut dead_time = Duration::ZERO;
+        let mut prev = first_ts;
+        for ts in self
+            .keystroke_history
+            .iter()
+            .map(|(ts, _, _)| *ts)
+            .filter(|ts| *ts > first_ts)
+            .chain([now])
+        {
+            dead_time += self.dead_time_of(ts.saturating_duration_since(prev));
+            prev = ts;
+        }
+        let duration = now
+            .duration_since(first_ts)
+            .saturating_sub(dead_time)
+            .as_secs_f64();
+
+        if duration < 0.1 {
+            return 0.0;
+        }
+
+        let chars = recent.len() as f64;
+        self.cpm_to_wpm((chars / duration) * 60.0)
+    }
+
+    /// CJK 课程的速度是否只计汉字（不计夹杂的标点、空格和拉丁字母）
+    pub fn set_cjk_net_wpm(&mut self, enabled: bool) {
+        self.cjk_net_wpm = enabled;
+        self.invalidate_snapshot();
+    }
+
+    pub fn cjk_net_wpm(&self) -> bool {
+        self.cjk_net_wpm
+    }
+
+    /// 正确输入的字符是否计入速度
+    fn counts_for_wpm(&self, ch: char) -> bool {
+        !(self.cjk_net_wpm && self.is_cjk_language()) || is_cjk_char(ch)
+    }
+
+    /// 判断是否为 CJK（中日韩）语言
+    pub fn is_cjk_language(&self) -> bool {
+        self.language.starts_with("zh-") // 中文
+            || self.language.starts_with("ja-") // 日文
+            || self.language.starts_with("ko-") // 韩文
+    }
+
+    /// 完成当前练习并生成统计
+    fn finalize_current_exercise(&self) -> ExerciseStats {
+        // 停顿不计入用时
+        let dead_time = self.exercise_dead_time();
+        let duration = self
+            .exercise_elapsed()
+            .unwrap_or(Duration::ZERO)
+            .saturating_sub(dead_time);
+
+        let accuracy = if self.total_keystrokes > 0 {
+            self.correct_keystrokes as f64 / self.total_keystrokes as f64
+        } else {
+            0.0
+        };
+
+        let (cpm, raw_cpm) = if duration.as_secs() > 0 {
+            let wpm_chars = self.correct_keystrokes - self.excluded_from_wpm;
+            (
+                (wpm_chars as f64 / duration.as_secs_f64()) * 60.0,
+                (self.total_keystrokes as f64 / duration.as_secs_f64()) * 60.0,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+
+        let exercise = self.get_current_exercise();
+        let mut stats = ExerciseStats::from_exercise(
+            exercise,
+            self.current_lesson_exercise_index(),
+            self.cpm_to_wpm(cpm),
+            accuracy,
+            self.total_keystrokes,
+            self.error_positions.len(),
+            duration,
+        );
+        stats.error_categories = self.error_categories.clone();
+        stats.capitalization_errors = self.capitalization_errors;
+        stats.attempts_to_perfect = self.perfect_practice.then_some(self.attempt);
+        stats.raw_wpm = self.cpm_to_wpm(raw_cpm);
+        stats.peak_wpm = self.exercise_peak_wpm;
+        stats.idle_secs = dead_time.as_secs();
+        stats.finger_stats = self.finger_stats();
+        stats.excluded_from_wpm = self.excluded_from_wpm;
+        stats.correct_keystrokes = self.correct_keystrokes;
+        stats
+    }
+
+    /// 当前练习已输入的字母按手指统计（错误数, 总数），输错后纠正的也计为错误
+    fn finger_stats(&self) -> HashMap<Finger, (usize, usize)> {
+        let mut fingers: HashMap<Finger, (usize, usize)> = HashMap::new();
+        let typed = self.current_position.min(self.target_chars.len());
+        for pos in (0..typed).filter(|&pos| self.input_mask[pos]) {
+            let Some(finger) = finger_map::finger_for(self.keyboard_layout, self.target_chars[pos])
+            else {
+                continue;
+            };
+            let entry = fingers.entry(finger).or_default();
+            if self.missed_positions.contains(&pos) {
+                entry.0 += 1;
             }
+            entry.1 += 1;
+        }
+        fingers
+    }
+
+    /// 按课程语言把 CPM 换算为 WPM
+    fn cpm_to_wpm(&self, cpm: f64) -> f64 {
+        if self.is_cjk_language() {
+            // CJK 语言: 1 个字符 = 1 个"词"
+            cpm
+        } else {
+            // 拉丁字母语言: 平均 5 个字符 = 1 个词
+            cpm / 5.0
+        }
+    }
+
+    /// 构建所有练习的统计（包括已完成和当前的）
+    fn collect_exercise_stats(&self) -> Vec<ExerciseStats> {
+        let mut all_exercise_stats = self.exercise_stats.clone();
+
+        // 如果当前练习已完成但还没添加到 exercise_stats，添加它
+        if self.is_current_exercise_complete() {
+            let current_stats = self.finalize_current_exercise();
+            all_exercise_stats.push(current_stats);
         }
 
-        self.build_weak_units_from_stats(unit_stats)
+        all_exercise_stats
     }
 
-    /// 从统计数据构建 WeakUnit 列表
-    fn build_weak_units_from_stats(
-        &self,
-        stats: HashMap<String, (usize, usize, UnitType)>,
-    ) -> Vec<WeakUnit> {
-        let mut units: Vec<WeakUnit> = stats
-            .into_iter()
-            .filter(|(_, (total, _, _))| *total >= 3) // 至少出现 3 次
-            .map(|(content, (total, errors, unit_type))| {
-                let error_rate = errors as f32 / total as f32;
-                WeakUnit {
-                    content,
-                    unit_type,
-                    error_count: errors,
-                    total_count: total,
-                    error_rate,
-                }
-            })
-            .filter(|unit| unit.error_rate > 0.15) // 错误率 > 15%
-            .collect();
+    /// 按分类汇总本次会话的错误按键数
+    pub fn get_error_categories(&self) -> HashMap<ErrorCategory, usize> {
+        sum_error_categories(&self.collect_exercise_stats())
+    }
+
+    /// 本次会话中大小写错误的按键数（不限于句首）
+    pub fn get_capitalization_errors(&self) -> usize {
+        self.collect_exercise_stats()
+            .iter()
+            .map(|s| s.capitalization_errors)
+            .sum()
+    }
+
+    /// 本次会话是否达到课程作者设定的目标（课程未设置目标时返回 None）
+    pub fn meets_lesson_goal(&self) -> Option<bool> {
+        let stats = self.finalize_session();
+        self.lesson
+            .meta
+            .goal_met(stats.overall_wpm, stats.overall_accuracy)
+    }
+
+    /// 完成会话并生成统计（汇总所有练习）
+    /// 汇总会话统计（同一练习多次完成时分别计入）；无限模式下可随时调用
+    pub fn finalize_session(&self) -> SessionStats {
+        let all_exercise_stats = self.collect_exercise_stats();
+
+        // 汇总所有练习的数据
+        let total_duration_secs: u64 = all_exercise_stats.iter().map(|s| s.duration_secs).sum();
+        let total_keystrokes: usize = all_exercise_stats.iter().map(|s| s.total_keystrokes).sum();
+        let total_errors: usize = all_exercise_stats.iter().map(|s| s.error_count).sum();
+        let correct_keystrokes: usize = all_exercise_stats
+            .iter()
+            .map(|s| s.correct_keystrokes)
+            .sum();
+        let excluded_from_wpm: usize = all_exercise_stats.iter().map(|s| s.excluded_from_wpm).sum();
+        let error_categories = sum_error_categories(&all_exercise_stats);
+        let finger_stats = sum_finger_stats(&all_exercise_stats);
+
+        let overall_accuracy = if total_keystrokes > 0 {
+            correct_keystrokes as f64 / total_keystrokes as f64
+        } else {
+            0.0
+        };
+
+        let (overall_cpm, raw_cpm) = if total_duration_secs > 0 {
+            (
+                (correct_keystrokes.saturating_sub(excluded_from_wpm) as f64
+                    / total_duration_secs as f64)
+                    * 60.0,
+                (total_keystrokes as f64 / total_duration_secs as f64) * 60.0,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+
+        // 提取薄弱单元（基于所有练习）
+        let weak_units = self.extract_weak_units();
+
+        SessionStats {
+            lesson_id: self.lesson.id,
+            exercise_stats: all_exercise_stats,
+            overall_wpm: self.cpm_to_wpm(overall_cpm),
+            overall_cpm,
+            overall_accuracy,
+            total_keystrokes,
+            error_count: total_errors,
+            duration_secs: total_duration_secs,
+            timestamp: chrono::Utc::now().timestamp(),
+            weak_units,
+            error_categories,
+            pace_deviation: self.pace_deviation(),
+            char_counts: self.char_counts.clone(),
+            slow_bigrams: extract_latin_bigrams(&self.bigram_latency, SLOW_BIGRAM_COUNT),
+            bigram_latency: self
+                .bigram_latency
+                .iter()
+                .map(|(&(a, b), &(total, count))| (format!("{a}{b}"), total, count))
+                .collect(),
+            consistency: self.consistency(),
+            peak_wpm: self.peak_wpm,
+            raw_wpm: self.cpm_to_wpm(raw_cpm),
+            finger_stats,
+            longest_flow: self.flow_duration(),
+            latency: self.latency_profile(),
+        }
+    }
+
+    /// 记录与上一个字符之间的延迟（两个都是输对的拉丁字母且间隔不算停顿时）
+    fn record_bigram_latency(&mut self, target: char, now: Instant) {
+        let prev_target = self
+            .current_position
+            .checked_sub(1)
+            .and_then(|pos| self.target_chars.get(pos).copied());
+        let Some((ts, prev, true)) = self.keystroke_history.back().copied() else {
+            return;
+        };
+        // 退格后重输时上一次按键不是目标中的前一个字符
+        let latin_pair = prev.is_ascii_alphabetic() && target.is_ascii_alphabetic();
+        if prev_target != Some(prev) || !latin_pair {
+            return;
+        }
+        let gap = now.saturating_duration_since(ts);
+        if gap > BIGRAM_MAX_GAP {
+            return;
+        }
+
+        let pair = (prev.to_ascii_lowercase(), target.to_ascii_lowercase());
+        let entry = self.bigram_latency.entry(pair).or_default();
+        entry.0 += gap;
+        entry.1 += 1;
+    }
 
-        units.sort_by(|a, b| b.error_rate.partial_cmp(&a.error_rate).unwrap());
-        units.truncate(10); // 保留前 10 个
-        units
+    /// 根据课程语言类型提取薄弱单元
+    fn extract_weak_units(&self) -> Vec<WeakUnit> {
+        extract_weak_units_from(
+            self.get_target_text(),
+            &self.error_positions,
+            &self.language,
+            self.lesson.lesson_type,
+            &self.weak_unit_config,
+        )
     }
 
-    /// 获取 UI 渲染用的快照
+    /// 获取 UI 渲染用的快照（两次输入之间重复调用返回缓存）
+    /// 剩余时间随时变化，不缓存
     pub fn get_snapshot(&self) -> SessionSnapshot {
+        let cached = self.snapshot_cache.borrow().clone();
+        let mut snapshot = match cached {
+            Some(snapshot) => snapshot,
+            None => {
+                let snapshot = self.compute_snapshot();
+                *self.snapshot_cache.borrow_mut() = Some(snapshot.clone());
+                snapshot
+            }
+        };
+        snapshot.time_remaining = self.time_remaining();
+        snapshot
+    }
+
+    fn invalidate_snapshot(&self) {
+        self.snapshot_cache.borrow_mut().take();
+    }
+
+    fn compute_snapshot(&self) -> SessionSnapshot {
         // 计算整个 session 的进度（所有练习）
-        let total_exercises = self.lesson.exercises.len();
-        let completed_exercises = self.exercise_stats.len();
-        let current_exercise_progress = if !self.target_chars.is_empty() {
+        let total_exercises = self.exercise_order.len();
+        // 无限模式下为本轮的进度
+        let completed_exercises = if self.mode == PracticeMode::Endless {
+            self.current_exercise_index
+        } else {
+            self.exercise_stats.len() - self.ramp_repeats
+        };
+        // 渐进隐藏即将重复时当前练习还不算完成
+        let current_exercise_progress = if self.ramp_will_repeat() {
+            0.0
+        } else if !self.target_chars.is_empty() {
             self.current_position as f32 / self.target_chars.len() as f32
         } else {
             0.0
@@ -786,7 +2199,11 @@ impl TypingSession {
             } else {
                 1.0
             },
+            first_try_accuracy: self.first_try_accuracy(),
             progress: overall_progress,
+            pace_deviation: self.pace_deviation(),
+            time_remaining: self.time_remaining(),
+            peak_wpm: self.peak_wpm,
         }
     }
 
@@ -797,7 +2214,134 @@ impl TypingSession {
 
     /// 获取已输入的文本
     pub fn get_input_text(&self) -> String {
-        self.input_chars.iter().collect()
+        self.input_graphemes.concat()
+    }
+
+    /// 获取已完成练习的最终输入（按完成顺序）
+    pub fn get_completed_exercise_inputs(&self) -> &[(usize, String)] {
+        &self.completed_inputs
+    }
+
+    /// 光标所在的行和列（均从 0 开始，列按字素簇计；换行符之后为下一行第 0 列）
+    pub fn cursor_line_col(&self) -> (usize, usize) {
+        let before = &self.target_chars[..self.current_position.min(self.target_chars.len())];
+        let line = before.iter().filter(|&&ch| ch == '\n').count();
+        let col = before.iter().rev().take_while(|&&ch| ch != '\n').count();
+        (line, col)
+    }
+
+    /// 当前练习每个分段（多行文本按行，否则按句）的错误率
+    /// 范围为字符索引（含结尾的换行或句末标点），错误率按输错过的位置计（纠正后也算）
+    /// 不含需要输入字符的分段（如空行）不返回
+    pub fn segment_error_density(&self) -> Vec<(Range<usize>, f32)> {
+        let by_line = self.target_chars.contains(&'\n');
+        let ends_segment = |ch: char| {
+            if by_line {
+                ch == '\n'
+            } else {
+                matches!(ch, '.' | '!' | '?' | '。' | '！' | '？')
+            }
+        };
+
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for (i, &ch) in self.target_chars.iter().enumerate() {
+            if ends_segment(ch) {
+                ranges.push(start..i + 1);
+                start = i + 1;
+            }
+        }
+        if start < self.target_chars.len() {
+            ranges.push(start..self.target_chars.len());
+        }
+
+        ranges
+            .into_iter()
+            .filter_map(|range| {
+                let required: Vec<usize> = range.clone().filter(|&i| self.input_mask[i]).collect();
+                if required
+                    .iter()
+                    .all(|&i| self.target_chars[i].is_whitespace())
+                {
+                    return None;
+                }
+                let missed = required
+                    .iter()
+                    .filter(|i| self.missed_positions.contains(i))
+                    .count();
+                Some((range, missed as f32 / required.len() as f32))
+            })
+            .collect()
+    }
+
+    /// 获取下一个需要输入的字符（字素簇的首字符，练习完成时返回 None）
+    pub fn current_target_char(&self) -> Option<char> {
+        self.target_chars.get(self.current_position).copied()
+    }
+
+    /// 当前练习中无法输入的字符位置（只检查需要输入的位置）
+    /// `is_typeable` 判断字符能否在当前键盘/输入法下输入
+    pub fn unreachable_positions(&self, is_typeable: impl Fn(char) -> bool) -> Vec<usize> {
+        self.target_chars
+            .iter()
+            .zip(&self.input_mask)
+            .enumerate()
+            .filter(|&(_, (&ch, &required))| required && !is_typeable(ch))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// 获取渲染窗口：光标之后至少保留 `preview_window` 个字符
+    /// 窗口起点按 `preview_window` 分块对齐，只在跨块时移动，避免每次按键都重新排版
+    pub fn preview_range(&self, preview_window: usize) -> Range<usize> {
+        let len = self.target_chars.len();
+        if preview_window == 0 {
+            return 0..len;
+        }
+
+        let start = self.current_position - self.current_position % preview_window;
+        let end = (start + preview_window * 2).min(len);
+        start.min(end)..end
+    }
+
+    /// 获取光标之后尚未输入的目标文本
+    pub fn remaining_text(&self) -> &str {
+        let text = self.get_target_text();
+        text.grapheme_indices(true)
+            .nth(self.current_position)
+            .map_or("", |(byte_idx, _)| &text[byte_idx..])
+    }
+
+    /// 光标之后的 `n` 个目标字素簇（记忆模式下用于临时提示被隐藏的内容）
+    pub fn peek_ahead(&self, n: usize) -> String {
+        self.remaining_text().graphemes(true).take(n).collect()
+    }
+
+    /// 当前练习已用时间（首次按键开始计时，不含暂停时长，未开始时返回 None）
+    /// 练习完成后停在完成时刻
+    pub fn exercise_elapsed(&self) -> Option<Duration> {
+        let end = self.exercise_end_time.unwrap_or_else(|| self.clock());
+        self.exercise_start_time.map(|t| {
+            end.saturating_duration_since(t)
+                .saturating_sub(self.paused_duration)
+        })
+    }
+
+    /// 以目标速度匀速输入时，经过 `elapsed` 应到达的位置（未取整、不截断）
+    pub fn expected_position_for_wpm(&self, target_wpm: f64, elapsed: Duration) -> f64 {
+        // 与 WPM 统计一致：CJK 1 字 = 1 词，拉丁字母 5 字符 = 1 词
+        let chars_per_minute = if self.is_cjk_language() {
+            target_wpm
+        } else {
+            target_wpm * 5.0
+        };
+        (chars_per_minute * elapsed.as_secs_f64() / 60.0).max(0.0)
+    }
+
+    /// 节拍器位置：以目标速度匀速推进时，经过 `elapsed` 应到达的字符位置
+    pub fn metronome_position(&self, target_wpm: f64, elapsed: Duration) -> usize {
+        let position = self.expected_position_for_wpm(target_wpm, elapsed) as usize;
+        position.min(self.target_chars.len())
     }
 
     fn send_event(&self, event: TypingEvent) {
@@ -806,50 +2350,650 @@ impl TypingSession {
         }
     }
 
+    /// 导出引擎内部状态（JSON），用于附在问题报告中复现
+    pub fn debug_dump(&self) -> String {
+        let mut error_positions: Vec<usize> = self.error_positions.iter().copied().collect();
+        error_positions.sort_unstable();
+        let mut missed_positions: Vec<usize> = self.missed_positions.iter().copied().collect();
+        missed_positions.sort_unstable();
+
+        let dump = DebugDump {
+            lesson_id: self.lesson.id,
+            lesson_title: &self.lesson.title,
+            exercise_index: self.current_lesson_exercise_index(),
+            order_position: self.current_exercise_index,
+            exercise_order: &self.exercise_order,
+            position: self.current_position,
+            target_text: self.get_target_text(),
+            input_text: self.get_input_text(),
+            error_positions,
+            missed_positions,
+            total_keystrokes: self.total_keystrokes,
+            correct_keystrokes: self.correct_keystrokes,
+            input_mode: self.input_mode,
+            current_input_mode: self.current_input_mode(),
+            auto_advance: self.auto_advance,
+            timer_start: self.timer_start,
+            allow_backspace: self.allow_backspace,
+            strict_completion: self.strict_completion,
+            perfect_practice: self.perfect_practice,
+            attempt: self.attempt,
+            tab_stop: self.tab_stop,
+            whitespace_policy: self.whitespace_policy(),
+            awaiting_start: self.is_awaiting_start(),
+            paused: self.is_paused(),
+            lesson_finished: self.lesson_finished,
+        };
+        serde_json::to_string_pretty(&dump).unwrap_or_default()
+    }
+
     /// 保存会话到数据库（需要启用 persistence feature）
+    /// 会话短于最短保存时长时不保存，返回 Ok(None)
     #[cfg(feature = "persistence")]
-    pub fn save_to_database(&self, db: &Database) -> Result<i64, Box<dyn std::error::Error>> {
+    pub fn save_to_database(
+        &self,
+        db: &Database,
+    ) -> Result<Option<i64>, Box<dyn std::error::Error>> {
         let stats = self.finalize_session();
+        if Duration::from_secs(stats.duration_secs) < self.min_session_duration {
+            debug!(
+                "⏭️  会话时长 {} 秒，短于最短保存时长 {:?}，不保存",
+                stats.duration_secs, self.min_session_duration
+            );
+            return Ok(None);
+        }
+
         let session_id = db.save_session(&stats, &self.lesson.title)?;
-        Ok(session_id)
+        Ok(Some(session_id))
+    }
+
+    /// 获取课程 ID
+    pub fn get_lesson_id(&self) -> u32 {
+        self.lesson.id
     }
 
     /// 获取课程标题
     pub fn get_lesson_title(&self) -> &str {
         &self.lesson.title
     }
+
+    /// 获取练习模式
+    pub fn get_mode(&self) -> PracticeMode {
+        self.mode
+    }
 }
 
-/// UI 渲染快照（轻量级）
-#[derive(Debug, Clone)]
-pub struct SessionSnapshot {
-    pub cursor_position: usize,
-    pub recent_errors: Vec<usize>,
-    pub current_wpm: f64,
-    pub accuracy: f64,
-    pub progress: f32,
+/// 根据历史表现生成自适应练习顺序（需要启用 persistence feature）
+///
+/// 历史准确率越低的练习权重越高，越可能排在前面
+#[cfg(feature = "persistence")]
+pub fn build_adaptive_order(
+    lesson: &Lesson,
+    db: &Database,
+) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
+    let history = db.get_exercise_accuracy(lesson.id)?;
+    Ok(adaptive_order_from_history(
+        lesson.exercises.len(),
+        &history,
+        &mut rand::thread_rng(),
+    ))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use keyzen_core::{Difficulty, LessonMeta};
+/// 根据每个练习的历史准确率做加权随机排序
+///
+/// 权重 = 1 - 准确率 + 0.05（无历史记录的练习按 50% 准确率处理），
+/// 使用 Efraimidis-Spirakis 加权抽样：key = u^(1/w)，按 key 降序排列
+pub fn adaptive_order_from_history<R: rand::Rng + ?Sized>(
+    exercise_count: usize,
+    history: &HashMap<usize, f64>,
+    rng: &mut R,
+) -> Vec<usize> {
+    let mut keyed: Vec<(f64, usize)> = (0..exercise_count)
+        .map(|i| {
+            let accuracy = history.get(&i).copied().unwrap_or(0.5).clamp(0.0, 1.0);
+            let weight = 1.0 - accuracy + 0.05;
+            let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+            (u.powf(1.0 / weight), i)
+        })
+        .collect();
 
-    fn create_test_lesson() -> Lesson {
-        Lesson {
-            id: 1,
-            lesson_type: LessonType::Prose,
-            language: "en-US".to_string(),
-            title: "Test Lesson".to_string(),
-            description: "A test lesson".to_string(),
-            source_text: "hello world".to_string(),
-            meta: LessonMeta {
-                difficulty: Difficulty::Beginner,
-                tags: vec!["test".to_string()],
-                estimated_time: Duration::from_secs(60),
-                prerequisite_ids: vec![],
-            },
-        }
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.into_iter().map(|(_, i)| i).collect()
+}
+
+/// 按指定方式排序课程列表（相同时按课程 ID）
+///
+/// `last_practiced` 为每个课程最近一次练习的时间戳，只在按最近练习排序时使用
+pub fn sort_lessons(lessons: &mut [Lesson], sort: LessonSort, last_practiced: &HashMap<u32, i64>) {
+    lessons.sort_by(|a, b| {
+        let order = match sort {
+            LessonSort::Id => std::cmp::Ordering::Equal,
+            LessonSort::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            LessonSort::Difficulty => a.meta.difficulty.cmp(&b.meta.difficulty),
+            // None 小于任何时间戳，倒序后没有记录的课程排在最后
+            LessonSort::LastPracticed => last_practiced.get(&b.id).cmp(&last_practiced.get(&a.id)),
+        };
+        order.then(a.id.cmp(&b.id))
+    });
+}
+
+/// 按平均延迟汇总双字母（有序字符对），返回最慢的 `limit` 个
+///
+/// `latency` 为每个字符对的总延迟和出现次数
+pub fn extract_latin_bigrams(
+    latency: &HashMap<(char, char), (Duration, u32)>,
+    limit: usize,
+) -> Vec<(String, Duration)> {
+    let mut bigrams: Vec<(String, Duration)> = latency
+        .iter()
+        .filter(|(_, (_, count))| *count > 0)
+        .map(|(&(a, b), &(total, count))| (format!("{a}{b}"), total / count))
+        .collect();
+    bigrams.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    bigrams.truncate(limit);
+    bigrams
+}
+
+/// 根据目标文本和出错位置（字素簇索引）按 `config` 的筛选条件提取薄弱单元
+/// 代码课程按 token 统计，其他课程按语言统计
+///
+/// 与 `TypingSession` 内部状态无关，可用于从已保存的数据重新计算薄弱单元
+pub fn extract_weak_units_from(
+    target: &str,
+    error_positions: &HashSet<usize>,
+    language: &str,
+    lesson_type: LessonType,
+    config: &WeakUnitConfig,
+) -> Vec<WeakUnit> {
+    let target_chars: Vec<&str> = target.graphemes(true).collect();
+    if lesson_type == LessonType::Code {
+        return extract_code_weak_units(&target_chars, error_positions, config);
+    }
+    match language {
+        lang if lang.starts_with("zh-") => {
+            extract_chinese_weak_units(&target_chars, error_positions, config)
+        }
+        lang if lang.starts_with("en-") => {
+            extract_english_weak_units(&target_chars, error_positions, config)
+        }
+        _ => extract_character_weak_units(&target_chars, error_positions, config), // 默认字符级别
+    }
+}
+
+/// 中文：提取单字符（汉字）和常见双字词组
+fn extract_chinese_weak_units(
+    target_chars: &[&str],
+    error_positions: &HashSet<usize>,
+    config: &WeakUnitConfig,
+) -> Vec<WeakUnit> {
+    let mut unit_stats: HashMap<String, (usize, usize, UnitType)> = HashMap::new();
+
+    // 1. 单字符统计
+    for (i, &target_char) in target_chars.iter().enumerate() {
+        let key = target_char.to_string();
+        let entry = unit_stats.entry(key).or_insert((0, 0, UnitType::Character));
+        entry.0 += 1; // 总次数
+        if error_positions.contains(&i) {
+            entry.1 += 1; // 错误次数
+        }
+    }
+
+    // 2. 双字词组统计（可选）
+    for i in 0..target_chars.len().saturating_sub(1) {
+        let c1 = target_chars[i];
+        let c2 = target_chars[i + 1];
+
+        // 只统计双汉字组合
+        let is_ascii_or_blank = |g: &str| g.is_ascii() || g.trim().is_empty();
+        if is_ascii_or_blank(c1) || is_ascii_or_blank(c2) {
+            continue;
+        }
+
+        let phrase = format!("{}{}", c1, c2);
+        let has_error = error_positions.contains(&i) || error_positions.contains(&(i + 1));
+
+        let entry = unit_stats.entry(phrase).or_insert((0, 0, UnitType::Phrase));
+        entry.0 += 1;
+        if has_error {
+            entry.1 += 1;
+        }
+    }
+
+    build_weak_units_from_stats(unit_stats, config)
+}
+
+/// 英文：提取单词级别
+fn extract_english_weak_units(
+    target_chars: &[&str],
+    error_positions: &HashSet<usize>,
+    config: &WeakUnitConfig,
+) -> Vec<WeakUnit> {
+    let mut unit_stats: HashMap<String, (usize, usize, UnitType)> = HashMap::new();
+
+    // 分词逻辑
+    let target_text = target_chars.concat();
+    let words: Vec<&str> = target_text.split_whitespace().collect();
+
+    let mut char_offset = 0;
+    for word in words {
+        let word_start = char_offset;
+        let word_end = char_offset + word.graphemes(true).count();
+
+        // 检查该单词是否有错误
+        let has_error = (word_start..word_end).any(|i| error_positions.contains(&i));
+
+        let entry = unit_stats
+            .entry(word.to_string())
+            .or_insert((0, 0, UnitType::Word));
+        entry.0 += 1;
+        if has_error {
+            entry.1 += 1;
+        }
+
+        // 跳过单词和后面的空格
+        char_offset = word_end;
+        // 查找下一个非空白字符的位置
+        while char_offset < target_chars.len() && target_chars[char_offset].trim().is_empty() {
+            char_offset += 1;
+        }
+    }
+
+    // 同时也统计字符级别（用于特殊字符和标点）
+    for (i, &target_char) in target_chars.iter().enumerate() {
+        // 只统计非字母数字的字符
+        let is_word_char = target_char.chars().any(|ch| ch.is_alphanumeric());
+        if !is_word_char && !target_char.trim().is_empty() {
+            let key = target_char.to_string();
+            let entry = unit_stats.entry(key).or_insert((0, 0, UnitType::Character));
+            entry.0 += 1;
+            if error_positions.contains(&i) {
+                entry.1 += 1;
+            }
+        }
+    }
+
+    build_weak_units_from_stats(unit_stats, config)
+}
+
+/// 代码：提取 token（标识符、关键字、字符串字面量、运算符）和分隔符
+fn extract_code_weak_units(
+    target_chars: &[&str],
+    error_positions: &HashSet<usize>,
+    config: &WeakUnitConfig,
+) -> Vec<WeakUnit> {
+    let mut unit_stats: HashMap<String, (usize, usize, UnitType)> = HashMap::new();
+
+    for span in code_token_spans(target_chars) {
+        let (key, unit_type) = if span.len() == 1 && is_code_delimiter(target_chars[span.start]) {
+            (target_chars[span.start].to_string(), UnitType::Character)
+        } else {
+            (target_chars[span.clone()].concat(), UnitType::Token)
+        };
+        let entry = unit_stats.entry(key).or_insert((0, 0, unit_type));
+        entry.0 += 1;
+        if span.clone().any(|i| error_positions.contains(&i)) {
+            entry.1 += 1;
+        }
+    }
+
+    build_weak_units_from_stats(unit_stats, config)
+}
+
+/// 代码中分隔 token 的符号（单独作为字符统计）
+fn is_code_delimiter(grapheme: &str) -> bool {
+    matches!(
+        grapheme,
+        "(" | ")" | "{" | "}" | "[" | "]" | ";" | "," | "."
+    )
+}
+
+/// 把代码切分为 token 的位置范围（跳过空白）
+/// 连续的标识符字符为一个 token；双引号括起的字符串（同一行内）和字符字面量为一个 token；
+/// 不成字符字面量的单引号与后面的标识符为一个 token（如生命周期 `'a`）；
+/// 连续的运算符符号（如 `->`、`::`、`==`）为一个 token；分隔符各自单独成一个范围
+fn code_token_spans(target_chars: &[&str]) -> Vec<Range<usize>> {
+    let is_ident = |g: &str| g.chars().all(|ch| ch.is_alphanumeric() || ch == '_');
+    let is_quote = |g: &str| g == "\"" || g == "'";
+    let is_operator =
+        |g: &str| !g.trim().is_empty() && !is_ident(g) && !is_quote(g) && !is_code_delimiter(g);
+
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < target_chars.len() {
+        let g = target_chars[i];
+        let start = i;
+        i += 1;
+        if g.trim().is_empty() {
+            continue;
+        }
+
+        if g == "\"" {
+            // 找到同一行内的闭合引号（跳过转义），找不到时引号单独作为运算符
+            let mut end = i;
+            while end < target_chars.len() && target_chars[end] != g && target_chars[end] != "\n" {
+                end += if target_chars[end] == "\\" { 2 } else { 1 };
+            }
+            if target_chars.get(end) == Some(&g) {
+                i = end + 1;
+            }
+        } else if g == "'" {
+            if let Some(end) = char_literal_end(target_chars, i) {
+                i = end + 1;
+            } else {
+                while i < target_chars.len() && is_ident(target_chars[i]) {
+                    i += 1;
+                }
+            }
+        } else if is_ident(g) {
+            while i < target_chars.len() && is_ident(target_chars[i]) {
+                i += 1;
+            }
+        } else if is_operator(g) {
+            while i < target_chars.len() && is_operator(target_chars[i]) {
+                i += 1;
+            }
+        }
+        spans.push(start..i);
+    }
+    spans
+}
+
+/// 字符字面量闭合引号的位置（`start` 为开引号之后）
+/// 引号在一个字符或一个转义序列之后闭合才算字符字面量，否则返回 None
+fn char_literal_end(target_chars: &[&str], start: usize) -> Option<usize> {
+    let is_quote_at = |end: usize| target_chars.get(end) == Some(&"'");
+    match target_chars.get(start) {
+        None | Some(&"'") | Some(&"\n") => None,
+        Some(&"\\") => (start + 2..=start + CHAR_ESCAPE_MAX_LEN)
+            .take_while(|&end| target_chars.get(end).is_some_and(|&g| g != "\n"))
+            .find(|&end| is_quote_at(end)),
+        Some(_) => is_quote_at(start + 1).then_some(start + 1),
+    }
+}
+
+/// 默认：字符级别统计
+fn extract_character_weak_units(
+    target_chars: &[&str],
+    error_positions: &HashSet<usize>,
+    config: &WeakUnitConfig,
+) -> Vec<WeakUnit> {
+    let mut unit_stats: HashMap<String, (usize, usize, UnitType)> = HashMap::new();
+
+    for (i, &target_char) in target_chars.iter().enumerate() {
+        let key = target_char.to_string();
+        let entry = unit_stats.entry(key).or_insert((0, 0, UnitType::Character));
+        entry.0 += 1;
+        if error_positions.contains(&i) {
+            entry.1 += 1;
+        }
+    }
+
+    build_weak_units_from_stats(unit_stats, config)
+}
+
+/// 从统计数据构建 WeakUnit 列表
+fn build_weak_units_from_stats(
+    stats: HashMap<String, (usize, usize, UnitType)>,
+    config: &WeakUnitConfig,
+) -> Vec<WeakUnit> {
+    let mut units: Vec<WeakUnit> = stats
+        .into_iter()
+        .filter(|(_, (total, _, _))| *total >= config.min_occurrences)
+        .map(|(content, (total, errors, unit_type))| {
+            let error_rate = errors as f32 / total as f32;
+            WeakUnit {
+                content,
+                unit_type,
+                error_count: errors,
+                total_count: total,
+                error_rate,
+            }
+        })
+        .filter(|unit| unit.error_rate > config.min_error_rate)
+        .collect();
+
+    units.sort_by(|a, b| b.error_rate.partial_cmp(&a.error_rate).unwrap());
+    units.truncate(config.max_results);
+    units
+}
+
+/// 渐进隐藏的下一个级别（已完全隐藏时返回 None）
+fn next_ramp_level(level: MemoryMode) -> Option<MemoryMode> {
+    match level {
+        MemoryMode::Off | MemoryMode::FirstLetter => Some(MemoryMode::Partial(PartialLevel::Low)),
+        MemoryMode::Partial(PartialLevel::Low) => Some(MemoryMode::Partial(PartialLevel::Medium)),
+        MemoryMode::Partial(PartialLevel::Medium) => Some(MemoryMode::Partial(PartialLevel::High)),
+        MemoryMode::Partial(PartialLevel::High) => Some(MemoryMode::Complete),
+        MemoryMode::Complete => None,
+    }
+}
+
+/// 输入字母与目标只有大小写不同（如目标 'H' 输入 'h'）
+fn is_case_mismatch(target: char, input: char) -> bool {
+    target.is_alphabetic() && target != input && target.to_lowercase().eq(input.to_lowercase())
+}
+
+/// 按字素簇切分练习内容，返回每个字素簇、其首字符和是否需要输入
+/// 是否需要输入取字素簇首字符在 `Exercise::input_mask` 中的值
+fn split_target(exercise: &Exercise) -> (Vec<String>, Vec<char>, Vec<bool>) {
+    let char_mask = exercise.input_mask();
+    let mut graphemes = Vec::new();
+    let mut chars = Vec::new();
+    let mut mask = Vec::new();
+    let mut char_index = 0;
+    for grapheme in exercise.content.graphemes(true) {
+        graphemes.push(grapheme.to_string());
+        chars.extend(grapheme.chars().next());
+        mask.push(char_mask.get(char_index).copied().unwrap_or(true));
+        char_index += grapheme.chars().count();
+    }
+    (graphemes, chars, mask)
+}
+
+/// 汇总多个练习的错误分类
+fn sum_error_categories(stats: &[ExerciseStats]) -> HashMap<ErrorCategory, usize> {
+    let mut categories = HashMap::new();
+    for exercise in stats {
+        for (&category, &count) in &exercise.error_categories {
+            *categories.entry(category).or_insert(0) += count;
+        }
+    }
+    categories
+}
+
+fn sum_finger_stats(stats: &[ExerciseStats]) -> HashMap<Finger, (usize, usize)> {
+    let mut fingers: HashMap<Finger, (usize, usize)> = HashMap::new();
+    for exercise in stats {
+        for (&finger, &(errors, total)) in &exercise.finger_stats {
+            let entry = fingers.entry(finger).or_default();
+            entry.0 += errors;
+            entry.1 += total;
+        }
+    }
+    fingers
+}
+
+/// 最长的心流时长：WPM 高于序列平均值且准确率不低于 `FLOW_MIN_ACCURACY` 的最长连续时段
+/// `wpm` 与 `accuracy` 为按时间同步采样的（秒数, 数值）序列
+fn longest_flow(wpm: &[(f64, f64)], accuracy: &[(f64, f64)]) -> Duration {
+    if wpm.is_empty() {
+        return Duration::ZERO;
+    }
+    let average = wpm.iter().map(|&(_, value)| value).sum::<f64>() / wpm.len() as f64;
+
+    let mut longest = 0.0_f64;
+    let mut run_start = None;
+    for (&(time, value), &(_, acc)) in wpm.iter().zip(accuracy) {
+        if value > average && acc >= FLOW_MIN_ACCURACY {
+            let start = *run_start.get_or_insert(time);
+            longest = longest.max(time - start);
+        } else {
+            run_start = None;
+        }
+    }
+    Duration::from_secs_f64(longest)
+}
+
+/// debug_dump 导出的内部状态
+#[derive(Serialize)]
+struct DebugDump<'a> {
+    lesson_id: u32,
+    lesson_title: &'a str,
+    exercise_index: usize,
+    order_position: usize,
+    exercise_order: &'a [usize],
+    position: usize,
+    target_text: &'a str,
+    input_text: String,
+    error_positions: Vec<usize>,
+    missed_positions: Vec<usize>,
+    total_keystrokes: usize,
+    correct_keystrokes: usize,
+    input_mode: InputMode,
+    current_input_mode: InputMode,
+    auto_advance: AutoAdvance,
+    timer_start: TimerStart,
+    allow_backspace: bool,
+    strict_completion: bool,
+    perfect_practice: bool,
+    attempt: usize,
+    tab_stop: Option<usize>,
+    whitespace_policy: WhitespacePolicy,
+    awaiting_start: bool,
+    paused: bool,
+    lesson_finished: bool,
+}
+
+/// UI 渲染快照（轻量级）
+#[derive(Debug, Clone)]
+pub struct SessionSnapshot {
+    pub cursor_position: usize,
+    pub recent_errors: Vec<usize>,
+    pub current_wpm: f64,
+    pub accuracy: f64,
+    pub first_try_accuracy: f64,
+    pub progress: f32,
+    pub pace_deviation: Option<PaceDeviation>,
+    pub time_remaining: Option<Duration>, // 限时挑战的剩余时间
+    pub peak_wpm: f64,                    // 本次会话最快的 5 秒爆发 WPM
+}
+
+/// 按录制时的节奏逐步重放会话的按键（需开启 replay feature）
+#[cfg(feature = "replay")]
+pub struct ReplayPlayer {
+    session: TypingSession,
+    events: Vec<ReplayEvent>,
+    next: usize,        // 下一个待重放的按键
+    position: Duration, // 已回放到的偏移
+}
+
+#[cfg(feature = "replay")]
+impl ReplayPlayer {
+    /// 从已录制的会话创建播放器（在沿用原设置的新会话中重放）
+    /// 回放无法还原该会话时返回 None
+    pub fn new(recorded: &TypingSession) -> Option<Self> {
+        if !recorded.is_replayable() {
+            return None;
+        }
+        let mut player = Self {
+            session: recorded.replay_session(),
+            events: recorded.export_replay(),
+            next: 0,
+            position: Duration::ZERO,
+        };
+        player.seek_first_event();
+        Some(player)
+    }
+
+    /// 停在第一次按键所在的练习（会话可能从中间的练习开始）
+    fn seek_first_event(&mut self) {
+        if let Some(first) = self.events.first() {
+            let first = *first;
+            self.session.seek_replay_event(&first);
+        }
+    }
+
+    /// 回放时间前进 `elapsed`，重放这段时间内的按键；返回是否重放了按键
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        self.position += elapsed;
+        let now = Instant::now();
+        let start = now.checked_sub(self.position).unwrap_or(now);
+        let first = self.next;
+        while let Some(event) = self.events.get(self.next) {
+            if event.offset > self.position {
+                break;
+            }
+            let event = *event;
+            self.next += 1;
+            if !self.session.replay_event(&event, start) {
+                self.next = self.events.len();
+            }
+        }
+        self.next > first
+    }
+
+    /// 距下一次按键的回放时长（已播放完时为 None）
+    pub fn until_next(&self) -> Option<Duration> {
+        self.events
+            .get(self.next)
+            .map(|event| event.offset.saturating_sub(self.position))
+    }
+
+    /// 是否已重放完所有按键
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+
+    /// 从头开始重放
+    pub fn rewind(&mut self) {
+        self.session = self.session.replay_session();
+        self.next = 0;
+        self.position = Duration::ZERO;
+        self.seek_first_event();
+    }
+
+    /// 已回放到的偏移
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    /// 回放总时长（最后一次按键的偏移）
+    pub fn duration(&self) -> Duration {
+        self.events
+            .last()
+            .map_or(Duration::ZERO, |event| event.offset)
+    }
+
+    /// 重放中的会话（用于渲染当前练习的输入）
+    pub fn session(&self) -> &TypingSession {
+        &self.session
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use keyzen_core::{Difficulty, LessonMeta};
+
+    fn create_test_lesson() -> Lesson {
+        Lesson {
+            id: 1,
+            lesson_type: LessonType::Prose,
+            language: "en-US".to_string(),
+            title: "Test Lesson".to_string(),
+            description: "A test lesson".to_string(),
+            exercises: vec![Exercise::new("hello world")],
+            meta: LessonMeta {
+                difficulty: Difficulty::Beginner,
+                tags: vec!["test".to_string()],
+                estimated_time: Duration::from_secs(60),
+                prerequisite_ids: vec![],
+                target_wpm: None,
+                target_accuracy: None,
+            },
+            mark_newlines: false,
+            regenerate_on_restart: None,
+        }
     }
 
     #[test]
@@ -880,4 +3024,1855 @@ mod tests {
         assert_eq!(session.current_position, 1); // Forgiving 模式继续
         assert_eq!(session.error_positions.len(), 1);
     }
+
+    #[test]
+    fn test_space_does_not_match_newline() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab\ncd")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        // 换行与空格不可互换
+        for ch in "ab cd".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.is_current_exercise_complete());
+        assert!(session.error_positions.contains(&2));
+        assert_eq!(session.get_input_text(), "ab cd");
+
+        // 在空格处多按了回车
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        for ch in "hello\nworld".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.error_positions.contains(&5));
+    }
+
+    #[test]
+    fn test_adaptive_order_favors_weak_exercise() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        // 练习 2 的历史准确率很差，其余都接近完美
+        let history: HashMap<usize, f64> = [(0, 1.0), (1, 0.98), (2, 0.2), (3, 1.0)].into();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut weak_first = 0;
+        for _ in 0..200 {
+            let order = adaptive_order_from_history(4, &history, &mut rng);
+            let mut sorted = order.clone();
+            sorted.sort();
+            assert_eq!(sorted, vec![0, 1, 2, 3]);
+            if order[0] == 2 {
+                weak_first += 1;
+            }
+        }
+        assert!(weak_first > 150, "weak exercise first {} / 200", weak_first);
+    }
+
+    #[test]
+    fn test_set_exercise_order() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("a"), Exercise::new("b"), Exercise::new("c")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        session.set_exercise_order(vec![2, 0, 9]);
+        assert_eq!(session.get_progress(), (0, 2));
+        assert_eq!(session.get_target_text(), "c");
+
+        session.handle_keystroke('c');
+        assert!(session.advance_to_next_exercise());
+        assert_eq!(session.get_target_text(), "a");
+        assert_eq!(session.exercise_stats[0].exercise_index, 2);
+        assert!(!session.has_next_exercise());
+    }
+
+    #[test]
+    fn test_current_target_char() {
+        let lesson = create_test_lesson();
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        assert_eq!(session.current_target_char(), Some('h'));
+
+        for ch in "hello ".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert_eq!(session.current_target_char(), Some('w'));
+        assert_eq!(session.remaining_text(), "world");
+
+        for ch in "world".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert_eq!(session.current_target_char(), None);
+        assert_eq!(session.remaining_text(), "");
+    }
+
+    #[test]
+    fn test_remaining_text_multibyte() {
+        let mut lesson = create_test_lesson();
+        lesson.language = "zh-CN".to_string();
+        lesson.exercises = vec![Exercise::new("你好世界")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        session.handle_keystroke('你');
+        assert_eq!(session.current_target_char(), Some('好'));
+        assert_eq!(session.remaining_text(), "好世界");
+    }
+
+    #[test]
+    fn test_cloze_only_blanked_words_require_input() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::cloze("the quick brown fox", vec![1, 3])];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        // 上下文 "the " 被跳过，光标停在第一个挖空单词
+        assert_eq!(session.current_target_char(), Some('q'));
+        assert_eq!(
+            session.generate_display_text(MemoryMode::Off),
+            "the _____ brown ___"
+        );
+
+        for ch in "quick".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert_eq!(session.current_target_char(), Some('f'));
+
+        // 退格跳过上下文，删除 "quick" 的最后一个字符
+        session.handle_keystroke('\u{0008}');
+        assert_eq!(session.current_target_char(), Some('k'));
+        session.handle_keystroke('k');
+
+        for ch in "fox".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.is_current_exercise_complete());
+        assert_eq!(session.get_input_text(), "the quick brown fox");
+        // 只有挖空单词的按键计入统计（退格不计入）
+        assert_eq!(session.total_keystrokes, 9);
+        assert_eq!(session.correct_keystrokes, 9);
+    }
+
+    #[test]
+    fn test_metronome_position() {
+        let session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        // 60 WPM = 300 字符/分钟 = 5 字符/秒
+        assert_eq!(session.metronome_position(60.0, Duration::ZERO), 0);
+        assert_eq!(session.metronome_position(60.0, Duration::from_secs(2)), 10);
+        assert_eq!(
+            session.metronome_position(60.0, Duration::from_millis(500)),
+            2
+        );
+        // 不超过练习长度
+        assert_eq!(
+            session.metronome_position(60.0, Duration::from_secs(60)),
+            11
+        );
+
+        let mut lesson = create_test_lesson();
+        lesson.language = "zh-CN".to_string();
+        lesson.exercises = vec![Exercise::new("你好世界")];
+        let session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        // CJK：60 WPM = 1 字/秒
+        assert_eq!(session.metronome_position(60.0, Duration::from_secs(3)), 3);
+    }
+
+    #[test]
+    fn test_capitalization_error_category() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("Hi. See")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        // 句首小写：大小写错误
+        session.handle_keystroke('h');
+        // 句中字母错误：其他错误
+        session.handle_keystroke('o');
+        for ch in ". ".chars() {
+            session.handle_keystroke(ch);
+        }
+        // 句末标点后的句首
+        session.handle_keystroke('s');
+        for ch in "ee".chars() {
+            session.handle_keystroke(ch);
+        }
+
+        let categories = session.get_error_categories();
+        assert_eq!(categories.get(&ErrorCategory::Capitalization), Some(&2));
+        assert_eq!(categories.get(&ErrorCategory::Other), Some(&1));
+        assert_eq!(session.finalize_session().error_categories, categories);
+    }
+
+    #[test]
+    fn test_capitalization_errors_counted_separately() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("aBc")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        // 句中的大写字母输成小写：不是句首，但仍计为大小写错误
+        for ch in "abx".chars() {
+            session.handle_keystroke(ch);
+        }
+
+        assert_eq!(session.get_capitalization_errors(), 1);
+        let stats = session.finalize_session();
+        assert_eq!(stats.exercise_stats[0].capitalization_errors, 1);
+        assert_eq!(stats.exercise_stats[0].error_count, 2);
+        assert_eq!(
+            stats.error_categories.get(&ErrorCategory::Capitalization),
+            None
+        );
+    }
+
+    #[test]
+    fn test_finger_stats() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ad; k")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        // 左小指输错，标点和空格不计入
+        for ch in "sd; k".chars() {
+            session.handle_keystroke(ch);
+        }
+
+        let fingers = session.finalize_session().finger_stats;
+        assert_eq!(fingers.len(), 3);
+        assert_eq!(fingers[&Finger::LeftPinky], (1, 1));
+        assert_eq!(fingers[&Finger::LeftMiddle], (0, 1));
+        assert_eq!(fingers[&Finger::RightMiddle], (0, 1));
+    }
+
+    #[test]
+    fn test_combining_accent_is_one_position() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("cafe\u{301}!")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        // 组合附加符号与前面的字母算一个位置，凑齐后才前进
+        for ch in "cafe".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert_eq!(session.get_snapshot().cursor_position, 3);
+        session.handle_keystroke('\u{301}');
+        assert_eq!(session.get_snapshot().cursor_position, 4);
+        assert_eq!(session.remaining_text(), "!");
+
+        // 输错后按位置标记整个字素簇
+        session.handle_keystroke('?');
+        assert_eq!(session.get_snapshot().recent_errors, vec![4]);
+        session.handle_keystroke('\u{0008}');
+        session.handle_keystroke('!');
+
+        assert!(session.is_current_exercise_complete());
+        assert_eq!(session.get_input_text(), "cafe\u{301}!");
+        assert_eq!(session.generate_display_text(MemoryMode::Complete), "____!");
+    }
+
+    #[test]
+    fn test_accent_matches_across_normalization_forms() {
+        // 预组合的目标用组合附加符号分步输入
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("caf\u{e9}")];
+        let mut session = TypingSession::new(lesson.clone(), PracticeMode::Zen, None);
+        for ch in "cafe\u{301}".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.is_current_exercise_complete());
+        assert!(!session.current_exercise_has_errors());
+
+        // 分解形式的目标直接输入预组合字符
+        lesson.exercises = vec![Exercise::new("cafe\u{301}")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        for ch in "caf\u{e9}".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.is_current_exercise_complete());
+        assert!(!session.current_exercise_has_errors());
+    }
+
+    #[test]
+    fn test_family_emoji_is_one_position() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new(format!("a{family}b"))];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        session.handle_keystroke('a');
+
+        // 只输入了前缀时退格清除缓存，不删除已输入的内容
+        session.handle_keystroke('\u{1F468}');
+        session.handle_keystroke('\u{0008}');
+        assert_eq!(session.get_snapshot().cursor_position, 1);
+
+        for ch in family.chars() {
+            session.handle_keystroke(ch);
+        }
+        assert_eq!(session.get_snapshot().cursor_position, 2);
+        assert_eq!(session.peek_ahead(5), "b");
+
+        // 不同的 emoji 作为一次错误按键
+        session.handle_keystroke('\u{0008}');
+        session.handle_keystroke('\u{1F600}');
+        assert_eq!(session.get_snapshot().recent_errors, vec![1]);
+        session.handle_keystroke('b');
+        assert!(session.is_current_exercise_complete());
+
+        let stats = session.finalize_session();
+        assert_eq!(stats.exercise_stats[0].total_keystrokes, 4);
+    }
+
+    #[test]
+    fn test_completed_exercise_inputs() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab"), Exercise::new("cd")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        for ch in "ax".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.advance_to_next_exercise());
+        for ch in "cd".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(!session.advance_to_next_exercise());
+
+        assert_eq!(
+            session.get_completed_exercise_inputs(),
+            &[(0, "ax".to_string()), (1, "cd".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_pace_deviation_ahead_of_target() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        assert_eq!(session.pace_deviation(), None);
+
+        // 目标 1 WPM（5 字符/分钟），瞬间输入远快于目标节奏
+        session.set_pace_target_wpm(Some(1.0));
+        for ch in "hello".chars() {
+            session.handle_keystroke(ch);
+        }
+
+        let deviation = session.pace_deviation().expect("deviation");
+        assert!(deviation.mean > 2.5, "mean {}", deviation.mean);
+        assert!((deviation.mean_abs - deviation.mean).abs() < 1e-9);
+        assert_eq!(session.get_snapshot().pace_deviation, Some(deviation));
+    }
+
+    #[test]
+    fn test_preview_range() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("a".repeat(5000))];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        assert_eq!(session.preview_range(200), 0..400);
+        assert_eq!(session.preview_range(0), 0..5000);
+
+        for _ in 0..730 {
+            session.handle_keystroke('a');
+        }
+        assert_eq!(session.preview_range(200), 600..1000);
+
+        for _ in 730..4900 {
+            session.handle_keystroke('a');
+        }
+        assert_eq!(session.preview_range(200), 4800..5000);
+    }
+
+    #[test]
+    fn test_unreachable_positions() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("pay 5€ now\n你好")];
+        let session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        let layout = keyboard::KeyboardLayout::Qwerty;
+        assert_eq!(
+            session.unreachable_positions(|ch| layout.can_type(ch)),
+            vec![5, 11, 12]
+        );
+        assert_eq!(
+            session.unreachable_positions(|ch| layout.can_type(ch) || keyboard::is_ime_char(ch)),
+            vec![5]
+        );
+    }
+
+    #[test]
+    fn test_first_try_accuracy() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        session.set_input_mode(InputMode::Strict);
+
+        // 输错 'h' 被拒绝，纠正后重新输入正确
+        session.handle_keystroke('j');
+        for ch in "hello world".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.is_current_exercise_complete());
+
+        let snapshot = session.get_snapshot();
+        assert!((snapshot.accuracy - 11.0 / 12.0).abs() < 1e-9);
+        assert!((snapshot.first_try_accuracy - 10.0 / 11.0).abs() < 1e-9);
+        assert!(snapshot.accuracy > snapshot.first_try_accuracy);
+    }
+
+    #[test]
+    fn test_backspace_disabled() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        session.set_allow_backspace(false);
+
+        session.handle_keystroke('h');
+        session.handle_keystroke('x');
+        session.handle_keystroke('\u{0008}');
+
+        assert_eq!(session.current_position, 2);
+        assert_eq!(session.get_input_text(), "hx");
+        assert_eq!(session.total_keystrokes, 2);
+    }
+
+    #[test]
+    fn test_recent_accuracy_series() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("a".repeat(30))];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        assert!(session.get_recent_accuracy_series(5).is_empty());
+
+        // 先全对，再连续输错
+        for _ in 0..10 {
+            session.handle_keystroke('a');
+        }
+        for _ in 0..5 {
+            session.handle_keystroke('x');
+        }
+
+        let series = session.get_recent_accuracy_series(100);
+        assert_eq!(series.len(), 15);
+        assert_eq!(series[9], 1.0);
+        assert_eq!(series[14], 0.5);
+        assert!(series[10..].windows(2).all(|w| w[1] < w[0]));
+
+        assert_eq!(session.get_recent_accuracy_series(3), &series[12..]);
+    }
+
+    #[test]
+    fn test_lesson_goal_not_met() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("abcd")];
+        lesson.meta.target_accuracy = Some(0.98);
+        let mut session = TypingSession::new(lesson.clone(), PracticeMode::Zen, None);
+
+        session.handle_keystroke('a');
+        session.handle_keystroke('x');
+        for ch in "bcd".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.is_current_exercise_complete());
+        assert_eq!(session.meets_lesson_goal(), Some(false));
+
+        // 未设置目标时不判断
+        lesson.meta.target_accuracy = None;
+        let session = TypingSession::new(lesson.clone(), PracticeMode::Zen, None);
+        assert_eq!(session.meets_lesson_goal(), None);
+
+        assert_eq!(lesson.meta.goal_met(60.0, 1.0), None);
+        lesson.meta.target_wpm = Some(50.0);
+        lesson.meta.target_accuracy = Some(0.98);
+        assert_eq!(lesson.meta.goal_met(60.0, 0.99), Some(true));
+        assert_eq!(lesson.meta.goal_met(40.0, 0.99), Some(false));
+    }
+
+    #[test]
+    fn test_extract_weak_units_from() {
+        // "the cat the dog the end"：第 2 个 "the" 出错
+        let target = "the cat the dog the end";
+        let errors: HashSet<usize> = [8].into_iter().collect();
+        let units = extract_weak_units_from(
+            target,
+            &errors,
+            "en-US",
+            LessonType::Prose,
+            &WeakUnitConfig::default(),
+        );
+
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].content, "the");
+        assert_eq!(units[0].unit_type, UnitType::Word);
+        assert_eq!(units[0].error_count, 1);
+        assert_eq!(units[0].total_count, 3);
+
+        // 其他语言按字符统计
+        let units = extract_weak_units_from(
+            "aaab",
+            &[0].into_iter().collect(),
+            "unknown",
+            LessonType::Prose,
+            &WeakUnitConfig::default(),
+        );
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].content, "a");
+        assert_eq!(units[0].unit_type, UnitType::Character);
+
+        assert!(extract_weak_units_from(
+            target,
+            &HashSet::new(),
+            "en-US",
+            LessonType::Prose,
+            &WeakUnitConfig::default()
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_code_token_weak_units() {
+        let target = "let x = foo(a); let y = foo(b); foo(\"hi there\");";
+        let graphemes: Vec<&str> = target.graphemes(true).collect();
+        let tokens: Vec<String> = code_token_spans(&graphemes)
+            .into_iter()
+            .map(|span| graphemes[span].concat())
+            .collect();
+        assert_eq!(
+            tokens,
+            [
+                "let",
+                "x",
+                "=",
+                "foo",
+                "(",
+                "a",
+                ")",
+                ";",
+                "let",
+                "y",
+                "=",
+                "foo",
+                "(",
+                "b",
+                ")",
+                ";",
+                "foo",
+                "(",
+                "\"hi there\"",
+                ")",
+                ";"
+            ]
+        );
+
+        // 第二个 foo 的 "o" 输错：错误归到整个 token
+        let errors: HashSet<usize> = [26].into_iter().collect();
+        let config = WeakUnitConfig {
+            min_occurrences: 1,
+            ..WeakUnitConfig::default()
+        };
+        let units = extract_weak_units_from(target, &errors, "en-US", LessonType::Code, &config);
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].content, "foo");
+        assert_eq!(units[0].unit_type, UnitType::Token);
+        assert_eq!(units[0].error_count, 1);
+        assert_eq!(units[0].total_count, 3);
+
+        // 生命周期不是字符字面量
+        let target = "f(s: &'a str, b: &'b u8, c: '\\'', d: 'x')";
+        let graphemes: Vec<&str> = target.graphemes(true).collect();
+        let tokens: Vec<String> = code_token_spans(&graphemes)
+            .into_iter()
+            .map(|span| graphemes[span].concat())
+            .collect();
+        assert_eq!(
+            tokens,
+            [
+                "f", "(", "s", ":", "&", "'a", "str", ",", "b", ":", "&", "'b", "u8", ",", "c",
+                ":", "'\\''", ",", "d", ":", "'x'", ")"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weak_unit_config() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("cat dog")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        for ch in "cxt dog".chars() {
+            session.handle_keystroke(ch);
+        }
+
+        // 默认要求至少出现 3 次，短文本没有薄弱单元
+        assert!(session.finalize_session().weak_units.is_empty());
+
+        session.set_weak_unit_config(WeakUnitConfig {
+            min_occurrences: 1,
+            ..WeakUnitConfig::default()
+        });
+        let units = session.finalize_session().weak_units;
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].content, "cat");
+        assert_eq!(units[0].total_count, 1);
+    }
+
+    #[test]
+    fn test_snapshot_cached_between_keystrokes() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        for ch in "hel".chars() {
+            session.handle_keystroke(ch);
+            std::thread::sleep(Duration::from_millis(60));
+        }
+
+        // 没有新输入时重复获取不重新计算（WPM 不随时间变化）
+        let first = session.get_snapshot();
+        std::thread::sleep(Duration::from_millis(50));
+        let second = session.get_snapshot();
+        assert!(first.current_wpm > 0.0);
+        assert_eq!(first.current_wpm, second.current_wpm);
+
+        session.handle_keystroke('l');
+        let third = session.get_snapshot();
+        assert_eq!(third.cursor_position, 4);
+        assert_ne!(third.current_wpm, first.current_wpm);
+    }
+
+    #[test]
+    fn test_partial_mask_stable_within_exercise() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new(
+            "the quick brown fox jumps over the lazy dog and runs away",
+        )];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        let mode = MemoryMode::Partial(PartialLevel::Medium);
+
+        // 重复渲染和输入不改变隐藏位置
+        let first = session.generate_display_text(mode);
+        assert_eq!(session.generate_display_text(mode), first);
+        for ch in "the qu".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert_eq!(session.generate_display_text(mode), first);
+
+        // 主动重新随机后隐藏的单词数不变
+        session.reshuffle_memory_mask();
+        let hidden = |text: &str| text.split(' ').filter(|w| w.contains('_')).count();
+        assert_eq!(hidden(&session.generate_display_text(mode)), hidden(&first));
+    }
+
+    #[test]
+    fn test_restart_regenerates_exercise() {
+        let words: Vec<String> = (0..100).map(|i| format!("word{}", i)).collect();
+        let mut lesson = create_test_lesson();
+        lesson.regenerate_on_restart = Some(ExerciseGenerator::SampleWords {
+            words: words.clone(),
+            count: 20,
+        });
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        session.handle_keystroke('h');
+
+        session.restart_current_exercise();
+        let first = session.get_target_text().to_string();
+        assert_eq!(session.get_input_text(), "");
+        session.restart_current_exercise();
+        let second = session.get_target_text().to_string();
+
+        assert_ne!(first, second);
+        for text in [&first, &second] {
+            let sampled: Vec<&str> = text.split(' ').collect();
+            assert_eq!(sampled.len(), 20);
+            assert!(sampled.iter().all(|w| words.iter().any(|word| word == w)));
+        }
+
+        // 未设置生成规则时内容不变
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        session.restart_current_exercise();
+        assert_eq!(session.get_target_text(), "hello world");
+    }
+
+    #[test]
+    fn test_display_text_cached_per_exercise() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab cd"), Exercise::new("ef")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        assert_eq!(session.generate_display_text(MemoryMode::Off), "ab cd");
+        session.handle_keystroke('a');
+        assert!(session.display_cache.borrow().is_some());
+
+        // 切换记忆模式时失效，不同模式重新生成
+        session.set_memory_mode(MemoryMode::Complete);
+        assert!(session.display_cache.borrow().is_none());
+        assert_eq!(session.generate_display_text(MemoryMode::Complete), "__ __");
+
+        // 切换练习时失效
+        session.advance_to_next_exercise();
+        assert!(session.display_cache.borrow().is_none());
+        assert_eq!(session.generate_display_text(MemoryMode::Off), "ef");
+    }
+
+    #[test]
+    fn test_memory_ramp_increases_hiding() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("abc"), Exercise::new("xyz")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        session.set_memory_ramp(Some(0.9));
+
+        let mut ratios = Vec::new();
+        loop {
+            ratios.push(session.ramp_level().unwrap().hide_ratio());
+            for ch in "abc".chars() {
+                session.handle_keystroke(ch);
+            }
+            session.advance_to_next_exercise();
+            if session.current_lesson_exercise_index() != 0 {
+                break;
+            }
+        }
+
+        assert_eq!(ratios, vec![0.0, 0.3, 0.5, 0.7, 1.0]);
+        assert!(!session.ramp_will_repeat());
+        assert_eq!(session.generate_display_text(MemoryMode::Off), "xyz");
+        assert_eq!(session.get_progress(), (1, 2));
+        assert!(session.get_snapshot().progress < 0.6);
+
+        // 准确率低于阈值时不提高级别，直接进入下一个练习
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab"), Exercise::new("cd")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        session.set_memory_ramp(Some(0.9));
+        for ch in "xab".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.advance_to_next_exercise());
+        assert_eq!(session.current_lesson_exercise_index(), 1);
+        assert_eq!(session.ramp_level(), Some(MemoryMode::Off));
+    }
+
+    #[test]
+    fn test_is_idle_after_gap() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        session.set_idle_threshold(Some(Duration::from_millis(50)));
+        assert!(!session.is_idle()); // 尚未开始
+
+        session.handle_keystroke('h');
+        assert!(!session.is_idle());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(session.is_idle());
+
+        session.handle_keystroke('e');
+        assert!(!session.is_idle());
+    }
+
+    #[test]
+    fn test_literal_space_between_chinese_words() {
+        let mut lesson = create_test_lesson();
+        lesson.language = "zh-CN".to_string();
+        lesson.exercises = vec![Exercise::new("你好 世界")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        assert!(session.is_cjk_language());
+
+        // 输入法提交 "你好"，空格作为普通按键输入，再提交 "世界"
+        for ch in "你好".chars() {
+            session.handle_keystroke(ch);
+        }
+        session.handle_keystroke(' ');
+        for ch in "世界".chars() {
+            session.handle_keystroke(ch);
+        }
+
+        assert!(session.is_current_exercise_complete());
+        assert!(!session.current_exercise_has_errors());
+    }
+
+    #[test]
+    fn test_manual_auto_advance() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab"), Exercise::new("cd")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        for ch in "ab".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.should_auto_advance());
+
+        session.set_auto_advance(AutoAdvance::Manual);
+        assert!(!session.should_auto_advance());
+        assert_eq!(session.get_progress(), (0, 2));
+
+        // 显式继续后才进入下一个练习
+        assert!(session.advance_or_finish());
+        assert_eq!(session.get_progress(), (1, 2));
+        assert!(!session.advance_or_finish()); // 未完成时不跳转
+
+        for ch in "cd".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(!session.is_lesson_finished());
+        assert!(session.advance_or_finish());
+        assert!(session.is_lesson_finished());
+    }
+
+    #[test]
+    fn test_cursor_line_col() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab\ncd\nef")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        assert_eq!(session.cursor_line_col(), (0, 0));
+
+        session.handle_keystroke('a');
+        session.handle_keystroke('b');
+        assert_eq!(session.cursor_line_col(), (0, 2));
+
+        // 换行符之后
+        session.handle_keystroke('\n');
+        assert_eq!(session.cursor_line_col(), (1, 0));
+
+        for ch in "cd\ne".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert_eq!(session.cursor_line_col(), (2, 1));
+    }
+
+    #[test]
+    fn test_longest_flow() {
+        // 平均 50 WPM：10-40 秒持续 60 WPM 且准确率高，但 25 秒时准确率下降
+        let wpm: Vec<(f64, f64)> = (0..=60)
+            .map(|t| (t as f64, if (10..=40).contains(&t) { 60.0 } else { 35.0 }))
+            .collect();
+        let accuracy: Vec<(f64, f64)> = (0..=60)
+            .map(|t| (t as f64, if t == 25 { 0.8 } else { 1.0 }))
+            .collect();
+
+        assert_eq!(longest_flow(&wpm, &accuracy), Duration::from_secs(14));
+        assert_eq!(longest_flow(&[], &[]), Duration::ZERO);
+
+        // 准确率一直很高时整段都算
+        let steady: Vec<(f64, f64)> = (0..=60).map(|t| (t as f64, 1.0)).collect();
+        assert_eq!(longest_flow(&wpm, &steady), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_wpm_series_bounded() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("a".repeat(500))];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        session.set_wpm_series_capacity(16);
+
+        for _ in 0..500 {
+            session.handle_keystroke('a');
+        }
+
+        let series = session.get_wpm_series();
+        assert!(!series.is_empty());
+        assert!(series.len() <= 16);
+        assert!(series.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+
+    #[test]
+    fn test_skip_mastered_exercises() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![
+            Exercise::new("a".to_string()),
+            Exercise::new("b".to_string()),
+            Exercise::new("c".to_string()),
+        ];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        assert!(session.skip_mastered_exercises(&HashSet::from([0, 2])));
+        assert_eq!(session.get_exercise_order(), &[1]);
+        assert_eq!(session.get_current_exercise().content, "b");
+
+        // 全部已掌握时不跳过
+        assert!(!session.skip_mastered_exercises(&HashSet::from([1])));
+        assert_eq!(session.get_exercise_order(), &[1]);
+    }
+
+    #[test]
+    fn test_exercise_completed_events() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![
+            Exercise::new("ab".to_string()),
+            Exercise::new("c".to_string()),
+        ];
+        let (tx, rx) = mpsc::channel();
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, Some(tx));
+        session.set_exercise_order(vec![1, 0]);
+
+        session.handle_keystroke('c');
+        session.advance_to_next_exercise();
+        session.handle_keystroke('a');
+        session.handle_keystroke('b');
+
+        let completed: Vec<(usize, usize)> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                TypingEvent::ExerciseCompleted { index, stats } => {
+                    Some((index, stats.exercise_index))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(completed, vec![(1, 1), (0, 0)]);
+    }
+
+    #[test]
+    fn test_word_boundary() {
+        let mut lesson = create_test_lesson();
+        lesson.lesson_type = LessonType::Chinese;
+        lesson.language = "zh-CN".to_string();
+        lesson.exercises = vec![Exercise::new("你好，世界。")];
+        let word_events = |boundary: Option<WordBoundary>| {
+            let (tx, rx) = mpsc::channel();
+            let mut session = TypingSession::new(lesson.clone(), PracticeMode::Zen, Some(tx));
+            session.set_word_boundary(boundary);
+            for ch in "你好，世界。".chars() {
+                session.handle_keystroke(ch);
+            }
+            rx.try_iter()
+                .filter(|event| matches!(event, TypingEvent::WordCompleted { .. }))
+                .count()
+        };
+
+        // 中文默认逐字（标点不计），按标点时每个短语一次
+        assert_eq!(word_events(None), 4);
+        assert_eq!(word_events(Some(WordBoundary::Punctuation)), 2);
+        assert_eq!(word_events(Some(WordBoundary::Whitespace)), 0);
+
+        let (tx, rx) = mpsc::channel();
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, Some(tx));
+        assert_eq!(session.word_boundary(), WordBoundary::Whitespace);
+        for ch in "hello world".chars() {
+            session.handle_keystroke(ch);
+        }
+        let words = rx
+            .try_iter()
+            .filter(|event| matches!(event, TypingEvent::WordCompleted { .. }))
+            .count();
+        assert_eq!(words, 1);
+    }
+
+    #[test]
+    fn test_delete_word() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("hello world")];
+        let (tx, rx) = mpsc::channel();
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, Some(tx));
+
+        for ch in "hello wxxl".chars() {
+            session.handle_keystroke(ch);
+        }
+        session.handle_keystroke(DELETE_WORD);
+        assert_eq!(session.current_position, 6);
+        assert_eq!(session.get_input_text(), "hello ");
+        assert!(session.error_positions.is_empty());
+
+        let mut corrected: Vec<usize> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                TypingEvent::ErrorCorrected { position } => Some(position),
+                _ => None,
+            })
+            .collect();
+        corrected.sort_unstable();
+        assert_eq!(corrected, vec![7, 8]);
+
+        // 光标在空格后时连同前一个单词一起删除
+        session.handle_keystroke(DELETE_WORD);
+        assert_eq!(session.current_position, 0);
+        assert_eq!(session.get_input_text(), "");
+    }
+
+    #[test]
+    fn test_delete_word_cjk_falls_back_to_single_char() {
+        let mut lesson = create_test_lesson();
+        lesson.language = "zh-CN".to_string();
+        lesson.exercises = vec![Exercise::new("你好世界")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        for ch in "你好世".chars() {
+            session.handle_keystroke(ch);
+        }
+        session.handle_keystroke(DELETE_WORD);
+        assert_eq!(session.get_input_text(), "你好");
+    }
+
+    #[test]
+    fn test_strict_completion_in_invisible_mode() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab".to_string())];
+
+        let mut session = TypingSession::new(lesson.clone(), PracticeMode::Zen, None);
+        session.set_input_mode(InputMode::Invisible);
+        session.handle_keystroke('a');
+        session.handle_keystroke('x');
+        assert!(session.is_current_exercise_complete());
+
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        session.set_input_mode(InputMode::Invisible);
+        session.set_strict_completion(true);
+        session.handle_keystroke('a');
+        session.handle_keystroke('x');
+        assert!(!session.is_current_exercise_complete());
+        session.handle_keystroke('b');
+        assert!(session.is_current_exercise_complete());
+
+        // 输错的按键也计入字符按键次数
+        let counts = session.finalize_session().char_counts;
+        assert_eq!(counts, HashMap::from([('a', 1), ('x', 1), ('b', 1)]));
+    }
+
+    #[test]
+    fn test_perfect_practice_repeats_until_perfect() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![
+            Exercise::new("ab".to_string()),
+            Exercise::new("c".to_string()),
+        ];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        session.set_perfect_practice(true);
+
+        // 第一次输错后纠正：完成时自动重来
+        session.handle_keystroke('x');
+        session.handle_keystroke('\u{0008}');
+        session.handle_keystroke('a');
+        session.handle_keystroke('b');
+        assert!(!session.is_current_exercise_complete());
+        assert_eq!(session.current_attempt(), 2);
+        assert_eq!(session.get_input_text(), "");
+
+        // 第二次无错完成
+        session.handle_keystroke('a');
+        session.handle_keystroke('b');
+        assert!(session.is_current_exercise_complete());
+        assert!(session.should_auto_advance());
+
+        assert!(session.advance_to_next_exercise());
+        assert_eq!(session.get_current_exercise().content, "c");
+        assert_eq!(session.current_attempt(), 1);
+        assert_eq!(
+            session.finalize_session().exercise_stats[0].attempts_to_perfect,
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_consistency_from_intervals() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        for _ in 0..CONSISTENCY_MIN_INTERVALS - 1 {
+            session.record_keystroke_interval(Duration::from_millis(200));
+        }
+        assert_eq!(session.consistency(), None);
+
+        // 间隔完全均匀时稳定度为 1，停顿不计入
+        session.record_keystroke_interval(Duration::from_millis(200));
+        session.record_keystroke_interval(Duration::from_secs(5));
+        assert!((session.consistency().unwrap() - 1.0).abs() < 1e-9);
+
+        // 间隔忽快忽慢时稳定度下降
+        for ms in [50, 400, 50, 400] {
+            session.record_keystroke_interval(Duration::from_millis(ms));
+        }
+        assert!(session.consistency().unwrap() < 0.8);
+    }
+
+    #[test]
+    fn test_timer_starts_on_show() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises.push(Exercise::new("again"));
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        session.set_timer_start(TimerStart::OnShow);
+        assert!(session.is_awaiting_start());
+
+        // 展示前的按键被忽略，也不启动计时
+        session.handle_keystroke('h');
+        assert_eq!(session.get_input_text(), "");
+        assert!(session.exercise_elapsed().is_none());
+
+        session.mark_shown();
+        assert!(!session.is_awaiting_start());
+        assert!(session.exercise_elapsed().is_some());
+
+        for ch in "hello world".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.advance_to_next_exercise());
+
+        // 下一个练习需要重新展示
+        assert!(session.is_awaiting_start());
+        assert!(session.exercise_elapsed().is_none());
+    }
+
+    #[test]
+    fn test_tab_stop_jumps_to_next_column() {
+        let mut lesson = create_test_lesson();
+        lesson.lesson_type = LessonType::Code;
+        lesson.exercises = vec![Exercise::new("if x:\n        y = 1")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        session.set_tab_stop(Some(4));
+
+        for ch in "if x:\n  ".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert_eq!(session.cursor_line_col(), (1, 2));
+
+        // 从第 2 列跳到第 4 列，再跳到第 8 列
+        session.handle_keystroke('\t');
+        assert_eq!(session.cursor_line_col(), (1, 4));
+        session.handle_keystroke('\t');
+        assert_eq!(session.cursor_line_col(), (1, 8));
+        // 越过的 6 个空格都计入按键数和速度
+        assert_eq!(session.total_keystrokes, 14);
+        assert_eq!(session.correct_keystrokes, 14);
+        assert_eq!(
+            session.recent_correct_keystrokes(Instant::now()).count(),
+            14
+        );
+
+        // 目标不是空格时 Tab 仍按普通字符判断
+        session.handle_keystroke('\t');
+        assert!(session.current_exercise_has_errors());
+        session.handle_keystroke('\u{0008}');
+
+        for ch in "y = 1".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.is_current_exercise_complete());
+        assert_eq!(session.get_input_text(), "if x:\n        y = 1");
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_short_session_not_saved() {
+        let db = Database::new(":memory:").unwrap();
+
+        let finished_session = |elapsed: Duration| {
+            let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+            session.set_min_session_duration(Duration::from_secs(5));
+            for ch in "hello world".chars() {
+                session.handle_keystroke(ch);
+            }
+            session.exercise_start_time = Instant::now().checked_sub(elapsed);
+            session
+        };
+
+        let short = finished_session(Duration::from_secs(2));
+        assert_eq!(short.save_to_database(&db).unwrap(), None);
+        assert!(db.get_recent_sessions(10).unwrap().is_empty());
+
+        let long = finished_session(Duration::from_secs(10));
+        assert!(long.save_to_database(&db).unwrap().is_some());
+        assert_eq!(db.get_recent_sessions(10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_exercise_statuses() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = ["one", "two", "three", "four"]
+            .into_iter()
+            .map(Exercise::new)
+            .collect();
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+
+        for ch in "one".chars() {
+            session.handle_keystroke(ch);
+        }
+        session.advance_to_next_exercise();
+        // 未完成第二个练习就跳到第三个
+        session.go_to_next_exercise();
+
+        let statuses = session.exercise_statuses();
+        assert_eq!(statuses[0], ExerciseStatus::Completed(1.0));
+        assert_eq!(statuses[1], ExerciseStatus::Skipped);
+        assert_eq!(statuses[2], ExerciseStatus::Current);
+        assert_eq!(statuses[3], ExerciseStatus::NotStarted);
+    }
+
+    #[test]
+    fn test_exercise_overrides_input_mode() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![
+            Exercise::new("ab"),
+            Exercise::new("cd")
+                .with_input_mode(InputMode::Strict)
+                .with_memory_mode(MemoryMode::Complete),
+        ];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        assert_eq!(session.current_input_mode(), InputMode::Forgiving);
+        assert_eq!(session.generate_display_text(MemoryMode::Off), "ab");
+
+        for ch in "ab".chars() {
+            session.handle_keystroke(ch);
+        }
+        session.advance_to_next_exercise();
+        assert_eq!(session.current_input_mode(), InputMode::Strict);
+        assert_eq!(session.get_input_mode(), InputMode::Forgiving);
+        assert_ne!(session.generate_display_text(MemoryMode::Off), "cd");
+
+        // 严格模式：输错不前进
+        session.handle_keystroke('x');
+        assert_eq!(session.get_snapshot().cursor_position, 0);
+    }
+
+    #[test]
+    fn test_debug_dump() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises.push(Exercise::new("second"));
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        for ch in "hello world".chars() {
+            session.handle_keystroke(ch);
+        }
+        session.advance_to_next_exercise();
+        for ch in "sx".chars() {
+            session.handle_keystroke(ch);
+        }
+
+        let dump: serde_json::Value = serde_json::from_str(&session.debug_dump()).unwrap();
+        assert_eq!(dump["lesson_id"], 1);
+        assert_eq!(dump["exercise_index"], 1);
+        assert_eq!(dump["position"], 2);
+        assert_eq!(dump["input_text"], "sx");
+        assert_eq!(dump["error_positions"], serde_json::json!([1]));
+    }
+
+    #[test]
+    fn test_time_limit_stops_input() {
+        let (tx, rx) = mpsc::channel();
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Timed, Some(tx));
+        session.set_time_limit(Some(Duration::from_secs(60)));
+
+        // 首次按键前不计时
+        assert_eq!(session.time_remaining(), Some(Duration::from_secs(60)));
+        session.handle_keystroke('h');
+        assert!(session.get_snapshot().time_remaining.unwrap() <= Duration::from_secs(60));
+
+        session.session_start_time = Instant::now().checked_sub(Duration::from_secs(61));
+        assert_eq!(session.time_remaining(), Some(Duration::ZERO));
+        session.handle_keystroke('e');
+        assert_eq!(session.get_input_text(), "h");
+        assert!(session.is_time_up());
+
+        let completed = rx
+            .try_iter()
+            .filter(|event| matches!(event, TypingEvent::SessionCompleted { .. }))
+            .count();
+        assert_eq!(completed, 1);
+    }
+
+    #[test]
+    fn test_finished_before_time_limit() {
+        let (tx, rx) = mpsc::channel();
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Timed, Some(tx));
+        session.set_time_limit(Some(Duration::from_secs(60)));
+        for ch in "hello world".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.is_current_exercise_complete());
+
+        session.session_start_time = Instant::now().checked_sub(Duration::from_secs(61));
+        assert!(!session.check_time_limit());
+
+        // 练习完成时已正常结束，到时不再重复发送
+        let completed = rx
+            .try_iter()
+            .filter(|event| matches!(event, TypingEvent::SessionCompleted { .. }))
+            .count();
+        assert_eq!(completed, 1);
+    }
+
+    #[test]
+    fn test_session_completed_emitted_once() {
+        let (tx, rx) = mpsc::channel();
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, Some(tx));
+        let start = Instant::now();
+        for (i, ch) in "hello world".chars().enumerate() {
+            session.handle_keystroke_at(ch, start + Duration::from_millis(200 * i as u64));
+        }
+        session.advance_or_finish();
+        session.check_time_limit();
+
+        let completed: Vec<SessionStats> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                TypingEvent::SessionCompleted { stats } => Some(stats),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(completed.len(), 1);
+
+        // 事件中的统计与之后保存的一致
+        let saved = session.finalize_session();
+        assert_eq!(completed[0].duration_secs, saved.duration_secs);
+        assert_eq!(completed[0].total_keystrokes, saved.total_keystrokes);
+        assert_eq!(completed[0].overall_wpm, saved.overall_wpm);
+    }
+
+    #[test]
+    fn test_whitespace_policy_by_lesson_type() {
+        let lesson_with = |lesson_type: LessonType| {
+            let mut lesson = create_test_lesson();
+            lesson.lesson_type = lesson_type;
+            lesson.exercises = vec![Exercise::new("a  b")];
+            lesson
+        };
+
+        // 散文：一个空格匹配连续的两个空格，按原样输入两个空格同样正确
+        for typed in ["a b", "a  b"] {
+            let mut prose =
+                TypingSession::new(lesson_with(LessonType::Prose), PracticeMode::Zen, None);
+            for ch in typed.chars() {
+                prose.handle_keystroke(ch);
+            }
+            assert!(prose.is_current_exercise_complete(), "{:?}", typed);
+            assert!(!prose.current_exercise_has_errors(), "{:?}", typed);
+            assert_eq!(prose.get_input_text(), "a  b");
+        }
+
+        // 代码：必须输入相同数量的空格
+        let mut code = TypingSession::new(lesson_with(LessonType::Code), PracticeMode::Zen, None);
+        for ch in "a b".chars() {
+            code.handle_keystroke(ch);
+        }
+        assert!(code.current_exercise_has_errors());
+
+        // 全局覆盖课程类型的默认策略
+        let mut strict_prose =
+            TypingSession::new(lesson_with(LessonType::Prose), PracticeMode::Zen, None);
+        strict_prose.set_whitespace_policy(Some(WhitespacePolicy::Exact));
+        for ch in "a  b".chars() {
+            strict_prose.handle_keystroke(ch);
+        }
+        assert!(strict_prose.is_current_exercise_complete());
+        assert!(!strict_prose.current_exercise_has_errors());
+    }
+
+    #[test]
+    fn test_endless_mode_loops() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab"), Exercise::new("cd")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Endless, None);
+
+        for text in ["ab", "cd", "ax"] {
+            for ch in text.chars() {
+                session.handle_keystroke(ch);
+            }
+            if session.is_current_exercise_complete() && !session.current_exercise_has_errors() {
+                assert!(session.should_auto_advance());
+                assert!(session.advance_to_next_exercise());
+            }
+        }
+
+        // 最后一个练习之后回到第一个
+        assert_eq!(session.total_loops(), 1);
+        assert_eq!(session.current_lesson_exercise_index(), 0);
+        assert!(session.get_snapshot().progress < 1.0);
+
+        // 同一练习的两次完成分别计入
+        let stats = session.finalize_session();
+        assert_eq!(stats.exercise_stats.len(), 3);
+        assert_eq!(stats.total_keystrokes, 6);
+        assert_eq!(stats.error_count, 1);
+        assert!((stats.overall_accuracy - 5.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_peak_wpm_above_average() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises[0].content = "the quick brown fox jumps".to_string();
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        let start = Instant::now().checked_sub(Duration::from_secs(60)).unwrap();
+
+        // 前 12 个字符快速连打，之后每秒一个
+        for (i, ch) in "the quick brown fox jumps".chars().enumerate() {
+            let offset = if i < 12 {
+                Duration::from_millis(50 * i as u64)
+            } else {
+                Duration::from_secs(i as u64)
+            };
+            session.handle_keystroke_at(ch, start + offset);
+        }
+
+        let stats = session.finalize_session();
+        assert!(session.peak_wpm() > 100.0);
+        assert!(stats.peak_wpm > stats.overall_wpm);
+        assert_eq!(stats.exercise_stats[0].peak_wpm, stats.peak_wpm);
+        assert_eq!(session.get_snapshot().peak_wpm, stats.peak_wpm);
+    }
+
+    #[test]
+    fn test_peak_wpm_ignores_short_window() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        let start = Instant::now();
+
+        // 0.4 秒内的连打不足 0.5 秒窗口，不计入峰值
+        for (i, ch) in "hello".chars().enumerate() {
+            session.handle_keystroke_at(ch, start + Duration::from_millis(100 * i as u64));
+        }
+        assert_eq!(session.peak_wpm(), 0.0);
+
+        session.handle_keystroke_at(' ', start + Duration::from_millis(600));
+        assert!(session.peak_wpm() > 0.0);
+    }
+
+    #[test]
+    fn test_peak_wpm_counts_intervals() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        let start = Instant::now();
+
+        // 每 100 毫秒一个字符：600 CPM，即 120 WPM
+        for (i, ch) in "hello world".chars().enumerate() {
+            session.handle_keystroke_at(ch, start + Duration::from_millis(100 * i as u64));
+        }
+        assert!((session.peak_wpm() - 120.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_cjk_net_wpm_excludes_punctuation() {
+        let mut lesson = create_test_lesson();
+        lesson.language = "zh-CN".to_string();
+        lesson.exercises = vec![Exercise::new("你好，世界! ok。")];
+        let start = Instant::now().checked_sub(Duration::from_secs(30)).unwrap();
+
+        let type_all = |session: &mut TypingSession| {
+            for (i, ch) in "你好，世界! ok。".chars().enumerate() {
+                session.handle_keystroke_at(ch, start + Duration::from_millis(500 * i as u64));
+            }
+            session.finalize_session()
+        };
+
+        let mut session = TypingSession::new(lesson.clone(), PracticeMode::Zen, None);
+        let all = type_all(&mut session);
+        assert_eq!(all.exercise_stats[0].excluded_from_wpm, 0);
+
+        // 只计汉字：标点、空格和拉丁字母都不计入
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        session.set_cjk_net_wpm(true);
+        let net = type_all(&mut session);
+        assert_eq!(net.exercise_stats[0].excluded_from_wpm, 6);
+        let ratio = net.exercise_stats[0].wpm / all.exercise_stats[0].wpm;
+        assert!((ratio - 4.0 / 10.0).abs() < 1e-9);
+        assert!(net.overall_wpm < all.overall_wpm);
+    }
+
+    #[test]
+    fn test_latency_profile() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("a".repeat(120))];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        assert_eq!(session.latency_profile(), None);
+
+        // 90 个 100ms、9 个 300ms、1 个 1s 的间隔，中间有一次 5 秒停顿（不计入）
+        let mut gaps = vec![100; 90];
+        gaps.extend([300; 9]);
+        gaps.push(1000);
+        gaps.insert(50, 5000);
+
+        let mut now = Instant::now().checked_sub(Duration::from_secs(60)).unwrap();
+        session.handle_keystroke_at('a', now);
+        for gap in gaps {
+            now += Duration::from_millis(gap);
+            session.handle_keystroke_at('a', now);
+        }
+
+        let latency = session.finalize_session().latency.unwrap();
+        assert_eq!(latency.p50, Duration::from_millis(100));
+        assert_eq!(latency.p95, Duration::from_millis(300));
+        assert_eq!(latency.p99, Duration::from_millis(300));
+    }
+
+    #[cfg(feature = "replay")]
+    #[test]
+    fn test_replay_round_trip() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab cd"), Exercise::new("hello")];
+        let mut session = TypingSession::new(lesson.clone(), PracticeMode::Zen, None);
+
+        let mut now = Instant::now().checked_sub(Duration::from_secs(30)).unwrap();
+        for ch in "ax\u{8}b cx".chars() {
+            now += Duration::from_millis(150);
+            session.handle_keystroke_at(ch, now);
+        }
+        assert!(session.advance_to_next_exercise());
+        for ch in "hwl".chars() {
+            now += Duration::from_millis(150);
+            session.handle_keystroke_at(ch, now);
+        }
+
+        let replay = session.export_replay();
+        assert_eq!(replay.len(), 10);
+        assert_eq!(replay[0].offset, Duration::ZERO);
+        assert!(!replay[2].correct);
+        assert_eq!((replay[7].exercise, replay[7].completed), (1, 1));
+
+        let json = serde_json::to_string(&replay).unwrap();
+        let loaded: Vec<ReplayEvent> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, replay);
+
+        let rebuilt = TypingSession::from_replay(lesson, PracticeMode::Zen, &loaded);
+        assert_eq!(rebuilt.export_replay(), replay);
+        assert_eq!(rebuilt.current_exercise_index, 1);
+        assert_eq!(rebuilt.current_position, session.current_position);
+        assert_eq!(rebuilt.error_positions, session.error_positions);
+        assert_eq!(rebuilt.total_keystrokes, session.total_keystrokes);
+        assert_eq!(rebuilt.correct_keystrokes, session.correct_keystrokes);
+        let (original, rebuilt) = (&session.exercise_stats[0], &rebuilt.exercise_stats[0]);
+        assert_eq!(rebuilt.total_keystrokes, original.total_keystrokes);
+        assert_eq!(rebuilt.error_count, original.error_count);
+        assert_eq!(rebuilt.accuracy, original.accuracy);
+        assert_eq!(rebuilt.wpm, original.wpm);
+    }
+
+    #[cfg(feature = "replay")]
+    #[test]
+    fn test_replay_follows_manual_navigation() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![
+            Exercise::new("ab"),
+            Exercise::new("cd"),
+            Exercise::new("ef"),
+        ];
+        let mut session = TypingSession::new(lesson.clone(), PracticeMode::Zen, None);
+        session.set_exercise_order(vec![2, 0, 1]);
+
+        // 从第三个位置继续，再手动后退一个练习
+        let mut now = Instant::now().checked_sub(Duration::from_secs(30)).unwrap();
+        assert!(session.go_to_exercise(1));
+        session.handle_keystroke_at('c', now);
+        assert!(session.go_to_previous_exercise());
+        for ch in "ax".chars() {
+            now += Duration::from_millis(100);
+            session.handle_keystroke_at(ch, now);
+        }
+
+        let replay = session.export_replay();
+        assert_eq!((replay[0].exercise, replay[0].position), (1, 2));
+        assert_eq!((replay[1].exercise, replay[1].position), (0, 1));
+
+        // 播放器从第一次按键所在的练习开始
+        let mut player = ReplayPlayer::new(&session).unwrap();
+        assert_eq!(player.session().get_target_text(), "cd");
+        player.advance(Duration::from_secs(1));
+        assert_eq!(player.session().get_target_text(), "ab");
+        assert_eq!(player.session().get_input_text(), "ax");
+
+        // 练习顺序不同时按课程中的索引跳转
+        let rebuilt = TypingSession::from_replay(lesson, PracticeMode::Zen, &replay);
+        assert_eq!(rebuilt.get_target_text(), "ab");
+        assert_eq!(rebuilt.get_input_text(), "ax");
+        assert_eq!(rebuilt.error_positions, session.error_positions);
+    }
+
+    #[cfg(feature = "replay")]
+    #[test]
+    fn test_replay_resets_on_restart() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab")];
+        let mut session = TypingSession::new(lesson.clone(), PracticeMode::Zen, None);
+
+        let now = Instant::now().checked_sub(Duration::from_secs(30)).unwrap();
+        session.handle_keystroke_at('x', now);
+        session.restart_current_exercise();
+        session.handle_keystroke_at('a', now + Duration::from_millis(100));
+
+        let rebuilt =
+            TypingSession::from_replay(lesson, PracticeMode::Zen, &session.export_replay());
+        assert_eq!(rebuilt.get_input_text(), "a");
+        assert!(rebuilt.error_positions.is_empty());
+
+        // 重来时重新生成了已输入的练习内容，无法回放
+        let mut lesson = create_test_lesson();
+        lesson.regenerate_on_restart = Some(ExerciseGenerator::SampleWords {
+            words: vec!["alpha".to_string(), "beta".to_string()],
+            count: 4,
+        });
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        session.restart_current_exercise();
+        assert!(ReplayPlayer::new(&session).is_some());
+        session.handle_keystroke('a');
+        session.restart_current_exercise();
+        assert!(ReplayPlayer::new(&session).is_none());
+    }
+
+    #[cfg(feature = "replay")]
+    #[test]
+    fn test_replay_player_steps() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab"), Exercise::new("cd")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        session.set_exercise_order(vec![1, 0]);
+
+        let mut now = Instant::now().checked_sub(Duration::from_secs(30)).unwrap();
+        for ch in "cx".chars() {
+            session.handle_keystroke_at(ch, now);
+            now += Duration::from_millis(100);
+        }
+        assert!(session.advance_to_next_exercise());
+        session.handle_keystroke_at('a', now);
+
+        let mut player = ReplayPlayer::new(&session).unwrap();
+        assert_eq!(player.duration(), Duration::from_millis(200));
+        assert_eq!(player.session().get_target_text(), "cd");
+        assert_eq!(player.until_next(), Some(Duration::ZERO));
+
+        assert!(player.advance(Duration::ZERO));
+        assert_eq!(player.until_next(), Some(Duration::from_millis(100)));
+        assert!(!player.advance(Duration::from_millis(50)));
+        assert!(player.advance(Duration::from_millis(50)));
+        // 输错的字符按原样显示
+        assert_eq!(player.session().get_input_text(), "cx");
+        assert!(player.session().error_positions.contains(&1));
+
+        player.advance(Duration::from_secs(1));
+        assert!(player.is_finished());
+        assert_eq!(player.until_next(), None);
+        assert_eq!(player.session().get_target_text(), "ab");
+        assert_eq!(player.session().get_input_text(), "a");
+
+        player.rewind();
+        assert_eq!(player.position(), Duration::ZERO);
+        assert_eq!(player.session().get_target_text(), "cd");
+        assert_eq!(player.session().get_input_text(), "");
+    }
+
+    #[test]
+    fn test_sort_lessons() {
+        let lesson = |id: u32, title: &str, difficulty: Difficulty| {
+            let mut lesson = create_test_lesson();
+            lesson.id = id;
+            lesson.title = title.to_string();
+            lesson.meta.difficulty = difficulty;
+            lesson
+        };
+        let mut lessons = vec![
+            lesson(3, "beta", Difficulty::Beginner),
+            lesson(1, "Gamma", Difficulty::Advanced),
+            lesson(2, "alpha", Difficulty::Beginner),
+        ];
+        let ids = |lessons: &[Lesson]| lessons.iter().map(|l| l.id).collect::<Vec<_>>();
+        let last_practiced = HashMap::from([(1, 100), (3, 200)]);
+
+        sort_lessons(&mut lessons, LessonSort::Title, &last_practiced);
+        assert_eq!(ids(&lessons), [2, 3, 1]);
+        sort_lessons(&mut lessons, LessonSort::Difficulty, &last_practiced);
+        assert_eq!(ids(&lessons), [2, 3, 1]);
+        sort_lessons(&mut lessons, LessonSort::LastPracticed, &last_practiced);
+        assert_eq!(ids(&lessons), [3, 1, 2]);
+        sort_lessons(&mut lessons, LessonSort::Id, &last_practiced);
+        assert_eq!(ids(&lessons), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_backspace_not_counted_in_accuracy() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+
+        session.handle_keystroke('x');
+        session.handle_keystroke('\u{0008}');
+        session.handle_keystroke('h');
+        assert_eq!(session.total_keystrokes, 2);
+        assert_eq!(session.correct_keystrokes, 1);
+        assert!((session.get_snapshot().accuracy - 0.5).abs() < 1e-9);
+
+        for ch in "ello world".chars() {
+            session.handle_keystroke(ch);
+        }
+        let stats = session.finalize_session();
+        assert_eq!(stats.exercise_stats[0].total_keystrokes, 12);
+        assert!((stats.exercise_stats[0].accuracy - 11.0 / 12.0).abs() < 1e-9);
+        assert!((stats.overall_accuracy - 11.0 / 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_raw_wpm_counts_errors() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        let start = Instant::now().checked_sub(Duration::from_secs(30)).unwrap();
+
+        for (i, ch) in "hxllo world".chars().enumerate() {
+            session.handle_keystroke_at(ch, start + Duration::from_millis(200 * i as u64));
+        }
+
+        let stats = session.finalize_session();
+        let exercise = &stats.exercise_stats[0];
+        assert!(exercise.raw_wpm > exercise.net_wpm());
+        assert!(stats.raw_wpm > stats.net_wpm());
+        assert_eq!(stats.net_wpm(), stats.overall_wpm);
+    }
+
+    #[test]
+    fn test_pause_excluded_from_elapsed() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        let start = Instant::now().checked_sub(Duration::from_secs(10)).unwrap();
+
+        for (i, ch) in "hello".chars().enumerate() {
+            session.handle_keystroke_at(ch, start + Duration::from_millis(250 * i as u64));
+        }
+        session.pause_at(start + Duration::from_secs(1));
+        assert!(session.is_paused());
+
+        // 暂停期间按键被忽略
+        session.handle_keystroke(' ');
+        assert_eq!(session.current_position, 5);
+
+        let wpm_before = session.wpm_at(start + Duration::from_secs(1));
+        session.resume_at(start + Duration::from_secs(9));
+        assert!(!session.is_paused());
+
+        // 暂停的 8 秒不计入用时，继续后实时 WPM 不会骤降
+        let elapsed = session.exercise_elapsed().unwrap();
+        assert!(elapsed < Duration::from_secs(3));
+        assert!((session.wpm_at(start + Duration::from_secs(9)) - wpm_before).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pause_before_first_key_not_deducted() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        let start = Instant::now().checked_sub(Duration::from_secs(10)).unwrap();
+
+        session.pause_at(start);
+        session.resume_at(start + Duration::from_secs(5));
+        let typing_start = start + Duration::from_secs(5);
+        for (i, ch) in "hello world".chars().enumerate() {
+            session.handle_keystroke_at(ch, typing_start + Duration::from_millis(100 * i as u64));
+        }
+
+        assert!(session.is_current_exercise_complete());
+        assert_eq!(session.exercise_elapsed(), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_pause_on_completion_not_deducted() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        let start = Instant::now().checked_sub(Duration::from_secs(10)).unwrap();
+
+        for (i, ch) in "hello world".chars().enumerate() {
+            session.handle_keystroke_at(ch, start + Duration::from_millis(100 * i as u64));
+        }
+        assert!(session.is_current_exercise_complete());
+
+        session.pause_at(start + Duration::from_secs(2));
+        session.resume_at(start + Duration::from_secs(7));
+        assert_eq!(session.exercise_elapsed(), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_dead_time_excluded_from_wpm() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        let start = Instant::now().checked_sub(Duration::from_secs(8)).unwrap();
+
+        // 输入两个字符后停顿 6 秒再继续
+        for (ch, millis) in [('h', 0), ('e', 250), ('l', 6250), ('l', 6500), ('o', 6750)] {
+            session.handle_keystroke_at(ch, start + Duration::from_millis(millis));
+        }
+        let now = start + Duration::from_millis(6750);
+
+        // 超过 3 秒阈值的 3 秒不计入用时
+        let stats = session.finalize_current_exercise();
+        assert_eq!(stats.idle_secs, 3);
+        let active_wpm = session.wpm_at(now);
+
+        session.set_dead_time_threshold(None);
+        assert!(active_wpm > session.wpm_at(now) * 1.5);
+    }
+
+    #[test]
+    fn test_peek_ahead() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        for ch in "hello".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert_eq!(session.peek_ahead(3), " wo");
+        assert_eq!(session.peek_ahead(100), " world");
+
+        for ch in " world".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert_eq!(session.peek_ahead(3), "");
+    }
+
+    #[test]
+    fn test_short_wpm_window_reacts_faster() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises[0].content = "the quick brown fox".to_string();
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        let start = Instant::now();
+
+        // 先每秒一个字符，最后突然加速
+        let mut times: Vec<_> = (0..8).map(Duration::from_secs).collect();
+        times.extend((1..=3).map(|i| Duration::from_millis(7000 + 100 * i)));
+        for (ch, offset) in "the quick b".chars().zip(times) {
+            session.handle_keystroke_at(ch, start + offset);
+        }
+        let now = start + Duration::from_millis(7300);
+
+        let long_wpm = session.wpm_at(now);
+        session.set_wpm_window(Duration::from_secs(2));
+        assert!(session.wpm_at(now) > long_wpm * 2.0);
+    }
+
+    #[test]
+    fn test_strict_recovery() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        session.set_input_mode(InputMode::Strict);
+
+        // 默认必须输入正确字符：错后紧接正确字符即可继续
+        session.handle_keystroke('x');
+        assert_eq!(session.current_position, 0);
+        session.handle_keystroke('h');
+        assert_eq!(session.current_position, 1);
+
+        // 任意键重试：错后的第一次按键只解除阻塞
+        session.set_strict_recovery(StrictRecovery::AnyKey);
+        session.handle_keystroke('x');
+        session.handle_keystroke('e');
+        assert_eq!(session.current_position, 1);
+        session.handle_keystroke('e');
+        assert_eq!(session.current_position, 2);
+        assert_eq!(session.total_keystrokes, 4);
+    }
+
+    #[test]
+    fn test_slow_bigrams() {
+        let mut session = TypingSession::new(create_test_lesson(), PracticeMode::Zen, None);
+        let start = Instant::now();
+        // "he" 慢、"ll" 快；"o w" 含空格不计，"or" 前停顿过长不计
+        let gaps = [0, 900, 100, 100, 100, 100, 100, 100, 3000, 100, 100];
+        let mut now = start;
+        for (ch, gap) in "hello world".chars().zip(gaps) {
+            now += Duration::from_millis(gap);
+            session.handle_keystroke_at(ch, now);
+        }
+
+        let stats = session.finalize_session();
+        let bigrams = stats.slow_bigrams;
+        assert_eq!(bigrams[0], ("he".to_string(), Duration::from_millis(900)));
+        assert!(bigrams
+            .iter()
+            .all(|(pair, _)| pair != "or" && !pair.contains(' ')));
+        assert_eq!(bigrams.len(), 7); // he el ll lo wo rl ld
+
+        // 持久化用的总延迟和次数包含全部双字母
+        assert_eq!(stats.bigram_latency.len(), 7);
+        assert!(stats
+            .bigram_latency
+            .contains(&("ll".to_string(), Duration::from_millis(100), 1)));
+
+        let latency = HashMap::from([(('a', 'b'), (Duration::from_millis(300), 3))]);
+        assert_eq!(
+            extract_latin_bigrams(&latency, 1),
+            vec![("ab".to_string(), Duration::from_millis(100))]
+        );
+    }
+
+    #[test]
+    fn test_segment_error_density() {
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("ab\ncd\nef")];
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        for ch in "ab\nxd\nef".chars() {
+            session.handle_keystroke(ch);
+        }
+
+        let density = session.segment_error_density();
+        assert_eq!(density.len(), 3);
+        assert_eq!(density[1].0, 3..6);
+        let worst = density.iter().max_by(|a, b| a.1.total_cmp(&b.1)).unwrap();
+        assert_eq!(worst.0, 3..6);
+        assert_eq!(density[0].1, 0.0);
+
+        // 单行文本按句分段
+        let mut lesson = create_test_lesson();
+        lesson.exercises = vec![Exercise::new("Hi. Yo!")];
+        let session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        let ranges: Vec<_> = session
+            .segment_error_density()
+            .into_iter()
+            .map(|(r, _)| r)
+            .collect();
+        assert_eq!(ranges, vec![0..3, 3..7]);
+    }
 }
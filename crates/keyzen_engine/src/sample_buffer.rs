@@ -0,0 +1,106 @@
+//! 固定容量的时间序列采样（用于实时 WPM 曲线等图表）
+
+/// 固定容量的采样缓冲区
+///
+/// 写满后把相邻两个采样合并为一个（取平均），之后每次合并更多的原始采样，
+/// 内存占用固定，同时保留从第一次采样到最新采样的完整时间范围
+#[derive(Debug, Clone)]
+pub struct SampleBuffer {
+    capacity: usize,
+    samples: Vec<(f64, f64)>,
+    /// 每个采样合并的原始采样数
+    stride: usize,
+    /// 尚未凑满一个采样的原始采样（时间之和, 数值之和, 个数）
+    pending: (f64, f64, usize),
+}
+
+impl SampleBuffer {
+    /// 创建缓冲区（容量至少为 2）
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(2);
+        Self {
+            capacity,
+            samples: Vec::with_capacity(capacity),
+            stride: 1,
+            pending: (0.0, 0.0, 0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 添加一个原始采样（时间通常为秒）
+    pub fn push(&mut self, time: f64, value: f64) {
+        self.pending.0 += time;
+        self.pending.1 += value;
+        self.pending.2 += 1;
+        if self.pending.2 < self.stride {
+            return;
+        }
+
+        let (time_sum, value_sum, count) = std::mem::replace(&mut self.pending, (0.0, 0.0, 0));
+        self.samples
+            .push((time_sum / count as f64, value_sum / count as f64));
+
+        if self.samples.len() >= self.capacity {
+            self.downsample();
+        }
+    }
+
+    /// 相邻两个采样合并为一个，后续采样的合并数加倍
+    fn downsample(&mut self) {
+        self.samples = self
+            .samples
+            .chunks(2)
+            .map(|pair| {
+                let n = pair.len() as f64;
+                (
+                    pair.iter().map(|s| s.0).sum::<f64>() / n,
+                    pair.iter().map(|s| s.1).sum::<f64>() / n,
+                )
+            })
+            .collect();
+        self.stride *= 2;
+    }
+
+    /// 当前的采样（时间, 数值），按时间升序
+    pub fn samples(&self) -> &[(f64, f64)] {
+        &self.samples
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.stride = 1;
+        self.pending = (0.0, 0.0, 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_and_spans_full_range() {
+        let mut buffer = SampleBuffer::new(8);
+        for i in 0..1000 {
+            buffer.push(i as f64, (i % 10) as f64);
+        }
+
+        assert!(buffer.len() <= 8);
+        assert!(buffer.len() >= 4);
+
+        let samples = buffer.samples();
+        assert!(samples.first().unwrap().0 < 100.0);
+        assert!(samples.last().unwrap().0 > 800.0);
+        assert!(samples.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+}
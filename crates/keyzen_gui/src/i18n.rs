@@ -0,0 +1,130 @@
+//! 界面文本本地化
+//!
+//! 每种界面语言对应 `locales/` 下一个嵌入的 RON 文件（key -> 文本）。
+//! 缺失的翻译回退到中文，中文也缺失时直接显示 key。
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+const ZH_CN: &str = include_str!("../locales/zh-CN.ron");
+const EN_US: &str = include_str!("../locales/en-US.ron");
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UiLanguage {
+    #[default]
+    Chinese,
+    English,
+}
+
+impl UiLanguage {
+    /// 配置中保存的语言代码
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Chinese => "zh-CN",
+            Self::English => "en-US",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "zh-CN" => Some(Self::Chinese),
+            "en-US" => Some(Self::English),
+            _ => None,
+        }
+    }
+
+    /// 语言自身的名称（用于语言选择按钮，不做翻译）
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Self::Chinese => "中文",
+            Self::English => "English",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Self::Chinese => ZH_CN,
+            Self::English => EN_US,
+        }
+    }
+}
+
+/// 当前界面语言的文本表
+pub struct Strings {
+    language: UiLanguage,
+    table: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Strings {
+    pub fn load(language: UiLanguage) -> Self {
+        Self {
+            language,
+            table: parse_table(language),
+            fallback: parse_table(UiLanguage::Chinese),
+        }
+    }
+
+    pub fn language(&self) -> UiLanguage {
+        self.language
+    }
+
+    /// 获取文本
+    pub fn t(&self, key: &str) -> String {
+        self.table
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+
+    /// 获取文本并依次替换其中的 `{}` 占位符
+    pub fn tf(&self, key: &str, args: &[&dyn Display]) -> String {
+        let template = self.t(key);
+        let mut result = String::with_capacity(template.len());
+        let mut args = args.iter();
+        let mut rest = template.as_str();
+
+        while let Some(idx) = rest.find("{}") {
+            result.push_str(&rest[..idx]);
+            match args.next() {
+                Some(arg) => result.push_str(&arg.to_string()),
+                None => result.push_str("{}"),
+            }
+            rest = &rest[idx + 2..];
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+fn parse_table(language: UiLanguage) -> HashMap<String, String> {
+    ron::from_str(language.source()).unwrap_or_else(|e| {
+        eprintln!("❌ 解析界面语言 {} 失败: {}", language.code(), e);
+        HashMap::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_strings_complete() {
+        let zh = parse_table(UiLanguage::Chinese);
+        let en = parse_table(UiLanguage::English);
+        assert!(!zh.is_empty());
+
+        let missing: Vec<_> = zh.keys().filter(|k| !en.contains_key(*k)).collect();
+        assert!(missing.is_empty(), "English strings missing: {:?}", missing);
+    }
+
+    #[test]
+    fn test_fallback_and_format() {
+        let strings = Strings::load(UiLanguage::English);
+        assert_eq!(strings.t("lesson_list.title"), "Choose a Lesson");
+        assert_eq!(strings.t("no.such.key"), "no.such.key");
+        assert_eq!(strings.tf("practice.progress", &[&2, &5]), "Exercise 2/5");
+    }
+}
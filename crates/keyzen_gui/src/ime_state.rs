@@ -0,0 +1,75 @@
+//! 输入法组字状态
+//!
+//! InputHandler 收到组字中的文本时标记，提交或取消时清除。
+//! 中文课程中没有正在组字时，Space 直接作为空格输入（输入法提交的文本不一定包含句间空格）。
+
+#[derive(Debug, Default)]
+pub struct ImeState {
+    composing: bool,
+}
+
+impl ImeState {
+    /// 输入法更新组字中的文本（空文本表示组字已取消）
+    pub fn mark(&mut self, text: &str) {
+        self.composing = !text.is_empty();
+    }
+
+    /// 输入法提交或取消组字
+    pub fn unmark(&mut self) {
+        self.composing = false;
+    }
+
+    /// 按下 Space 时是否直接作为空格输入：开启设置、中文课程且没有正在组字
+    /// 组字中的 Space 留给输入法选择候选词
+    pub fn space_is_literal(&self, enabled: bool, cjk_lesson: bool) -> bool {
+        enabled && cjk_lesson && !self.composing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use keyzen_core::PracticeMode;
+    use keyzen_engine::TypingSession;
+
+    #[test]
+    fn test_space_between_committed_chinese_words() {
+        let lesson = keyzen_data::lesson_from_text(1, "test", "你好 世界").unwrap();
+        let mut session = TypingSession::new(lesson, PracticeMode::Zen, None);
+        let mut ime = ImeState::default();
+
+        // 输入 "nihao" 组字中按 Space 选择候选词，由输入法提交 "你好"
+        ime.mark("nihao");
+        assert!(!ime.space_is_literal(true, session.is_cjk_language()));
+        ime.unmark();
+        for ch in "你好".chars() {
+            session.handle_keystroke(ch);
+        }
+
+        // 输入法空闲时 Space 直接作为空格输入
+        assert!(ime.space_is_literal(true, session.is_cjk_language()));
+        session.handle_keystroke(' ');
+
+        ime.mark("shijie");
+        ime.unmark();
+        for ch in "世界".chars() {
+            session.handle_keystroke(ch);
+        }
+        assert!(session.is_current_exercise_complete());
+        assert!(!session.current_exercise_has_errors());
+    }
+
+    #[test]
+    fn test_space_left_to_ime_otherwise() {
+        let mut ime = ImeState::default();
+        // 关闭设置或非中文课程时 Space 交给输入法
+        assert!(!ime.space_is_literal(false, true));
+        assert!(!ime.space_is_literal(true, false));
+
+        // 组字被取消（空文本）后恢复空闲
+        ime.mark("ni");
+        assert!(!ime.space_is_literal(true, true));
+        ime.mark("");
+        assert!(ime.space_is_literal(true, true));
+    }
+}
@@ -1,17 +1,46 @@
+mod i18n;
+mod ime_state;
+mod reload_flag;
+
 use gpui::prelude::*;
 use gpui::*;
+use i18n::{Strings, UiLanguage};
+use ime_state::ImeState;
 use keyzen_core::*;
 use keyzen_data::LessonLoader;
-use keyzen_engine::TypingSession;
-use keyzen_persistence::{Database, SessionRecord};
+use keyzen_engine::keyboard::{is_ime_char, KeyId, KeyboardLayout};
+use keyzen_engine::{ReplayPlayer, TypingSession};
+use keyzen_persistence::{
+    Database, LastSession, SessionRecord, Settings, DEFAULT_WEAK_UNIT_THRESHOLD, MASTERY_ACCURACY,
+};
 use log::debug;
+use rand::Rng;
+use reload_flag::ReloadFlag;
+use std::collections::{HashMap, HashSet};
 use std::ops::Range;
-use std::sync::{mpsc, Arc, Mutex};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+use unicode_segmentation::UnicodeSegmentation;
 
 // 定义 Actions
 actions!(
     keyzen,
-    [Quit, BackToList, ShowHistory, ShowSettings, ToggleTheme]
+    [
+        Quit,
+        BackToList,
+        ShowHistory,
+        ShowSettings,
+        ToggleTheme,
+        RandomLesson,
+        AbortSession,
+        ToggleMastered,
+        CopyDebugDump,
+        ExportLessonText,
+        TogglePause,
+        PeekAhead
+    ]
 );
 
 // 主题枚举
@@ -35,29 +64,144 @@ struct ThemeColors {
     cursor: Hsla,
 }
 
+/// 词云可选的最低错误率
+const WEAK_UNIT_THRESHOLD_OPTIONS: [f32; 4] = [DEFAULT_WEAK_UNIT_THRESHOLD, 0.2, 0.3, 0.5];
+
+/// 渐进隐藏提高级别所需的准确率
+const MEMORY_RAMP_ACCURACY: f64 = 0.95;
+
+/// 准确率趋势图显示的采样数
+const ACCURACY_SPARKLINE_LEN: usize = 40;
+
+/// 历史页“最常练习”列表的课程数
+const FAVORITE_LESSONS_COUNT: usize = 5;
+
+/// 跨课程复习的练习数
+const REVIEW_EXERCISE_COUNT: usize = 10;
+
+/// 跨课程复习课程的 ID（不与内置和导入的课程冲突）
+const REVIEW_LESSON_ID: u32 = u32::MAX;
+
+/// 可选的预览窗口（字符数）
+const PREVIEW_WINDOW_OPTIONS: [usize; 3] = [200, 500, 1000];
+
+/// 可选的暂停检测时长（秒）
+const IDLE_TIMEOUT_OPTIONS_SECS: [u64; 3] = [5, 10, 30];
+
+/// 可选的最短保存时长（秒，0 表示全部保存）
+const MIN_SESSION_OPTIONS_SECS: [u64; 4] = [0, 5, 10, 30];
+
+/// 可选的自动跳转停顿（毫秒）
+const AUTO_ADVANCE_DELAY_OPTIONS: [u64; 4] = [0, 200, 400, 800];
+
+/// 节拍器可选的目标速度（WPM）
+const METRONOME_WPM_OPTIONS: [u32; 3] = [30, 45, 60];
+
+/// 视觉节拍可选的速度（BPM）
+const VISUAL_BEAT_BPM_OPTIONS: [u32; 3] = [60, 90, 120];
+
+/// 可选的 WPM 着色分界值
+const WPM_BAND_PRESETS: [[u32; 3]; 3] = [[20, 40, 60], [30, 50, 80], [50, 80, 110]];
+
+/// 最高速度段的金色
+const WPM_GOLD: u32 = 0xFFD93D;
+
+/// 实时 WPM 统计窗口可选值（秒）
+const WPM_WINDOW_OPTIONS_SECS: [u64; 3] = [3, 5, 10];
+
+/// 开始前倒计时可选值（秒）
+const START_COUNTDOWN_OPTIONS_SECS: [u64; 2] = [3, 5];
+
+/// 视觉节拍每拍亮起的时长（毫秒）
+const VISUAL_BEAT_FLASH_MS: u128 = 100;
+
+/// 提示接下来字符的数量和显示时长
+const PEEK_AHEAD_CHARS: usize = 10;
+const PEEK_AHEAD_DURATION: Duration = Duration::from_millis(1500);
+
+/// 按键回放可选的倍速
+const REPLAY_SPEEDS: [f32; 4] = [0.5, 1.0, 2.0, 4.0];
+
 struct KeyzenApp {
     session: Option<Entity<SessionModel>>,
+    session_subscription: Option<Subscription>, // 当前会话变化时刷新界面（每个会话只订阅一次）
     lessons: Vec<Lesson>,
     lesson_loader: LessonLoader,
-    needs_reload: Arc<Mutex<bool>>, // 标记是否需要重新加载
+    needs_reload: Arc<ReloadFlag>, // 标记是否需要重新加载
     selected_lesson: Option<usize>,
     focus_handle: FocusHandle,
-    database: Arc<Database>,
+    database: Rc<Database>,
+    database_recovered: Option<PathBuf>, // 损坏后被移走的数据库文件（在课程列表提示，点击关闭）
+    exported_lesson_text: Option<PathBuf>, // 刚导出的课程文本文件（在课程列表提示，点击关闭）
     show_history: bool,
     show_settings: bool,
+    confirm_abort: bool, // 等待再次按键确认放弃当前练习
     current_theme: Theme,
     memory_mode: MemoryMode,
+    exercise_order: ExerciseOrder,
+    lesson_sort: LessonSort, // 课程列表排序方式
+    // 每个课程练习过的练习索引（课程列表的继续进度）
+    practiced_exercises: HashMap<u32, HashSet<usize>>,
+    input_mode: InputMode,
+    strict_recovery: StrictRecovery,
+    max_content_width: Option<f32>, // 练习区最大宽度（None 表示不限制）
+    show_keyboard: bool,            // 是否显示屏幕键盘
+    show_whitespace: bool,          // 练习区用 · 和 ↵ 显示空格与换行
+    hide_stats: bool,               // 专注模式：练习中隐藏统计信息
+    metronome_wpm: Option<u32>,     // 节拍器目标速度（None 表示关闭）
+    visual_beat_bpm: Option<u32>,   // 视觉节拍速度（None 表示关闭）
+    wpm_bands: Option<[u32; 3]>,    // WPM 着色分界值（None 表示关闭）
+    wpm_window: Duration,           // 实时 WPM 统计窗口
+    weak_unit_threshold: f32,       // 词云最低错误率
+    preview_window: Option<usize>,  // 光标后至少渲染的字符数（None 表示渲染全部）
+    auto_advance: AutoAdvance,      // 完成练习后的跳转方式
+    auto_advance_delay: Duration,   // 无错完成后自动跳转前的停顿
+    idle_timeout: Option<Duration>, // 超过该时长没有按键时显示暂停提示（None 表示关闭）
+    min_session_duration: Duration, // 短于该时长的会话不保存
+    start_countdown_secs: u64,      // 开始前倒计时秒数（0 表示关闭）
+    allow_backspace: bool,          // 是否允许退格（关闭为硬核模式）
+    whitespace_policy: Option<WhitespacePolicy>, // 空格匹配方式（None 按课程类型）
+    strict_completion: bool,        // 最后一个字符必须输对才算完成
+    perfect_practice: bool,         // 有错完成时重来当前练习
+    preview_exercises: bool,        // 练习开始前显示预览，点击开始后才计时
+    memory_ramp: bool,              // 是否开启渐进隐藏
+    skip_mastered: bool,            // 是否跳过已掌握的练习
+    resume_on_launch: bool,         // 启动时回到上次练习的课程
+    last_session: Option<LastSession>, // 已保存的上次练习位置
+    pending_resume: Option<(usize, usize)>, // 启动时待恢复的（课程索引, 练习索引）
+    strings: Strings,               // 当前界面语言的文本
     // 缓存完成时的统计快照（避免 WPM 持续变化）
     completion_snapshot: Option<keyzen_engine::SessionSnapshot>,
+    replay: Option<ReplayModel>, // 正在查看的按键回放（在完成页打开）
     // 缓存历史记录,用于列表渲染
     cached_sessions: Vec<SessionRecord>,
     // 用于 InputHandler
     practice_area_bounds: Option<Bounds<Pixels>>,
+    ime: ImeState,           // 输入法组字状态
+    cjk_literal_space: bool, // 中文课程中未组字时 Space 直接作为空格输入
+    cjk_net_wpm: bool,       // 中文课程的速度只计汉字
 }
 
 struct SessionModel {
     session: TypingSession,
     _event_rx: mpsc::Receiver<TypingEvent>,
+    auto_advance_delay: Duration, // 无错完成后跳转下一个练习前的停顿
+    pending_advance: Option<Task<()>>, // 已计划的延迟跳转
+    idle_check: Option<Task<()>>, // 暂停检测时长到达后刷新界面
+    peek_hint: Option<String>,    // 临时显示的接下来几个字符
+    peek_task: Option<Task<()>>,  // 到时后隐藏提示
+    countdown: Option<u64>,       // 开始前倒计时剩余秒数（倒计时中不接受输入）
+    countdown_task: Option<Task<()>>, // 每秒推进倒计时
+    mastered_exercises: HashSet<usize>, // 当前课程已掌握的练习索引
+    started_at: Instant,          // 会话创建时间（视觉节拍从此时开始）
+    beat_task: Option<Task<()>>,  // 视觉节拍亮起和熄灭时刷新界面
+}
+
+/// 完成页打开的按键回放
+struct ReplayModel {
+    player: ReplayPlayer,
+    speed: f32,                 // 回放倍速
+    playback: Option<Task<()>>, // 等待下一次按键的计时（None 表示已暂停）
 }
 
 // 自定义 Element 用于注册 InputHandler
@@ -153,33 +297,193 @@ impl SessionModel {
         Self {
             session,
             _event_rx: event_rx,
+            auto_advance_delay: Duration::ZERO,
+            pending_advance: None,
+            idle_check: None,
+            peek_hint: None,
+            peek_task: None,
+            countdown: None,
+            countdown_task: None,
+            mastered_exercises: HashSet::new(),
+            started_at: Instant::now(),
+            beat_task: None,
         }
     }
 
     fn handle_keystroke(&mut self, key: &str, cx: &mut Context<Self>) {
-        // 处理退格键
-        if key == "backspace" {
-            self.session.handle_keystroke('\u{0008}');
+        // 倒计时结束前忽略所有按键
+        if self.countdown.is_some() {
+            return;
+        }
+
+        // 预览中按 Enter 开始练习，其他按键忽略
+        if self.session.is_awaiting_start() {
+            if key == "\n" {
+                self.start_exercise(cx);
+            }
+            return;
+        }
+
+        let is_backspace = matches!(key, "backspace" | "word-backspace");
+
+        // 禁止退格时忽略退格（也不会取消延迟跳转）
+        if is_backspace && !self.session.allows_backspace() {
+            return;
+        }
+
+        // 手动跳转模式：练习完成后按 Enter 继续（最后一个练习则结束课程）
+        if key == "\n"
+            && self.session.get_auto_advance() == AutoAdvance::Manual
+            && self.session.advance_or_finish()
+        {
+            debug!("⏩ 按 Enter 继续");
             cx.notify();
             return;
         }
 
-        // 处理普通字符
-        if let Some(ch) = key.chars().next() {
-            self.session.handle_keystroke(ch);
+        // 延迟跳转期间继续输入：退格取消跳转；其他字符立即跳转并计入下一个练习
+        if self.pending_advance.take().is_some() && !is_backspace {
+            self.session.advance_to_next_exercise();
+            debug!("⏩ 停顿期间继续输入，立即跳转到下一个练习");
+        }
+
+        // 处理退格键（Ctrl/Alt+Backspace 删除到单词开头）
+        if is_backspace {
+            self.session.handle_keystroke(if key == "word-backspace" {
+                keyzen_engine::DELETE_WORD
+            } else {
+                '\u{0008}'
+            });
+            self.schedule_idle_check(cx);
+            cx.notify();
+            return;
+        }
+
+        // 处理普通字符（一个字素簇可能由多个字符组成，由引擎凑齐后判定）
+        if !key.is_empty() {
+            for ch in key.chars() {
+                self.session.handle_keystroke(ch);
+            }
+            self.schedule_idle_check(cx);
             cx.notify();
 
             // 检查当前练习是否完成且无错误，才自动跳转
-            if self.session.is_current_exercise_complete()
-                && !self.session.current_exercise_has_errors()
-            {
-                if self.session.has_next_exercise() {
-                    self.session.advance_to_next_exercise();
-                    debug!("✅ 练习无错误，自动跳转到下一个练习");
+            if self.session.should_auto_advance() {
+                self.schedule_advance(cx);
+            }
+        }
+    }
+
+    /// 结束预览，开始当前练习（从此时开始计时）
+    fn start_exercise(&mut self, cx: &mut Context<Self>) {
+        self.session.mark_shown();
+        debug!("▶️ 预览结束，开始练习");
+        cx.notify();
+    }
+
+    /// 开始 `secs` 秒倒计时，结束后才接受输入（计时从之后的首次按键开始）
+    fn start_countdown(&mut self, secs: u64, cx: &mut Context<Self>) {
+        if secs == 0 {
+            return;
+        }
+
+        self.countdown = Some(secs);
+        self.countdown_task = Some(cx.spawn(async move |this, cx| {
+            for remaining in (0..secs).rev() {
+                cx.background_executor().timer(Duration::from_secs(1)).await;
+                let updated = this.update(cx, |model, cx| {
+                    model.countdown = (remaining > 0).then_some(remaining);
                     cx.notify();
+                });
+                if updated.is_err() {
+                    return;
                 }
             }
+            debug!("▶️ 倒计时结束，开始练习");
+        }));
+        cx.notify();
+    }
+
+    /// 停顿 `auto_advance_delay` 后跳转到下一个练习，让用户看清完成的一行
+    fn schedule_advance(&mut self, cx: &mut Context<Self>) {
+        if self.auto_advance_delay.is_zero() {
+            self.session.advance_to_next_exercise();
+            debug!("✅ 练习无错误，自动跳转到下一个练习");
+            cx.notify();
+            return;
         }
+
+        let delay = self.auto_advance_delay;
+        self.pending_advance = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(delay).await;
+            this.update(cx, |model, cx| {
+                // 停顿期间已被取消或提前跳转时不再处理
+                if let Some(task) = model.pending_advance.take() {
+                    task.detach();
+                    model.session.advance_to_next_exercise();
+                    debug!("✅ 练习无错误，自动跳转到下一个练习");
+                    cx.notify();
+                }
+            })
+            .ok();
+        }));
+    }
+
+    /// 在暂停检测时长后刷新界面以显示暂停提示（每次按键重新计时）
+    fn schedule_idle_check(&mut self, cx: &mut Context<Self>) {
+        let Some(threshold) = self.session.idle_threshold() else {
+            return;
+        };
+
+        self.idle_check = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(threshold).await;
+            this.update(cx, |_, cx| cx.notify()).ok();
+        }));
+    }
+
+    /// 短暂显示光标之后的几个字符，到时后自动隐藏
+    fn flash_peek(&mut self, cx: &mut Context<Self>) {
+        let hint = self.session.peek_ahead(PEEK_AHEAD_CHARS);
+        if hint.is_empty() {
+            return;
+        }
+
+        self.peek_hint = Some(hint);
+        self.peek_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(PEEK_AHEAD_DURATION).await;
+            this.update(cx, |this, cx| {
+                this.peek_hint = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    /// 在视觉节拍每次亮起和熄灭时刷新界面（None 停止），课程完成后停止
+    fn set_visual_beat(&mut self, bpm: Option<u32>, cx: &mut Context<Self>) {
+        let Some(bpm) = bpm else {
+            self.beat_task = None;
+            return;
+        };
+
+        let started_at = self.started_at;
+        self.beat_task = Some(cx.spawn(async move |this, cx| loop {
+            let delay = visual_beat_next_change(started_at.elapsed(), bpm);
+            cx.background_executor().timer(delay).await;
+            let completed = this.update(cx, |model, cx| {
+                cx.notify();
+                model.is_completed()
+            });
+            if !matches!(completed, Ok(false)) {
+                return;
+            }
+        }));
+    }
+
+    /// 取消已计划的延迟跳转（手动切换练习时）
+    fn cancel_pending_advance(&mut self) {
+        self.pending_advance = None;
     }
 
     fn get_target_text(&self) -> &str {
@@ -199,6 +503,14 @@ impl SessionModel {
     }
 
     fn is_completed(&self) -> bool {
+        // 无限模式不会自动结束，按 Esc 退出
+        if self.session.get_mode() == PracticeMode::Endless {
+            return false;
+        }
+        // 手动跳转模式需要按 Enter 确认结束
+        if self.session.get_auto_advance() == AutoAdvance::Manual {
+            return self.session.is_lesson_finished();
+        }
         let snapshot = self.session.get_snapshot();
         snapshot.progress >= 1.0
     }
@@ -211,7 +523,7 @@ impl KeyzenApp {
             panic!("无法初始化课程加载器");
         });
 
-        let lessons = match loader.load_all() {
+        let mut lessons = match loader.load_all() {
             Ok(lessons) => {
                 debug!("✅ 成功加载 {} 个课程", lessons.len());
                 for lesson in &lessons {
@@ -232,65 +544,118 @@ impl KeyzenApp {
         };
 
         // 初始化数据库
-        let database = Arc::new(Database::default().unwrap_or_else(|e| {
+        let (database, database_recovered) = Database::default_or_recover().unwrap_or_else(|e| {
             eprintln!("警告: 无法创建数据库: {}", e);
-            Database::new(":memory:").expect("无法创建内存数据库")
-        }));
+            (Database::new(":memory:").expect("无法创建内存数据库"), None)
+        });
+        if let Some(path) = &database_recovered {
+            eprintln!("警告: 数据库已损坏，已移至 {:?} 并新建数据库", path);
+        }
+        let database = Rc::new(database);
 
-        // 从数据库加载主题配置
-        let current_theme = database
-            .get_config("theme")
-            .ok()
-            .flatten()
-            .and_then(|s| match s.as_str() {
-                "light" => Some(Theme::Light),
-                "dark" => Some(Theme::Dark),
-                _ => None,
-            })
-            .unwrap_or(Theme::Dark); // 默认深色主题
-
-        // 从数据库加载记忆模式配置
-        let memory_mode = database
-            .get_config("memory_mode")
-            .ok()
-            .flatten()
-            .and_then(|s| {
-                // 解析格式：off, complete, first_letter, partial_low, partial_medium, partial_high
-                match s.as_str() {
-                    "off" => Some(MemoryMode::Off),
-                    "complete" => Some(MemoryMode::Complete),
-                    "first_letter" => Some(MemoryMode::FirstLetter),
-                    "partial_low" => Some(MemoryMode::Partial(PartialLevel::Low)),
-                    "partial_medium" => Some(MemoryMode::Partial(PartialLevel::Medium)),
-                    "partial_high" => Some(MemoryMode::Partial(PartialLevel::High)),
-                    _ => None,
+        // 从数据库加载设置（首次启动时从旧版逐项配置迁移）
+        let settings = database.load_settings().unwrap_or_else(|e| {
+            eprintln!("读取设置失败，使用默认设置: {}", e);
+            Settings::default()
+        });
+        let current_theme = match settings.theme.as_str() {
+            "light" => Theme::Light,
+            _ => Theme::Dark, // 默认深色主题
+        };
+        let ui_language = UiLanguage::from_code(&settings.ui_language).unwrap_or_default();
+
+        let practiced_exercises = practiced_exercises_for(&database);
+
+        // 按保存的排序方式排列课程列表
+        keyzen_engine::sort_lessons(
+            &mut lessons,
+            settings.lesson_sort,
+            &last_practiced_for(&database, settings.lesson_sort),
+        );
+
+        // 上次练习的课程已不存在时清除记录，停留在课程列表
+        let last_session = database.load_last_session().unwrap_or_else(|e| {
+            eprintln!("读取上次练习位置失败: {}", e);
+            None
+        });
+        let pending_resume = last_session
+            .filter(|_| settings.resume_on_launch)
+            .and_then(|last| {
+                let lesson_index = lessons.iter().position(|l| l.id == last.lesson_id);
+                if lesson_index.is_none() {
+                    debug!("⚠️  上次练习的课程 {} 已不存在", last.lesson_id);
+                    if let Err(e) = database.clear_last_session() {
+                        eprintln!("清除上次练习位置失败: {}", e);
+                    }
                 }
-            })
-            .unwrap_or(MemoryMode::Off); // 默认关闭
+                lesson_index.map(|index| (index, last.exercise_index))
+            });
+
+        let strings = Strings::load(ui_language);
+        set_app_menus(&strings, cx);
 
-        let needs_reload = Arc::new(Mutex::new(false));
+        let needs_reload = Arc::new(ReloadFlag::default());
         let needs_reload_clone = needs_reload.clone();
 
         let mut app = Self {
             session: None,
+            session_subscription: None,
             lessons,
+            practiced_exercises,
             lesson_loader: loader,
             needs_reload,
             selected_lesson: None,
             focus_handle: cx.focus_handle(),
             database,
+            database_recovered,
+            exported_lesson_text: None,
             show_history: false,
             show_settings: false,
+            confirm_abort: false,
             current_theme,
-            memory_mode,
+            memory_mode: settings.memory_mode,
+            exercise_order: settings.exercise_order,
+            lesson_sort: settings.lesson_sort,
+            input_mode: settings.input_mode,
+            strict_recovery: settings.strict_recovery,
+            max_content_width: settings.max_content_width,
+            show_keyboard: settings.show_keyboard,
+            show_whitespace: settings.show_whitespace,
+            hide_stats: settings.hide_stats,
+            metronome_wpm: settings.metronome_wpm,
+            visual_beat_bpm: settings.visual_beat_bpm,
+            wpm_bands: settings.wpm_bands,
+            wpm_window: Duration::from_secs(settings.wpm_window_secs),
+            weak_unit_threshold: settings.weak_unit_threshold,
+            preview_window: settings.preview_window,
+            auto_advance: settings.auto_advance,
+            auto_advance_delay: Duration::from_millis(settings.auto_advance_delay_ms),
+            idle_timeout: settings.idle_timeout_secs.map(Duration::from_secs),
+            min_session_duration: Duration::from_secs(settings.min_session_secs),
+            start_countdown_secs: settings.start_countdown_secs,
+            allow_backspace: settings.allow_backspace,
+            whitespace_policy: settings.whitespace_policy,
+            strict_completion: settings.strict_completion,
+            perfect_practice: settings.perfect_practice,
+            preview_exercises: settings.preview_exercises,
+            memory_ramp: settings.memory_ramp,
+            skip_mastered: settings.skip_mastered,
+            resume_on_launch: settings.resume_on_launch,
+            last_session,
+            pending_resume,
+            strings,
             completion_snapshot: None,
+            replay: None,
             cached_sessions: Vec::new(),
             practice_area_bounds: None,
+            ime: ImeState::default(),
+            cjk_literal_space: settings.cjk_literal_space,
+            cjk_net_wpm: settings.cjk_net_wpm,
         };
 
         // 启动文件监听
         if let Err(e) = app.lesson_loader.start_watching(move || {
-            *needs_reload_clone.lock().unwrap() = true;
+            needs_reload_clone.mark();
             debug!("📂 检测到用户课程文件变化，标记需要重新加载");
         }) {
             eprintln!("⚠️  启动课程文件监听失败: {}", e);
@@ -305,16 +670,18 @@ impl KeyzenApp {
 
         match self.lesson_loader.load_all() {
             Ok(new_lessons) => {
+                // 按 id 记住当前课程，重新加载后它在列表中的位置可能变化
+                let selected_id = self.selected_lesson_id();
                 self.lessons = new_lessons;
+                self.sort_lessons_keeping(selected_id);
+                self.practiced_exercises = practiced_exercises_for(&self.database);
                 debug!("✅ 课程已重新加载: {} 个", self.lessons.len());
 
-                // 如果当前正在练习的课程索引超出范围，返回主页
-                if let Some(idx) = self.selected_lesson {
-                    if idx >= self.lessons.len() {
-                        self.session = None;
-                        self.selected_lesson = None;
-                        debug!("⚠️  当前课程已失效，返回主页");
-                    }
+                // 如果当前正在练习的课程已被删除，返回主页
+                if selected_id.is_some() && self.selected_lesson.is_none() {
+                    self.session = None;
+                    self.session_subscription = None;
+                    debug!("⚠️  当前课程已失效，返回主页");
                 }
 
                 cx.notify();
@@ -325,92 +692,745 @@ impl KeyzenApp {
         }
     }
 
+    /// 把剪贴板中的文本导入为用户课程（每行一个练习）
+    fn import_clipboard_text(&mut self, cx: &mut Context<Self>) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            eprintln!("⚠️  剪贴板中没有可导入的文本");
+            return;
+        };
+
+        let title = self.strings.t("lesson_list.imported_title");
+        match self.lesson_loader.import_text(&title, &text) {
+            Ok(_) => self.reload_lessons(cx),
+            Err(e) => eprintln!("❌ 导入文本失败: {}", e),
+        }
+    }
+
+    /// 创建练习会话（按配置应用练习顺序）
+    fn create_session(&self, lesson: Lesson, cx: &mut Context<Self>) -> Entity<SessionModel> {
+        let order = match self.exercise_order {
+            ExerciseOrder::Sequential => None,
+            ExerciseOrder::Adaptive => keyzen_engine::build_adaptive_order(&lesson, &self.database)
+                .map_err(|e| eprintln!("生成自适应练习顺序失败: {}", e))
+                .ok(),
+        };
+
+        let pace_target_wpm = self.metronome_wpm.map(f64::from);
+        let memory_mode = self.memory_mode;
+
+        let auto_advance = self.auto_advance;
+        let auto_advance_delay = self.auto_advance_delay;
+        let idle_timeout = self.idle_timeout;
+        let min_session_duration = self.min_session_duration;
+        let start_countdown_secs = self.start_countdown_secs;
+        let visual_beat_bpm = self.visual_beat_bpm;
+        let wpm_window = self.wpm_window;
+        let cjk_net_wpm = self.cjk_net_wpm;
+        let allow_backspace = self.allow_backspace;
+        let whitespace_policy = self.whitespace_policy;
+        let input_mode = self.input_mode;
+        let strict_recovery = self.strict_recovery;
+        let strict_completion = self.strict_completion;
+        let perfect_practice = self.perfect_practice;
+        let timer_start = if self.preview_exercises {
+            TimerStart::OnShow
+        } else {
+            TimerStart::OnFirstKey
+        };
+        let memory_ramp = self.memory_ramp.then_some(MEMORY_RAMP_ACCURACY);
+        let skip_mastered = self.skip_mastered;
+        let mastered_exercises = self
+            .database
+            .get_mastered_exercises(lesson.id)
+            .map_err(|e| eprintln!("读取已掌握练习失败: {}", e))
+            .unwrap_or_default();
+
+        cx.new(|cx| {
+            let mut model = SessionModel::new(lesson, cx);
+            model.auto_advance_delay = auto_advance_delay;
+            model.session.set_auto_advance(auto_advance);
+            model.session.set_allow_backspace(allow_backspace);
+            model.session.set_whitespace_policy(whitespace_policy);
+            model.session.set_input_mode(input_mode);
+            model.session.set_strict_recovery(strict_recovery);
+            model.session.set_strict_completion(strict_completion);
+            model.session.set_perfect_practice(perfect_practice);
+            model.session.set_timer_start(timer_start);
+            model.session.set_memory_mode(memory_mode);
+            model.session.set_memory_ramp(memory_ramp);
+            model.session.set_idle_threshold(idle_timeout);
+            model.session.set_min_session_duration(min_session_duration);
+            model.session.set_wpm_window(wpm_window);
+            model.session.set_cjk_net_wpm(cjk_net_wpm);
+            if let Some(order) = order {
+                model.session.set_exercise_order(order);
+            }
+            if skip_mastered {
+                model.session.skip_mastered_exercises(&mastered_exercises);
+            }
+            model.mastered_exercises = mastered_exercises;
+            // 开启节拍器时同时统计相对目标节奏的偏差
+            model.session.set_pace_target_wpm(pace_target_wpm);
+            model.start_countdown(start_countdown_secs, cx);
+            model.set_visual_beat(visual_beat_bpm, cx);
+            model
+        })
+    }
+
+    /// 订阅当前会话的变化（替换旧订阅，旧订阅随之取消）
+    fn observe_session(&mut self, cx: &mut Context<Self>) {
+        self.session_subscription = self.session.as_ref().map(|session| {
+            cx.observe(session, |this, _, cx| {
+                this.remember_last_session(cx);
+                cx.notify();
+            })
+        });
+    }
+
+    /// 记录当前练习的位置（变化时才写入），课程完成后下次从第一个练习开始
+    fn remember_last_session(&mut self, cx: &mut Context<Self>) {
+        let Some((session, lesson)) = self
+            .session
+            .as_ref()
+            .zip(self.selected_lesson.and_then(|idx| self.lessons.get(idx)))
+        else {
+            return;
+        };
+        let session = session.read(cx);
+        let last = LastSession {
+            lesson_id: lesson.id,
+            exercise_index: if session.is_completed() {
+                0
+            } else {
+                session.session.current_lesson_exercise_index()
+            },
+        };
+        if self.last_session == Some(last) {
+            return;
+        }
+
+        if let Err(e) = self.database.save_last_session(&last) {
+            eprintln!("保存上次练习位置失败: {}", e);
+        }
+        self.last_session = Some(last);
+    }
+
     fn start_lesson(&mut self, lesson_index: usize, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(lesson) = self.lessons.get(lesson_index).cloned() {
-            self.session = Some(cx.new(|cx| SessionModel::new(lesson, cx)));
+            self.session = Some(self.create_session(lesson, cx));
+            self.observe_session(cx);
             self.selected_lesson = Some(lesson_index);
+            self.remember_last_session(cx);
             self.completion_snapshot = None; // 清除之前的完成快照
+            self.confirm_abort = false;
             self.focus_handle.focus(window);
             cx.notify();
         }
     }
 
-    fn back_to_list(&mut self, _: &BackToList, window: &mut Window, cx: &mut Context<Self>) {
-        // 如果在设置页面，Esc 关闭设置
-        if self.show_settings {
-            self.show_settings = false;
-            self.focus_handle.focus(window);
-            cx.notify();
-            return;
-        }
+    /// 开始跨课程复习：由最近表现不佳的练习组成一次性课程
+    fn start_struggles_review(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let picks = match self
+            .database
+            .build_recent_struggles_review(REVIEW_EXERCISE_COUNT)
+        {
+            Ok(picks) => picks,
+            Err(e) => {
+                eprintln!("❌ 生成复习失败: {}", e);
+                return;
+            }
+        };
+        let title = self.strings.t("lesson_list.review_title");
+        let Some(lesson) =
+            keyzen_data::review_lesson(REVIEW_LESSON_ID, &title, &self.lessons, &picks)
+        else {
+            debug!("⚠️  最近没有需要复习的练习");
+            return;
+        };
+
+        self.session = Some(self.create_session(lesson, cx));
+        self.observe_session(cx);
+        self.selected_lesson = None;
+        self.completion_snapshot = None;
+        self.confirm_abort = false;
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    /// 开始课程并跳转到指定练习（从上次进度继续）
+    fn resume_lesson(
+        &mut self,
+        lesson_index: usize,
+        exercise_index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.start_lesson(lesson_index, window, cx);
+        if let Some(session) = &self.session {
+            session.update(cx, |session_model, cx| {
+                session_model.session.go_to_exercise(exercise_index);
+                cx.notify();
+            });
+        }
+    }
+
+    /// 在课程列表随机开始一个课程（优先选择尚未掌握的课程）
+    fn random_lesson(&mut self, _: &RandomLesson, window: &mut Window, cx: &mut Context<Self>) {
+        // 只在课程列表页面生效
+        if self.session.is_some() || self.show_history || self.show_settings {
+            return;
+        }
+        if self.lessons.is_empty() {
+            debug!("⚠️  没有可用课程，无法随机选课");
+            return;
+        }
+
+        let unmastered: Vec<usize> = self
+            .lessons
+            .iter()
+            .enumerate()
+            .filter(|(_, lesson)| {
+                let history = self
+                    .database
+                    .get_lesson_history(lesson.id as i32, 20)
+                    .unwrap_or_default();
+                !history.iter().any(|r| r.accuracy >= MASTERY_ACCURACY)
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        // 全部已掌握时从所有课程中选择
+        let mut rng = rand::thread_rng();
+        let lesson_index = if unmastered.is_empty() {
+            rng.gen_range(0..self.lessons.len())
+        } else {
+            unmastered[rng.gen_range(0..unmastered.len())]
+        };
+
+        debug!("🎲 随机开始课程: {}", lesson_index);
+        self.start_lesson(lesson_index, window, cx);
+    }
+
+    fn back_to_list(&mut self, _: &BackToList, window: &mut Window, cx: &mut Context<Self>) {
+        // 如果在设置页面，Esc 关闭设置
+        if self.show_settings {
+            self.show_settings = false;
+            self.focus_handle.focus(window);
+            cx.notify();
+            return;
+        }
+
+        // 正在查看回放时，Esc 回到完成页
+        if self.replay.take().is_some() {
+            cx.notify();
+            return;
+        }
+
+        // 正在确认放弃练习时，Esc 取消确认
+        if self.confirm_abort {
+            self.confirm_abort = false;
+            cx.notify();
+            return;
+        }
+
+        // 在清除 session 前保存数据
+        if let Some(session) = &self.session {
+            let db = self.database.clone();
+            session.update(cx, |session_model, _cx| {
+                if let Err(e) = session_model.session.save_to_database(&db) {
+                    eprintln!("保存会话数据失败: {}", e);
+                }
+            });
+        }
+
+        self.session = None;
+        self.session_subscription = None;
+        self.selected_lesson = None;
+        self.show_history = false;
+        self.completion_snapshot = None; // 清除完成快照
+        self.sort_lessons(); // 按最近练习排序时刚结束的课程排到最前
+        self.practiced_exercises = practiced_exercises_for(&self.database);
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    /// 放弃当前练习且不保存（需要连按两次确认）
+    fn abort_session(&mut self, _: &AbortSession, window: &mut Window, cx: &mut Context<Self>) {
+        if self.session.is_none() || self.show_settings {
+            return;
+        }
+
+        if !self.confirm_abort {
+            self.confirm_abort = true;
+            cx.notify();
+            return;
+        }
+
+        debug!("🗑️  放弃当前练习，不保存记录");
+        self.session = None;
+        self.session_subscription = None;
+        self.selected_lesson = None;
+        self.confirm_abort = false;
+        self.completion_snapshot = None;
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    /// 手动标记或取消标记当前练习为已掌握
+    fn toggle_mastered(
+        &mut self,
+        _: &ToggleMastered,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.show_settings {
+            return;
+        }
+        let Some(session) = self.session.clone() else {
+            return;
+        };
+        self.confirm_abort = false;
+
+        let db = self.database.clone();
+        session.update(cx, |session_model, cx| {
+            let lesson_id = session_model.session.get_lesson_id();
+            let index = session_model.session.current_lesson_exercise_index();
+            let result = if session_model.mastered_exercises.remove(&index) {
+                db.unmark_exercise_mastered(lesson_id, index)
+            } else {
+                session_model.mastered_exercises.insert(index);
+                db.mark_exercise_mastered(lesson_id, index)
+            };
+            if let Err(e) = result {
+                eprintln!("保存已掌握标记失败: {}", e);
+            }
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    /// 复制当前练习的引擎状态到剪贴板，便于附在问题报告中
+    fn copy_debug_dump(&mut self, _: &CopyDebugDump, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(session) = &self.session else {
+            return;
+        };
+
+        let dump = session.read(cx).session.debug_dump();
+        cx.write_to_clipboard(ClipboardItem::new_string(dump));
+        debug!("📋 已复制引擎状态到剪贴板");
+    }
+
+    /// 把当前课程的全部练习导出为纯文本文件（含提示）
+    fn export_lesson_text(
+        &mut self,
+        _: &ExportLessonText,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(lesson_index) = self.selected_lesson {
+            self.export_lesson_text_at(lesson_index, cx);
+        }
+    }
+
+    /// 导出课程文本，成功后把文件路径复制到剪贴板并在课程列表提示
+    fn export_lesson_text_at(&mut self, lesson_index: usize, cx: &mut Context<Self>) {
+        let Some(lesson) = self.lessons.get(lesson_index) else {
+            return;
+        };
+
+        match self.lesson_loader.export_lesson_text(lesson) {
+            Ok(path) => {
+                debug!("📄 课程文本已导出到: {}", path.display());
+                cx.write_to_clipboard(ClipboardItem::new_string(path.display().to_string()));
+                self.exported_lesson_text = Some(path);
+                cx.notify();
+            }
+            Err(e) => eprintln!("❌ 导出课程文本失败: {}", e),
+        }
+    }
+
+    /// 暂停或继续当前练习
+    fn toggle_pause(&mut self, _: &TogglePause, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.show_settings {
+            return;
+        }
+        let Some(session) = &self.session else {
+            return;
+        };
+        self.confirm_abort = false;
+
+        session.update(cx, |session_model, cx| {
+            if session_model.session.is_paused() {
+                session_model.session.resume();
+            } else {
+                session_model.session.pause();
+            }
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    /// 短暂提示接下来的几个字符
+    fn peek_ahead(&mut self, _: &PeekAhead, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.show_settings {
+            return;
+        }
+        self.confirm_abort = false;
+        if let Some(session) = &self.session {
+            session.update(cx, |session_model, cx| session_model.flash_peek(cx));
+        }
+    }
+
+    fn show_history(&mut self, _: &ShowHistory, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_history = !self.show_history;
+        self.confirm_abort = false;
+        if self.show_history {
+            // 加载历史记录
+            self.cached_sessions = self.database.get_recent_sessions(10).unwrap_or_default();
+        }
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    fn show_settings(&mut self, _: &ShowSettings, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_settings = !self.show_settings;
+        self.confirm_abort = false;
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    /// 当前的全部设置
+    fn current_settings(&self) -> Settings {
+        Settings {
+            theme: match self.current_theme {
+                Theme::Dark => "dark",
+                Theme::Light => "light",
+            }
+            .to_string(),
+            ui_language: self.strings.language().code().to_string(),
+            memory_mode: self.memory_mode,
+            exercise_order: self.exercise_order,
+            lesson_sort: self.lesson_sort,
+            input_mode: self.input_mode,
+            strict_recovery: self.strict_recovery,
+            max_content_width: self.max_content_width,
+            show_keyboard: self.show_keyboard,
+            show_whitespace: self.show_whitespace,
+            hide_stats: self.hide_stats,
+            metronome_wpm: self.metronome_wpm,
+            visual_beat_bpm: self.visual_beat_bpm,
+            wpm_bands: self.wpm_bands,
+            wpm_window_secs: self.wpm_window.as_secs(),
+            weak_unit_threshold: self.weak_unit_threshold,
+            preview_window: self.preview_window,
+            idle_timeout_secs: self.idle_timeout.map(|t| t.as_secs()),
+            min_session_secs: self.min_session_duration.as_secs(),
+            start_countdown_secs: self.start_countdown_secs,
+            auto_advance: self.auto_advance,
+            auto_advance_delay_ms: self.auto_advance_delay.as_millis() as u64,
+            allow_backspace: self.allow_backspace,
+            whitespace_policy: self.whitespace_policy,
+            strict_completion: self.strict_completion,
+            perfect_practice: self.perfect_practice,
+            preview_exercises: self.preview_exercises,
+            memory_ramp: self.memory_ramp,
+            skip_mastered: self.skip_mastered,
+            cjk_literal_space: self.cjk_literal_space,
+            cjk_net_wpm: self.cjk_net_wpm,
+            resume_on_launch: self.resume_on_launch,
+        }
+    }
+
+    /// 保存全部设置到数据库
+    fn save_settings(&self) {
+        if let Err(e) = self.database.save_settings(&self.current_settings()) {
+            eprintln!("保存设置失败: {}", e);
+        }
+    }
+
+    fn toggle_theme(&mut self, _: &ToggleTheme, _window: &mut Window, cx: &mut Context<Self>) {
+        self.current_theme = match self.current_theme {
+            Theme::Dark => Theme::Light,
+            Theme::Light => Theme::Dark,
+        };
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_memory_mode(&mut self, mode: MemoryMode, cx: &mut Context<Self>) {
+        self.memory_mode = mode;
+        if let Some(session) = &self.session {
+            session.update(cx, |model, _cx| model.session.set_memory_mode(mode));
+        }
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_exercise_order(&mut self, order: ExerciseOrder, cx: &mut Context<Self>) {
+        self.exercise_order = order;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_lesson_sort(&mut self, sort: LessonSort, cx: &mut Context<Self>) {
+        self.lesson_sort = sort;
+        self.sort_lessons();
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    /// 按当前排序方式重排课程列表（保持当前选中的课程）
+    fn sort_lessons(&mut self) {
+        self.sort_lessons_keeping(self.selected_lesson_id());
+    }
+
+    fn selected_lesson_id(&self) -> Option<u32> {
+        self.selected_lesson
+            .and_then(|idx| self.lessons.get(idx))
+            .map(|lesson| lesson.id)
+    }
+
+    /// 排序课程列表，并按 id 重新定位选中的课程（找不到时取消选中）
+    fn sort_lessons_keeping(&mut self, selected_id: Option<u32>) {
+        keyzen_engine::sort_lessons(
+            &mut self.lessons,
+            self.lesson_sort,
+            &last_practiced_for(&self.database, self.lesson_sort),
+        );
+        if let Some(id) = selected_id {
+            self.selected_lesson = self.lessons.iter().position(|lesson| lesson.id == id);
+        }
+    }
+
+    fn set_max_content_width(&mut self, width: Option<f32>, cx: &mut Context<Self>) {
+        self.max_content_width = width;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_show_keyboard(&mut self, show: bool, cx: &mut Context<Self>) {
+        self.show_keyboard = show;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_show_whitespace(&mut self, show: bool, cx: &mut Context<Self>) {
+        self.show_whitespace = show;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_hide_stats(&mut self, hide: bool, cx: &mut Context<Self>) {
+        self.hide_stats = hide;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_metronome_wpm(&mut self, wpm: Option<u32>, cx: &mut Context<Self>) {
+        self.metronome_wpm = wpm;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_visual_beat_bpm(&mut self, bpm: Option<u32>, cx: &mut Context<Self>) {
+        self.visual_beat_bpm = bpm;
+        if let Some(session) = &self.session {
+            session.update(cx, |session, cx| session.set_visual_beat(bpm, cx));
+        }
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_wpm_bands(&mut self, bands: Option<[u32; 3]>, cx: &mut Context<Self>) {
+        self.wpm_bands = bands;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_wpm_window(&mut self, window: Duration, cx: &mut Context<Self>) {
+        self.wpm_window = window;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_weak_unit_threshold(&mut self, threshold: f32, cx: &mut Context<Self>) {
+        self.weak_unit_threshold = threshold;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_preview_window(&mut self, window: Option<usize>, cx: &mut Context<Self>) {
+        self.preview_window = window;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_idle_timeout(&mut self, timeout: Option<Duration>, cx: &mut Context<Self>) {
+        self.idle_timeout = timeout;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_auto_advance(&mut self, auto_advance: AutoAdvance, cx: &mut Context<Self>) {
+        self.auto_advance = auto_advance;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_min_session_duration(&mut self, duration: Duration, cx: &mut Context<Self>) {
+        self.min_session_duration = duration;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_start_countdown(&mut self, secs: u64, cx: &mut Context<Self>) {
+        self.start_countdown_secs = secs;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_auto_advance_delay(&mut self, delay_ms: u64, cx: &mut Context<Self>) {
+        // 选择停顿时长即切换回自动跳转
+        self.set_auto_advance(AutoAdvance::OnPerfect, cx);
+        self.auto_advance_delay = Duration::from_millis(delay_ms);
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_allow_backspace(&mut self, allow: bool, cx: &mut Context<Self>) {
+        self.allow_backspace = allow;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_whitespace_policy(&mut self, policy: Option<WhitespacePolicy>, cx: &mut Context<Self>) {
+        self.whitespace_policy = policy;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_strict_recovery(&mut self, recovery: StrictRecovery, cx: &mut Context<Self>) {
+        self.strict_recovery = recovery;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_strict_completion(&mut self, strict: bool, cx: &mut Context<Self>) {
+        self.strict_completion = strict;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_perfect_practice(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.perfect_practice = enabled;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_preview_exercises(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.preview_exercises = enabled;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_memory_ramp(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.memory_ramp = enabled;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_skip_mastered(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.skip_mastered = enabled;
+
+        self.save_settings();
+
+        cx.notify();
+    }
+
+    fn set_cjk_literal_space(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.cjk_literal_space = enabled;
 
-        // 在清除 session 前保存数据
-        if let Some(session) = &self.session {
-            let db = self.database.clone();
-            session.update(cx, |session_model, _cx| {
-                if let Err(e) = session_model.session.save_to_database(&db) {
-                    eprintln!("保存会话数据失败: {}", e);
-                }
-            });
-        }
+        self.save_settings();
 
-        self.session = None;
-        self.selected_lesson = None;
-        self.show_history = false;
-        self.completion_snapshot = None; // 清除完成快照
-        self.focus_handle.focus(window);
         cx.notify();
     }
 
-    fn show_history(&mut self, _: &ShowHistory, window: &mut Window, cx: &mut Context<Self>) {
-        self.show_history = !self.show_history;
-        if self.show_history {
-            // 加载历史记录
-            self.cached_sessions = self.database.get_recent_sessions(10).unwrap_or_default();
+    fn set_cjk_net_wpm(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.cjk_net_wpm = enabled;
+        if let Some(session) = &self.session {
+            session.update(cx, |model, _cx| model.session.set_cjk_net_wpm(enabled));
         }
-        self.focus_handle.focus(window);
-        cx.notify();
-    }
 
-    fn show_settings(&mut self, _: &ShowSettings, window: &mut Window, cx: &mut Context<Self>) {
-        self.show_settings = !self.show_settings;
-        self.focus_handle.focus(window);
+        self.save_settings();
+
         cx.notify();
     }
 
-    fn toggle_theme(&mut self, _: &ToggleTheme, _window: &mut Window, cx: &mut Context<Self>) {
-        self.current_theme = match self.current_theme {
-            Theme::Dark => Theme::Light,
-            Theme::Light => Theme::Dark,
-        };
+    fn set_resume_on_launch(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.resume_on_launch = enabled;
 
-        // 保存主题配置到数据库
-        let theme_str = match self.current_theme {
-            Theme::Dark => "dark",
-            Theme::Light => "light",
-        };
-        if let Err(e) = self.database.save_config("theme", theme_str) {
-            eprintln!("保存主题配置失败: {}", e);
-        }
+        self.save_settings();
 
         cx.notify();
     }
 
-    fn set_memory_mode(&mut self, mode: MemoryMode, cx: &mut Context<Self>) {
-        self.memory_mode = mode;
+    fn set_ui_language(&mut self, language: UiLanguage, cx: &mut Context<Self>) {
+        self.strings = Strings::load(language);
+        set_app_menus(&self.strings, cx);
 
-        // 保存记忆模式配置到数据库
-        let mode_str = match mode {
-            MemoryMode::Off => "off",
-            MemoryMode::Complete => "complete",
-            MemoryMode::FirstLetter => "first_letter",
-            MemoryMode::Partial(PartialLevel::Low) => "partial_low",
-            MemoryMode::Partial(PartialLevel::Medium) => "partial_medium",
-            MemoryMode::Partial(PartialLevel::High) => "partial_high",
-        };
-        if let Err(e) = self.database.save_config("memory_mode", mode_str) {
-            eprintln!("保存记忆模式配置失败: {}", e);
-        }
+        self.save_settings();
 
         cx.notify();
     }
@@ -445,10 +1465,30 @@ impl KeyzenApp {
         }
     }
 
+    /// 按速度分段给 WPM 着色；未开启时使用 `default`
+    fn wpm_color(&self, wpm: f64, colors: &ThemeColors, default: Hsla) -> Hsla {
+        let Some([low, mid, high]) = self.wpm_bands else {
+            return default;
+        };
+
+        if wpm < low as f64 {
+            colors.text_muted
+        } else if wpm < mid as f64 {
+            colors.text_primary
+        } else if wpm < high as f64 {
+            colors.accent
+        } else {
+            rgb(WPM_GOLD).into()
+        }
+    }
+
     fn restart_lesson(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(lesson_index) = self.selected_lesson {
-            if let Some(lesson) = self.lessons.get(lesson_index).cloned() {
-                self.session = Some(cx.new(|cx| SessionModel::new(lesson, cx)));
+            if let Some(mut lesson) = self.lessons.get(lesson_index).cloned() {
+                // 设置了重新生成规则的课程每次重来都使用新内容
+                keyzen_data::regenerate_lesson(&mut lesson, &mut rand::thread_rng());
+                self.session = Some(self.create_session(lesson, cx));
+                self.observe_session(cx);
                 self.completion_snapshot = None; // 清除完成快照
                 self.focus_handle.focus(window);
                 cx.notify();
@@ -456,6 +1496,83 @@ impl KeyzenApp {
         }
     }
 
+    /// 打开当前会话的按键回放并开始播放
+    fn open_replay(&mut self, cx: &mut Context<Self>) {
+        let Some(session) = &self.session else {
+            return;
+        };
+        let Some(player) = ReplayPlayer::new(&session.read(cx).session) else {
+            eprintln!("会话重来时重新生成了练习内容，无法回放");
+            return;
+        };
+
+        self.replay = Some(ReplayModel {
+            player,
+            speed: 1.0,
+            playback: None,
+        });
+        debug!("⏯️ 打开按键回放");
+        self.schedule_replay_step(cx);
+        cx.notify();
+    }
+
+    /// 按倍速等到下一次按键时重放，直到播放完或暂停
+    fn schedule_replay_step(&mut self, cx: &mut Context<Self>) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+        let Some(wait) = replay.player.until_next() else {
+            replay.playback = None;
+            return;
+        };
+
+        let delay = wait.div_f32(replay.speed);
+        replay.playback = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(delay).await;
+            this.update(cx, |app, cx| {
+                // 期间已暂停、改变倍速或关闭回放时不再处理
+                let Some(task) = app.replay.as_mut().and_then(|r| r.playback.take()) else {
+                    return;
+                };
+                task.detach();
+                if let Some(replay) = &mut app.replay {
+                    replay.player.advance(wait);
+                }
+                app.schedule_replay_step(cx);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// 暂停或继续回放（播放完后从头开始）
+    fn toggle_replay_playback(&mut self, cx: &mut Context<Self>) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+
+        if replay.playback.take().is_none() {
+            if replay.player.is_finished() {
+                replay.player.rewind();
+            }
+            self.schedule_replay_step(cx);
+        }
+        cx.notify();
+    }
+
+    /// 设置回放倍速（播放中按新倍速重新计时）
+    fn set_replay_speed(&mut self, speed: f32, cx: &mut Context<Self>) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+
+        replay.speed = speed;
+        if replay.playback.is_some() {
+            self.schedule_replay_step(cx);
+        }
+        cx.notify();
+    }
+
     fn render_lesson_list(&self, cx: &mut Context<Self>) -> AnyElement {
         let colors = self.get_colors();
 
@@ -476,106 +1593,356 @@ impl KeyzenApp {
                             .text_size(px(20.0))
                             .font_weight(FontWeight::MEDIUM)
                             .text_color(colors.text_primary)
-                            .child("选择课程"),
+                            .child(self.strings.t("lesson_list.title")),
                     )
                     .child(
                         div()
-                            .px_4()
-                            .py_2()
-                            .bg(colors.bg_secondary)
-                            .hover(|style| style.bg(colors.bg_hover))
-                            .rounded(px(8.0))
-                            .cursor_pointer()
-                            .on_mouse_down(
-                                MouseButton::Left,
-                                cx.listener(|this, _event, window, cx| {
-                                    this.show_history(&ShowHistory, window, cx);
-                                }),
-                            )
+                            .flex()
+                            .gap_2()
+                            .when(!self.lessons.is_empty(), |el| {
+                                el.child(
+                                    div()
+                                        .px_4()
+                                        .py_2()
+                                        .bg(colors.bg_secondary)
+                                        .hover(|style| style.bg(colors.bg_hover))
+                                        .rounded(px(8.0))
+                                        .cursor_pointer()
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(|this, _event, window, cx| {
+                                                this.random_lesson(&RandomLesson, window, cx);
+                                            }),
+                                        )
+                                        .child(
+                                            div()
+                                                .text_size(px(14.0))
+                                                .text_color(colors.accent)
+                                                .child(self.strings.t("lesson_list.random")),
+                                        ),
+                                )
+                                .child(
+                                    div()
+                                        .px_4()
+                                        .py_2()
+                                        .bg(colors.bg_secondary)
+                                        .hover(|style| style.bg(colors.bg_hover))
+                                        .rounded(px(8.0))
+                                        .cursor_pointer()
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(|this, _event, window, cx| {
+                                                this.start_struggles_review(window, cx);
+                                            }),
+                                        )
+                                        .child(
+                                            div()
+                                                .text_size(px(14.0))
+                                                .text_color(colors.accent)
+                                                .child(self.strings.t("lesson_list.review")),
+                                        ),
+                                )
+                            })
                             .child(
                                 div()
-                                    .text_size(px(14.0))
-                                    .text_color(colors.accent)
-                                    .child("查看历史记录"),
+                                    .px_4()
+                                    .py_2()
+                                    .bg(colors.bg_secondary)
+                                    .hover(|style| style.bg(colors.bg_hover))
+                                    .rounded(px(8.0))
+                                    .cursor_pointer()
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _event, window, cx| {
+                                            this.show_history(&ShowHistory, window, cx);
+                                        }),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_size(px(14.0))
+                                            .text_color(colors.accent)
+                                            .child(self.strings.t("lesson_list.view_history")),
+                                    ),
                             ),
                     ),
             )
-            .child(
-                // 课程列表容器 - 可滚动
-                uniform_list(
-                    "lesson_list",
-                    self.lessons.len(),
-                    cx.processor(|this: &mut KeyzenApp, range, _window, cx| {
-                        let colors = this.get_colors();
-                        let mut items = Vec::new();
-                        for i in range {
-                            if let Some(lesson) = this.lessons.get(i).cloned() {
-                                let lesson_index = i;
-
-                                items.push(
-                                    div().id(i).px_8().py_2().child(
-                                        div()
-                                            .p_4()
-                                            .bg(colors.bg_secondary)
-                                            .hover(|style| style.bg(colors.bg_hover))
-                                            .rounded(px(12.0))
-                                            .cursor_pointer()
-                                            .on_mouse_down(
-                                                MouseButton::Left,
-                                                cx.listener(move |this, _event, window, cx| {
-                                                    this.start_lesson(lesson_index, window, cx);
-                                                }),
-                                            )
-                                            .child(
-                                                div()
-                                                    .flex()
-                                                    .flex_col()
-                                                    .gap_2()
-                                                    .child(
-                                                        div()
-                                                            .text_size(px(16.0))
-                                                            .font_weight(FontWeight::MEDIUM)
-                                                            .text_color(colors.text_primary)
-                                                            .child(format!(
-                                                                "{}. {}",
-                                                                i + 1,
-                                                                lesson.title
-                                                            )),
-                                                    )
-                                                    .child(
-                                                        div()
-                                                            .text_size(px(14.0))
-                                                            .text_color(colors.text_secondary)
-                                                            .child(lesson.description),
-                                                    ),
-                                            ),
-                                    ),
-                                );
+            .when_some(self.database_recovered.as_ref(), |el, path| {
+                el.child(
+                    // 数据库损坏恢复提示
+                    div()
+                        .p_4()
+                        .bg(colors.bg_secondary)
+                        .rounded(px(8.0))
+                        .cursor_pointer()
+                        .text_size(px(14.0))
+                        .text_color(colors.error)
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| {
+                                this.database_recovered = None;
+                                cx.notify();
+                            }),
+                        )
+                        .child(
+                            self.strings
+                                .tf("lesson_list.database_recovered", &[&path.display()]),
+                        ),
+                )
+            })
+            .when_some(self.exported_lesson_text.as_ref(), |el, path| {
+                el.child(
+                    // 课程文本导出提示
+                    div()
+                        .p_4()
+                        .bg(colors.bg_secondary)
+                        .rounded(px(8.0))
+                        .cursor_pointer()
+                        .text_size(px(14.0))
+                        .text_color(colors.text_secondary)
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| {
+                                this.exported_lesson_text = None;
+                                cx.notify();
+                            }),
+                        )
+                        .child(
+                            self.strings
+                                .tf("lesson_list.exported_text", &[&path.display()]),
+                        ),
+                )
+            })
+            .when(self.lessons.is_empty(), |el| {
+                el.child(self.render_empty_lessons(&colors, cx))
+            })
+            .when(!self.lessons.is_empty(), |el| {
+                el.child(
+                    // 课程列表容器 - 可滚动
+                    uniform_list(
+                        "lesson_list",
+                        self.lessons.len(),
+                        cx.processor(|this: &mut KeyzenApp, range: Range<usize>, _window, cx| {
+                            let colors = this.get_colors();
+                            let mut items = Vec::new();
+                            for i in range {
+                                if let Some(lesson) = this.lessons.get(i).cloned() {
+                                    let lesson_index = i;
+                                    // 上次练到中途的课程可以从第一个未练习的练习继续
+                                    let resume_index = resume_exercise(
+                                        this.practiced_exercises.get(&lesson.id),
+                                        lesson.exercises.len(),
+                                    );
+
+                                    items.push(
+                                        div().id(i).px_8().py_2().child(
+                                            div()
+                                                .p_4()
+                                                .bg(colors.bg_secondary)
+                                                .hover(|style| style.bg(colors.bg_hover))
+                                                .rounded(px(12.0))
+                                                .cursor_pointer()
+                                                .on_mouse_down(
+                                                    MouseButton::Left,
+                                                    cx.listener(move |this, _event, window, cx| {
+                                                        this.start_lesson(lesson_index, window, cx);
+                                                    }),
+                                                )
+                                                .child(
+                                                    div()
+                                                        .flex()
+                                                        .flex_col()
+                                                        .gap_2()
+                                                        .child(
+                                                            div()
+                                                                .text_size(px(16.0))
+                                                                .font_weight(FontWeight::MEDIUM)
+                                                                .text_color(colors.text_primary)
+                                                                .child(format!(
+                                                                    "{}. {}",
+                                                                    i + 1,
+                                                                    lesson.title
+                                                                )),
+                                                        )
+                                                        .child(
+                                                            div()
+                                                                .text_size(px(14.0))
+                                                                .text_color(colors.text_secondary)
+                                                                .child(lesson.description),
+                                                        )
+                                                        .when_some(resume_index, |el, index| {
+                                                            el.child(
+                                                                div()
+                                                                    .text_size(px(13.0))
+                                                                    .text_color(colors.accent)
+                                                                    .hover(|style| style.underline())
+                                                                    .on_mouse_down(
+                                                                        MouseButton::Left,
+                                                                        cx.listener(
+                                                                            move |this,
+                                                                                  _event,
+                                                                                  window,
+                                                                                  cx| {
+                                                                                cx.stop_propagation();
+                                                                                this.resume_lesson(
+                                                                                    lesson_index,
+                                                                                    index,
+                                                                                    window,
+                                                                                    cx,
+                                                                                );
+                                                                            },
+                                                                        ),
+                                                                    )
+                                                                    .child(this.strings.tf(
+                                                                        "lesson_list.resume",
+                                                                        &[&(index + 1)],
+                                                                    )),
+                                                            )
+                                                        })
+                                                        .child(this.render_export_text_link(
+                                                            lesson_index,
+                                                            &colors,
+                                                            cx,
+                                                        )),
+                                                ),
+                                        ),
+                                    );
+                                }
                             }
-                        }
-                        items
-                    }),
+                            items
+                        }),
+                    )
+                    .flex_1(),
                 )
-                .flex_1(),
-            )
+            })
             .into_any()
     }
 
+    /// 课程列表中的“导出文本”链接
+    fn render_export_text_link(
+        &self,
+        lesson_index: usize,
+        colors: &ThemeColors,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .text_size(px(13.0))
+            .text_color(colors.text_muted)
+            .hover(|style| style.underline())
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| {
+                    cx.stop_propagation();
+                    this.export_lesson_text_at(lesson_index, cx);
+                }),
+            )
+            .child(self.strings.t("lesson_list.export_text"))
+    }
+
+    /// 课程列表为空时的引导卡片：提示课程目录并提供导入入口
+    fn render_empty_lessons(
+        &self,
+        colors: &ThemeColors,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let lessons_dir = LessonLoader::get_user_data_dir_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+
+        div().flex_1().flex().justify_center().items_center().child(
+            div()
+                .flex()
+                .flex_col()
+                .items_center()
+                .gap_4()
+                .p_8()
+                .max_w(px(560.0))
+                .bg(colors.bg_secondary)
+                .rounded(px(12.0))
+                .child(
+                    div()
+                        .text_size(px(18.0))
+                        .font_weight(FontWeight::MEDIUM)
+                        .text_color(colors.text_primary)
+                        .child(self.strings.t("lesson_list.empty_title")),
+                )
+                .child(
+                    div()
+                        .text_size(px(14.0))
+                        .text_color(colors.text_secondary)
+                        .child(self.strings.t("lesson_list.empty_hint")),
+                )
+                .child(
+                    // 用户课程目录
+                    div()
+                        .px_3()
+                        .py_2()
+                        .bg(colors.bg_primary)
+                        .rounded(px(6.0))
+                        .text_size(px(13.0))
+                        .text_color(colors.text_muted)
+                        .child(lessons_dir),
+                )
+                .child(
+                    div()
+                        .px_4()
+                        .py_2()
+                        .bg(colors.accent)
+                        .rounded(px(8.0))
+                        .cursor_pointer()
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| {
+                                this.import_clipboard_text(cx);
+                            }),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(14.0))
+                                .text_color(rgb(0xFFFFFF))
+                                .child(self.strings.t("lesson_list.import_text")),
+                        ),
+                )
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(colors.text_muted)
+                        .child(self.strings.t("lesson_list.import_text_hint")),
+                ),
+        )
+    }
+
     fn render_history_view(&self, cx: &mut Context<Self>) -> AnyElement {
         let colors = self.get_colors();
 
         // 获取总体统计
-        let overall_stats = self.database.get_overall_stats().unwrap_or_else(|_| {
-            keyzen_persistence::OverallStats {
-                total_sessions: 0,
-                total_keystrokes: 0,
-                avg_wpm: 0.0,
-                max_wpm: 0.0,
-                avg_accuracy: 0.0,
-            }
-        });
+        let overall_stats =
+            self.database
+                .get_overall_stats()
+                .unwrap_or(keyzen_persistence::OverallStats {
+                    total_sessions: 0,
+                    total_keystrokes: 0,
+                    avg_wpm: 0.0,
+                    max_wpm: 0.0,
+                    avg_accuracy: 0.0,
+                });
+        // 获取累计练习时长
+        let total_minutes = self
+            .database
+            .get_total_practice_time()
+            .map(|d| d.as_secs() / 60)
+            .unwrap_or(0);
         // 获取薄弱单元数据（词云）
-        let weak_units = self.database.get_overall_weak_units(20).unwrap_or_default();
+        let weak_units = self
+            .database
+            .get_overall_weak_units_above(20, self.weak_unit_threshold)
+            .unwrap_or_default();
+        let show_word_cloud =
+            !weak_units.is_empty() || self.weak_unit_threshold > DEFAULT_WEAK_UNIT_THRESHOLD;
+        // 练习次数最多的课程
+        let favorites = self
+            .database
+            .get_most_practiced_lessons(FAVORITE_LESSONS_COUNT)
+            .unwrap_or_default();
 
         div()
             .flex()
@@ -595,7 +1962,7 @@ impl KeyzenApp {
                             .text_size(px(20.0))
                             .font_weight(FontWeight::MEDIUM)
                             .text_color(colors.text_primary)
-                            .child("练习历史"),
+                            .child(self.strings.t("history.title")),
                     )
                     .child(
                         div()
@@ -615,7 +1982,7 @@ impl KeyzenApp {
                                 div()
                                     .text_size(px(14.0))
                                     .text_color(colors.accent)
-                                    .child("返回课程列表"),
+                                    .child(self.strings.t("common.back_to_list")),
                             ),
                     ),
             )
@@ -639,7 +2006,7 @@ impl KeyzenApp {
                                         div()
                                             .text_size(px(14.0))
                                             .text_color(colors.text_secondary)
-                                            .child("总练习次数"),
+                                            .child(self.strings.t("history.total_sessions")),
                                     )
                                     .child(
                                         div()
@@ -658,7 +2025,7 @@ impl KeyzenApp {
                                         div()
                                             .text_size(px(14.0))
                                             .text_color(colors.text_secondary)
-                                            .child("平均速度"),
+                                            .child(self.strings.t("history.avg_speed")),
                                     )
                                     .child(
                                         div()
@@ -677,7 +2044,7 @@ impl KeyzenApp {
                                         div()
                                             .text_size(px(14.0))
                                             .text_color(colors.text_secondary)
-                                            .child("最高速度"),
+                                            .child(self.strings.t("history.max_speed")),
                                     )
                                     .child(
                                         div()
@@ -696,7 +2063,7 @@ impl KeyzenApp {
                                         div()
                                             .text_size(px(14.0))
                                             .text_color(colors.text_secondary)
-                                            .child("平均准确率"),
+                                            .child(self.strings.t("history.avg_accuracy")),
                                     )
                                     .child(
                                         div()
@@ -711,9 +2078,51 @@ impl KeyzenApp {
                             ),
                     ),
             )
-            .when(!weak_units.is_empty(), |this| {
+            .child(
+                // 累计练习时长
+                div()
+                    .text_size(px(14.0))
+                    .text_color(colors.text_secondary)
+                    .child(self.strings.tf(
+                        "history.total_time",
+                        &[&(total_minutes / 60), &(total_minutes % 60)],
+                    )),
+            )
+            .when(!favorites.is_empty(), |this| {
+                this.child(
+                    // 最常练习的课程
+                    div()
+                        .w_full()
+                        .p_6()
+                        .bg(colors.bg_secondary)
+                        .rounded(px(12.0))
+                        .flex()
+                        .flex_col()
+                        .gap_3()
+                        .child(
+                            div()
+                                .text_size(px(16.0))
+                                .font_weight(FontWeight::MEDIUM)
+                                .text_color(colors.text_primary)
+                                .child(self.strings.t("history.favorites")),
+                        )
+                        .children(favorites.into_iter().map(|(_, title, count)| {
+                            div()
+                                .flex()
+                                .justify_between()
+                                .text_size(px(14.0))
+                                .child(div().text_color(colors.text_primary).child(title))
+                                .child(
+                                    div().text_color(colors.text_secondary).child(
+                                        self.strings.tf("history.favorite_count", &[&count]),
+                                    ),
+                                )
+                        })),
+                )
+            })
+            .when(show_word_cloud, |this| {
                 this.child(
-                    // 薄弱模式词云（仅在有数据时显示）
+                    // 薄弱模式词云（仅在有数据或调高了阈值时显示）
                     div()
                         .w_full()
                         .p_6()
@@ -726,10 +2135,38 @@ impl KeyzenApp {
                                 .gap_4()
                                 .child(
                                     div()
-                                        .text_size(px(16.0))
-                                        .font_weight(FontWeight::MEDIUM)
-                                        .text_color(colors.text_primary)
-                                        .child("薄弱模式识别"),
+                                        .flex()
+                                        .justify_between()
+                                        .items_center()
+                                        .child(
+                                            div()
+                                                .text_size(px(16.0))
+                                                .font_weight(FontWeight::MEDIUM)
+                                                .text_color(colors.text_primary)
+                                                .child(self.strings.t("history.weak_units")),
+                                        )
+                                        .child(
+                                            // 最低错误率筛选
+                                            div()
+                                                .flex()
+                                                .items_center()
+                                                .gap_2()
+                                                .child(
+                                                    div()
+                                                        .text_size(px(13.0))
+                                                        .text_color(colors.text_muted)
+                                                        .child(self.strings.t("history.min_error_rate")),
+                                                )
+                                                .children(WEAK_UNIT_THRESHOLD_OPTIONS.map(|threshold| {
+                                                    self.render_option_button(
+                                                        self.weak_unit_threshold == threshold,
+                                                        &format!("{:.0}%", threshold * 100.0),
+                                                        &colors,
+                                                        move |this, cx| this.set_weak_unit_threshold(threshold, cx),
+                                                        cx,
+                                                    )
+                                                })),
+                                        ),
                                 )
                                 .child(self.render_word_cloud(weak_units, &colors))
                         ),
@@ -741,7 +2178,7 @@ impl KeyzenApp {
                     .text_size(px(16.0))
                     .font_weight(FontWeight::MEDIUM)
                     .text_color(colors.text_primary)
-                    .child("最近练习"),
+                    .child(self.strings.t("history.recent")),
             )
             .when(self.cached_sessions.is_empty(), |el| {
                 el.child(
@@ -750,7 +2187,7 @@ impl KeyzenApp {
                         .flex()
                         .justify_center()
                         .text_color(colors.text_muted)
-                        .child("暂无练习记录")
+                        .child(self.strings.t("history.empty"))
                 )
             })
             .when(!self.cached_sessions.is_empty(), |el| {
@@ -767,7 +2204,7 @@ impl KeyzenApp {
                                     let lesson_title = record.lesson_title;
                                     let completed_at = {
                                         let datetime = chrono::DateTime::from_timestamp(record.completed_at, 0)
-                                            .unwrap_or_else(|| chrono::Utc::now());
+                                            .unwrap_or_else(chrono::Utc::now);
                                         datetime.format("%Y-%m-%d %H:%M").to_string()
                                     };
                                     let wpm = format!("{:.0}", record.wpm);
@@ -820,7 +2257,7 @@ impl KeyzenApp {
                                                                                 div()
                                                                                     .text_size(px(14.0))
                                                                                     .text_color(colors.text_secondary)
-                                                                                    .child("速度"),
+                                                                                    .child(this.strings.t("common.speed")),
                                                                             )
                                                                             .child(
                                                                                 div()
@@ -839,7 +2276,7 @@ impl KeyzenApp {
                                                                                 div()
                                                                                     .text_size(px(14.0))
                                                                                     .text_color(colors.text_secondary)
-                                                                                    .child("准确率"),
+                                                                                    .child(this.strings.t("common.accuracy")),
                                                                             )
                                                                             .child(
                                                                                 div()
@@ -867,32 +2304,133 @@ impl KeyzenApp {
     fn render_practice_area(&mut self, cx: &mut Context<Self>) -> AnyElement {
         let colors = self.get_colors();
 
-        let (snapshot, target_text, display_text, input_text, progress, current_exercise) =
-            if let Some(session) = &self.session {
-                let session_read = session.read(cx);
-                let (current, total) = session_read.session.get_progress();
-                (
-                    session_read.get_snapshot(),
-                    session_read.get_target_text().to_string(),
-                    session_read.generate_display_text(self.memory_mode),
-                    session_read.get_input_text(),
-                    (current, total),
-                    session_read.session.get_current_exercise().clone(),
-                )
-            } else {
-                return div().into_any();
-            };
+        let (
+            snapshot,
+            target_text,
+            display_text,
+            input_text,
+            progress,
+            current_exercise,
+            next_char,
+            metronome_pos,
+            preview_range,
+            unreachable_count,
+            accuracy_series,
+            is_idle,
+            is_paused,
+            continue_prompt,
+            cursor_line_col,
+            is_mastered,
+            perfect_attempt,
+            awaiting_start,
+            countdown,
+        ) = if let Some(session) = &self.session {
+            let session_read = session.read(cx);
+            let (current, total) = session_read.session.get_progress();
+            // 节拍器从首次按键开始推进
+            let metronome_pos = self.metronome_wpm.and_then(|wpm| {
+                session_read
+                    .session
+                    .exercise_elapsed()
+                    .map(|elapsed| session_read.session.metronome_position(wpm as f64, elapsed))
+            });
+            (
+                session_read.get_snapshot(),
+                session_read.get_target_text().to_string(),
+                session_read.generate_display_text(self.memory_mode),
+                session_read.get_input_text(),
+                (current, total),
+                session_read.session.get_current_exercise().clone(),
+                session_read.session.current_target_char(),
+                metronome_pos,
+                // 超长练习只渲染光标附近的窗口
+                session_read
+                    .session
+                    .preview_range(self.preview_window.unwrap_or(0)),
+                // 键盘和系统输入法都无法输入的字符
+                session_read
+                    .session
+                    .unreachable_positions(|ch| {
+                        KeyboardLayout::Qwerty.can_type(ch) || is_ime_char(ch)
+                    })
+                    .len(),
+                session_read
+                    .session
+                    .get_recent_accuracy_series(ACCURACY_SPARKLINE_LEN),
+                session_read.session.is_idle(),
+                session_read.session.is_paused(),
+                // 手动跳转模式下练习完成后提示按 Enter
+                (session_read.session.get_auto_advance() == AutoAdvance::Manual
+                    && session_read.session.is_current_exercise_complete())
+                .then(|| {
+                    // 与 advance_or_finish 一致：渐进隐藏重复时也是继续而非结束
+                    if session_read.session.will_continue() {
+                        "practice.press_enter_next"
+                    } else {
+                        "practice.press_enter_finish"
+                    }
+                }),
+                // 多行练习显示光标所在行列
+                session_read
+                    .get_target_text()
+                    .contains('\n')
+                    .then(|| session_read.session.cursor_line_col()),
+                session_read
+                    .mastered_exercises
+                    .contains(&session_read.session.current_lesson_exercise_index()),
+                // 完美练习显示当前尝试次数
+                session_read
+                    .session
+                    .is_perfect_practice()
+                    .then(|| session_read.session.current_attempt()),
+                session_read.session.is_awaiting_start(),
+                session_read.countdown,
+            )
+        } else {
+            return div().into_any();
+        };
 
-        let target_chars: Vec<char> = target_text.chars().collect();
-        let display_chars: Vec<char> = display_text.chars().collect();
-        let input_chars: Vec<char> = input_text.chars().collect();
+        let goal_text = self.lesson_goal_text();
+        // 与引擎一致按字素簇对齐位置
+        let target_chars: Vec<&str> = target_text.graphemes(true).collect();
+        let display_chars: Vec<&str> = display_text.graphemes(true).collect();
+        let input_chars: Vec<&str> = input_text.graphemes(true).collect();
 
-        // 获取当前课程名称
+        // 最近出错位置对应的目标字符（屏幕键盘上标记）
+        let error_chars: HashSet<char> = snapshot
+            .recent_errors
+            .iter()
+            .filter_map(|&pos| target_chars.get(pos)?.chars().next())
+            .collect();
+
+        // 获取当前课程名称（复习等一次性课程不在课程列表中）
         let lesson_title = self
             .selected_lesson
             .and_then(|idx| self.lessons.get(idx))
-            .map(|lesson| lesson.title.clone())
-            .unwrap_or_default();
+            .map(|lesson| lesson.title.clone())
+            .or_else(|| {
+                self.session
+                    .as_ref()
+                    .map(|session| session.read(cx).session.get_lesson_title().to_string())
+            })
+            .unwrap_or_default();
+
+        // 课程开启换行标记时用 ↵ 标出需要按回车的位置
+        let mark_newlines = self
+            .selected_lesson
+            .and_then(|idx| self.lessons.get(idx))
+            .is_some_and(|lesson| lesson.mark_newlines);
+        let show_whitespace = self.show_whitespace;
+        let peek_hint = self
+            .session
+            .as_ref()
+            .and_then(|session| session.read(cx).peek_hint.clone());
+
+        // 视觉节拍：每拍开始时短暂亮起
+        let beat_lit = self
+            .visual_beat_bpm
+            .zip(self.session.as_ref())
+            .map(|(bpm, session)| visual_beat_lit(session.read(cx).started_at.elapsed(), bpm));
 
         let content = div()
             .flex()
@@ -908,14 +2446,23 @@ impl KeyzenApp {
                     .p_8()
                     .pb_4()
                     .child(
-                        // 课程名称
+                        // 课程名称（开启视觉节拍时右侧显示节拍指示点）
                         div()
                             .flex()
                             .justify_center()
+                            .items_center()
+                            .gap_2()
                             .text_size(px(18.0))
                             .font_weight(FontWeight::MEDIUM)
                             .text_color(colors.text_primary)
-                            .child(lesson_title),
+                            .child(lesson_title)
+                            .when_some(beat_lit, |el, lit| {
+                                el.child(div().size(px(8.0)).rounded_full().bg(if lit {
+                                    colors.accent
+                                } else {
+                                    colors.bg_secondary
+                                }))
+                            }),
                     )
                     .child(
                         // 练习进度 + 导航按钮
@@ -949,6 +2496,7 @@ impl KeyzenApp {
                                             cx.listener(|this, _event, _window, cx| {
                                                 if let Some(session) = &this.session {
                                                     session.update(cx, |session_model, cx| {
+                                                        session_model.cancel_pending_advance();
                                                         session_model
                                                             .session
                                                             .go_to_previous_exercise();
@@ -966,7 +2514,7 @@ impl KeyzenApp {
                                             } else {
                                                 colors.text_muted
                                             })
-                                            .child("← 上一个"),
+                                            .child(self.strings.t("practice.previous")),
                                     ),
                             )
                             .child(
@@ -974,7 +2522,10 @@ impl KeyzenApp {
                                 div()
                                     .text_size(px(14.0))
                                     .text_color(colors.text_secondary)
-                                    .child(format!("练习 {}/{}", progress.0 + 1, progress.1)),
+                                    .child(self.strings.tf(
+                                        "practice.progress",
+                                        &[&(progress.0 + 1), &progress.1],
+                                    )),
                             )
                             .child(
                                 // 下一个按钮
@@ -1001,6 +2552,7 @@ impl KeyzenApp {
                                             cx.listener(|this, _event, _window, cx| {
                                                 if let Some(session) = &this.session {
                                                     session.update(cx, |session_model, cx| {
+                                                        session_model.cancel_pending_advance();
                                                         session_model.session.go_to_next_exercise();
                                                         cx.notify();
                                                     });
@@ -1016,12 +2568,12 @@ impl KeyzenApp {
                                             } else {
                                                 colors.text_muted
                                             })
-                                            .child("下一个 →"),
+                                            .child(self.strings.t("practice.next")),
                                     ),
                             ),
                     )
                     .child(
-                        // 统计信息
+                        // 统计信息（专注模式下隐藏但保留位置，避免练习区跳动）
                         div()
                             .flex()
                             .justify_center()
@@ -1029,39 +2581,105 @@ impl KeyzenApp {
                             .text_sm()
                             .font_family("JetBrains Mono")
                             .text_color(colors.text_secondary)
+                            .when(self.hide_stats, |el| el.invisible())
                             .child(
                                 div().flex().gap_1().child("WPM:").child(
                                     div()
                                         .w(px(36.0))
                                         .text_align(TextAlign::Right)
+                                        .text_color(self.wpm_color(
+                                            snapshot.current_wpm,
+                                            &colors,
+                                            colors.text_secondary,
+                                        ))
                                         .child(format!("{:.0}", snapshot.current_wpm)),
                                 ),
                             )
                             .child("|")
                             .child(
-                                div().flex().gap_1().child("准确率:").child(
-                                    div()
-                                        .w(px(60.0))
-                                        .text_align(TextAlign::Right)
-                                        .child(format!("{:.1}%", snapshot.accuracy * 100.0)),
-                                ),
+                                div()
+                                    .flex()
+                                    .gap_1()
+                                    .child(self.strings.t("practice.accuracy_label"))
+                                    .child(
+                                        div()
+                                            .w(px(60.0))
+                                            .text_align(TextAlign::Right)
+                                            .child(format!("{:.1}%", snapshot.accuracy * 100.0)),
+                                    )
+                                    .child(
+                                        // 最近窗口准确率趋势
+                                        div().h(px(16.0)).flex().items_end().gap(px(1.0)).children(
+                                            accuracy_series.iter().map(|&acc| {
+                                                div().w(px(2.0)).h(px(2.0 + acc as f32 * 14.0)).bg(
+                                                    if acc >= 0.9 {
+                                                        colors.accent
+                                                    } else {
+                                                        colors.error
+                                                    },
+                                                )
+                                            }),
+                                        ),
+                                    ),
                             )
                             .child("|")
                             .child(
-                                div().flex().gap_1().child("进度:").child(
+                                div()
+                                    .flex()
+                                    .gap_1()
+                                    .child(self.strings.t("practice.first_try_label"))
+                                    .child(div().w(px(60.0)).text_align(TextAlign::Right).child(
+                                        format!("{:.1}%", snapshot.first_try_accuracy * 100.0),
+                                    )),
+                            )
+                            .child("|")
+                            .child(
+                                div()
+                                    .flex()
+                                    .gap_1()
+                                    .child(self.strings.t("practice.progress_label"))
+                                    .child(
+                                        div()
+                                            .w(px(48.0))
+                                            .text_align(TextAlign::Right)
+                                            .child(format!("{:.0}%", snapshot.progress * 100.0)),
+                                    ),
+                            )
+                            .when_some(cursor_line_col, |el, (line, col)| {
+                                el.child("|").child(
+                                    self.strings
+                                        .tf("practice.line_col", &[&(line + 1), &(col + 1)]),
+                                )
+                            })
+                            .when_some(perfect_attempt, |el, attempt| {
+                                el.child("|")
+                                    .child(self.strings.tf("practice.attempt", &[&attempt]))
+                            })
+                            .when(is_mastered, |el| {
+                                el.child("|").child(
                                     div()
-                                        .w(px(48.0))
-                                        .text_align(TextAlign::Right)
-                                        .child(format!("{:.0}%", snapshot.progress * 100.0)),
-                                ),
-                            ),
+                                        .text_color(colors.accent)
+                                        .child(self.strings.t("practice.mastered")),
+                                )
+                            })
+                            .when_some(goal_text, |el, goal| {
+                                // 课程作者设定的目标
+                                el.child("|").child(
+                                    div()
+                                        .flex()
+                                        .gap_1()
+                                        .child(self.strings.t("practice.goal_label"))
+                                        .child(goal),
+                                )
+                            }),
                     ),
             )
             .child(
-                // 打字区域（占据剩余空间）
-                div().flex_1().px_8().pb_4().child(
+                // 打字区域（占据剩余空间，宽屏时居中并限制宽度）
+                div().flex_1().flex().justify_center().px_8().pb_4().child(
                     div()
                         .w_full()
+                        .when_some(self.max_content_width, |el, width| el.max_w(px(width)))
                         .p_12()
                         .bg(colors.bg_secondary)
                         .rounded(px(16.0))
@@ -1077,63 +2695,250 @@ impl KeyzenApp {
                                     .child(current_exercise.hint.as_ref().unwrap().clone()),
                             )
                         })
+                        .when_some(continue_prompt, |el, key| {
+                            el.child(
+                                // 手动跳转提示
+                                div()
+                                    .flex()
+                                    .justify_center()
+                                    .text_size(px(14.0))
+                                    .text_color(colors.accent)
+                                    .child(self.strings.t(key)),
+                            )
+                        })
+                        .when(is_idle, |el| {
+                            el.child(
+                                // 暂停提示
+                                div()
+                                    .flex()
+                                    .justify_center()
+                                    .text_size(px(14.0))
+                                    .text_color(colors.text_secondary)
+                                    .child(self.strings.t("practice.idle_paused")),
+                            )
+                        })
+                        .when_some(peek_hint, |el, hint| {
+                            el.child(
+                                // 接下来字符的临时提示
+                                div()
+                                    .flex()
+                                    .justify_center()
+                                    .text_size(px(14.0))
+                                    .text_color(colors.accent)
+                                    .child(self.strings.tf("practice.peek_hint", &[&hint])),
+                            )
+                        })
+                        .when(is_paused, |el| {
+                            el.child(
+                                // 手动暂停提示
+                                div()
+                                    .flex()
+                                    .justify_center()
+                                    .text_size(px(14.0))
+                                    .text_color(colors.accent)
+                                    .child(self.strings.t("practice.paused")),
+                            )
+                        })
+                        .when_some(countdown, |el, remaining| {
+                            el.child(
+                                // 开始前倒计时
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .items_center()
+                                    .gap_2()
+                                    .child(
+                                        div()
+                                            .font_family("JetBrains Mono")
+                                            .text_size(px(48.0))
+                                            .text_color(colors.accent)
+                                            .child(remaining.to_string()),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_size(px(12.0))
+                                            .text_color(colors.text_muted)
+                                            .child(self.strings.t("practice.countdown_hint")),
+                                    ),
+                            )
+                        })
+                        .when(awaiting_start, |el| {
+                            el.child(
+                                // 练习预览：显示完整内容，开始后才计时
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_6()
+                                    .child(
+                                        div()
+                                            .font_family("JetBrains Mono")
+                                            .text_size(px(18.0))
+                                            .line_height(px(28.0))
+                                            .text_color(colors.text_secondary)
+                                            .children(target_text.split_inclusive('\n').map(
+                                                |line| {
+                                                    div()
+                                                        .min_h(px(28.0))
+                                                        .child(preview_line(line, show_whitespace))
+                                                },
+                                            )),
+                                    )
+                                    .child(
+                                        div()
+                                            .flex()
+                                            .flex_col()
+                                            .items_center()
+                                            .gap_2()
+                                            .child(
+                                                div()
+                                                    .px_6()
+                                                    .py_2()
+                                                    .bg(colors.accent)
+                                                    .rounded(px(8.0))
+                                                    .cursor_pointer()
+                                                    .text_size(px(14.0))
+                                                    .text_color(colors.bg_primary)
+                                                    .on_mouse_down(
+                                                        MouseButton::Left,
+                                                        cx.listener(|this, _event, _window, cx| {
+                                                            if let Some(session) = &this.session {
+                                                                session.update(cx, |model, cx| {
+                                                                    model.start_exercise(cx)
+                                                                });
+                                                            }
+                                                        }),
+                                                    )
+                                                    .child(self.strings.t("practice.start")),
+                                            )
+                                            .child(
+                                                div()
+                                                    .text_size(px(12.0))
+                                                    .text_color(colors.text_muted)
+                                                    .child(self.strings.t("practice.preview_hint")),
+                                            ),
+                                    ),
+                            )
+                        })
+                        .when(unreachable_count > 0, |el| {
+                            el.child(
+                                // 无法输入的字符警告
+                                div().text_size(px(13.0)).text_color(colors.error).child(
+                                    self.strings
+                                        .tf("practice.unreachable_warning", &[&unreachable_count]),
+                                ),
+                            )
+                        })
                         .child(
-                            // 打字文本
+                            // 打字文本（暂停或倒计时时变暗，预览时隐藏）
                             div()
                                 .w_full()
+                                .when(is_idle || is_paused || countdown.is_some(), |el| {
+                                    el.opacity(0.4)
+                                })
+                                .when(awaiting_start, |el| el.hidden())
                                 .font_family("JetBrains Mono")
                                 .text_size(px(24.0))
                                 .line_height(px(36.0))
                                 .flex()
                                 .flex_row()
                                 .flex_wrap()
-                                .children(display_chars.iter().enumerate().map(
-                                    |(i, &display_char)| {
-                                        let target_char =
-                                            target_chars.get(i).copied().unwrap_or(' ');
-
-                                        // 决定显示什么字符：已正确输入的显示真实字符，其他显示隐藏字符
-                                        let show_char = if i < input_chars.len() {
-                                            let input_char = input_chars[i];
-                                            if input_char == target_char {
-                                                target_char // 输入正确，显示真实字符
+                                .when(preview_range.start > 0, |el| {
+                                    // 窗口之前还有已输入文本
+                                    el.child(
+                                        div().h(px(36.0)).text_color(colors.text_muted).child("… "),
+                                    )
+                                })
+                                .children(
+                                    display_chars
+                                        .iter()
+                                        .enumerate()
+                                        .skip(preview_range.start)
+                                        .take(preview_range.len())
+                                        .flat_map(|(i, &display_char)| {
+                                            let target_char =
+                                                target_chars.get(i).copied().unwrap_or(" ");
+
+                                            // 决定显示什么字符：已正确输入的显示真实字符，其他显示隐藏字符
+                                            let show_char = if i < input_chars.len() {
+                                                let input_char = input_chars[i];
+                                                if input_char == target_char {
+                                                    target_char // 输入正确，显示真实字符
+                                                } else {
+                                                    display_char // 输入错误，显示隐藏字符（会标红）
+                                                }
                                             } else {
-                                                display_char // 输入错误，显示隐藏字符（会标红）
-                                            }
-                                        } else {
-                                            display_char // 未输入，显示隐藏字符
-                                        };
-
-                                        let (color, bg_color) = if i < input_chars.len() {
-                                            let input_char = input_chars[i];
-                                            if input_char == target_char {
-                                                (colors.text_primary, None)
+                                                display_char // 未输入，显示隐藏字符
+                                            };
+
+                                            let (color, bg_color) = if i < input_chars.len() {
+                                                let input_char = input_chars[i];
+                                                if input_char == target_char {
+                                                    (colors.text_primary, None)
+                                                } else {
+                                                    (colors.error, Some(colors.error_bg))
+                                                }
+                                            } else if i == input_chars.len() {
+                                                (rgb(0x000000).into(), Some(colors.cursor))
                                             } else {
-                                                (colors.error, Some(colors.error_bg))
-                                            }
-                                        } else if i == input_chars.len() {
-                                            (rgb(0x000000).into(), Some(colors.cursor))
-                                        } else {
-                                            (colors.text_secondary, None)
-                                        };
-
-                                        let mut char_div = div()
-                                            .h(px(36.0))
-                                            .flex()
-                                            .items_center()
-                                            .text_color(color)
-                                            .child(show_char.to_string());
+                                                (colors.text_secondary, None)
+                                            };
+
+                                            let show_char = match show_char {
+                                                "\n" if mark_newlines || show_whitespace => "↵",
+                                                "\n" => " ",
+                                                " " if show_whitespace => "·",
+                                                grapheme => grapheme,
+                                            };
+                                            // 空白标记用弱化颜色，与正文区分
+                                            let color = if show_whitespace
+                                                && target_char.trim().is_empty()
+                                                && bg_color.is_none()
+                                            {
+                                                colors.text_muted
+                                            } else {
+                                                color
+                                            };
+
+                                            let mut char_div = div()
+                                                .h(px(36.0))
+                                                .flex()
+                                                .items_center()
+                                                .text_color(color)
+                                                .child(show_char.to_string());
 
-                                        if let Some(bg) = bg_color {
-                                            char_div = char_div.bg(bg);
-                                        }
+                                            if let Some(bg) = bg_color {
+                                                char_div = char_div.bg(bg);
+                                            }
+                                            if metronome_pos == Some(i) {
+                                                // 节拍器位置：下划线标记目标节奏
+                                                char_div = char_div
+                                                    .border_b_2()
+                                                    .border_color(colors.accent);
+                                            }
 
-                                        char_div
-                                    },
-                                )),
+                                            let mut items = vec![char_div.into_any_element()];
+                                            if target_char == "\n" {
+                                                // 目标换行处强制折行
+                                                items.push(
+                                                    div().w_full().h(px(0.0)).into_any_element(),
+                                                );
+                                            }
+                                            items
+                                        }),
+                                ),
                         ),
                 ),
             )
+            .when(self.show_keyboard, |el| {
+                el.child(
+                    // 屏幕键盘
+                    div()
+                        .flex()
+                        .justify_center()
+                        .px_8()
+                        .child(self.render_keyboard(next_char, &error_chars, &colors)),
+                )
+            })
             .child(
                 // 固定在底部的提示
                 div()
@@ -1143,8 +2948,15 @@ impl KeyzenApp {
                     .flex()
                     .justify_center()
                     .text_xs()
-                    .text_color(colors.text_muted)
-                    .child("按 Esc 返回课程列表"),
+                    .map(|el| {
+                        if self.confirm_abort {
+                            el.text_color(colors.error)
+                                .child(self.strings.t("practice.abort_confirm"))
+                        } else {
+                            el.text_color(colors.text_muted)
+                                .child(self.strings.t("practice.esc_hint"))
+                        }
+                    }),
             );
 
         PracticeAreaElement {
@@ -1154,6 +2966,19 @@ impl KeyzenApp {
         .into_any()
     }
 
+    /// 当前课程的作者目标（如 "50 WPM · 98%"），未设置时返回 None
+    fn lesson_goal_text(&self) -> Option<String> {
+        let meta = &self.lessons.get(self.selected_lesson?)?.meta;
+        let mut parts = Vec::new();
+        if let Some(wpm) = meta.target_wpm {
+            parts.push(format!("{:.0} WPM", wpm));
+        }
+        if let Some(accuracy) = meta.target_accuracy {
+            parts.push(format!("{:.0}%", accuracy * 100.0));
+        }
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+
     fn render_completion_stats(
         &self,
         snapshot: keyzen_engine::SessionSnapshot,
@@ -1161,11 +2986,92 @@ impl KeyzenApp {
     ) -> AnyElement {
         let colors = self.get_colors();
 
-        // 获取当前课程名称
+        // 大小写错误总数及其中的句首大小写错误
+        let (capitalization_errors, sentence_start_errors) = self
+            .session
+            .as_ref()
+            .map(|session| {
+                let session = &session.read(cx).session;
+                let sentence_start = session
+                    .get_error_categories()
+                    .get(&ErrorCategory::Capitalization)
+                    .copied()
+                    .unwrap_or(0);
+                (session.get_capitalization_errors(), sentence_start)
+            })
+            .unwrap_or((0, 0));
+
+        // 重来时重新生成过已输入的练习内容则无法回放
+        let replayable = self
+            .session
+            .as_ref()
+            .is_some_and(|session| session.read(cx).session.is_replayable());
+
+        // 是否达到课程作者设定的目标
+        let goal_result = self.lesson_goal_text().zip(
+            self.session
+                .as_ref()
+                .and_then(|session| session.read(cx).session.meets_lesson_goal()),
+        );
+
+        // 按历史进步趋势估算还需几次达到目标（未设置目标时按掌握准确率估算）
+        let sessions_to_mastery = self
+            .selected_lesson
+            .and_then(|idx| self.lessons.get(idx))
+            .and_then(|lesson| {
+                self.database
+                    .estimate_sessions_to_mastery(
+                        lesson.id,
+                        lesson.meta.target_wpm.unwrap_or(0.0),
+                        lesson.meta.target_accuracy.unwrap_or(MASTERY_ACCURACY),
+                    )
+                    .map_err(|e| eprintln!("估算掌握所需次数失败: {}", e))
+                    .ok()
+                    .flatten()
+            })
+            .filter(|n| *n > 0);
+
+        // 本次会话最快的 5 秒爆发 WPM
+        let peak_wpm = Some(snapshot.peak_wpm).filter(|wpm| *wpm > 0.0);
+
+        // 按键间隔百分位
+        let latency = self
+            .session
+            .as_ref()
+            .and_then(|session| session.read(cx).session.latency_profile());
+
+        // 最长的心流时长（不足 1 秒不显示）
+        let flow_secs = self
+            .session
+            .as_ref()
+            .map(|session| session.read(cx).session.flow_duration().as_secs())
+            .filter(|secs| *secs > 0);
+
+        // 节奏稳定度及其在历史中的百分位（本次会话尚未保存）
+        let consistency = self
+            .session
+            .as_ref()
+            .and_then(|session| session.read(cx).session.consistency())
+            .map(|consistency| {
+                let percentile = self
+                    .database
+                    .get_consistency_percentile(consistency)
+                    .map_err(|e| eprintln!("查询节奏稳定度百分位失败: {}", e))
+                    .ok()
+                    .flatten();
+                (consistency, percentile)
+            });
+
+        // 获取当前课程名称（复习等一次性课程不在课程列表中）
         let lesson_title = self
             .selected_lesson
             .and_then(|idx| self.lessons.get(idx))
             .map(|lesson| lesson.title.clone())
+            .or_else(|| {
+                self.session
+                    .as_ref()
+                    .map(|session| session.read(cx).session.get_lesson_title().to_string())
+            })
             .unwrap_or_default();
 
         div()
@@ -1182,7 +3088,7 @@ impl KeyzenApp {
                     .text_size(px(28.0))
                     .font_weight(FontWeight::BOLD)
                     .text_color(colors.accent)
-                    .child("课程完成！"),
+                    .child(self.strings.t("completion.title")),
             )
             .child(
                 // 课程名称
@@ -1215,13 +3121,17 @@ impl KeyzenApp {
                                         div()
                                             .text_size(px(16.0))
                                             .text_color(colors.text_secondary)
-                                            .child("速度 (WPM)"),
+                                            .child(self.strings.t("completion.wpm")),
                                     )
                                     .child(
                                         div()
                                             .text_size(px(24.0))
                                             .font_weight(FontWeight::BOLD)
-                                            .text_color(colors.text_primary)
+                                            .text_color(self.wpm_color(
+                                                snapshot.current_wpm,
+                                                &colors,
+                                                colors.text_primary,
+                                            ))
                                             .child(format!("{:.0}", snapshot.current_wpm)),
                                     ),
                             )
@@ -1235,7 +3145,7 @@ impl KeyzenApp {
                                         div()
                                             .text_size(px(16.0))
                                             .text_color(colors.text_secondary)
-                                            .child("准确率"),
+                                            .child(self.strings.t("common.accuracy")),
                                     )
                                     .child(
                                         div()
@@ -1244,7 +3154,141 @@ impl KeyzenApp {
                                             .text_color(colors.text_primary)
                                             .child(format!("{:.1}%", snapshot.accuracy * 100.0)),
                                     ),
-                            ),
+                            )
+                            .child(
+                                // 首次正确率
+                                div()
+                                    .w_full()
+                                    .flex()
+                                    .justify_between()
+                                    .child(
+                                        div()
+                                            .text_size(px(16.0))
+                                            .text_color(colors.text_secondary)
+                                            .child(self.strings.t("common.first_try_accuracy")),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_size(px(24.0))
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(colors.text_primary)
+                                            .child(format!(
+                                                "{:.1}%",
+                                                snapshot.first_try_accuracy * 100.0
+                                            )),
+                                    ),
+                            )
+                            .when_some(goal_result, |el, (goal, met)| {
+                                el.child(
+                                    div()
+                                        .text_size(px(14.0))
+                                        .text_color(if met { colors.accent } else { colors.error })
+                                        .child(self.strings.tf(
+                                            if met {
+                                                "completion.goal_met"
+                                            } else {
+                                                "completion.goal_missed"
+                                            },
+                                            &[&goal],
+                                        )),
+                                )
+                            })
+                            .when_some(sessions_to_mastery, |el, sessions| {
+                                el.child(
+                                    div()
+                                        .text_size(px(14.0))
+                                        .text_color(colors.text_secondary)
+                                        .child(
+                                            self.strings
+                                                .tf("completion.sessions_to_mastery", &[&sessions]),
+                                        ),
+                                )
+                            })
+                            .when_some(peak_wpm, |el, wpm| {
+                                el.child(
+                                    div()
+                                        .text_size(px(14.0))
+                                        .text_color(self.wpm_color(
+                                            wpm,
+                                            &colors,
+                                            colors.text_secondary,
+                                        ))
+                                        .child(
+                                            self.strings.tf(
+                                                "completion.peak_wpm",
+                                                &[&format!("{:.0}", wpm)],
+                                            ),
+                                        ),
+                                )
+                            })
+                            .when_some(consistency, |el, (consistency, percentile)| {
+                                let consistency = format!("{:.0}%", consistency * 100.0);
+                                let text = match percentile {
+                                    Some(p) if p >= 1.0 => self
+                                        .strings
+                                        .tf("completion.consistency_best", &[&consistency]),
+                                    Some(p) => self.strings.tf(
+                                        "completion.consistency_percentile",
+                                        &[&consistency, &format!("{:.0}%", p * 100.0)],
+                                    ),
+                                    None => {
+                                        self.strings.tf("completion.consistency", &[&consistency])
+                                    }
+                                };
+                                el.child(
+                                    div()
+                                        .text_size(px(14.0))
+                                        .text_color(colors.text_secondary)
+                                        .child(text),
+                                )
+                            })
+                            .when_some(latency, |el, latency| {
+                                el.child(
+                                    div()
+                                        .text_size(px(14.0))
+                                        .text_color(colors.text_secondary)
+                                        .child(self.strings.tf(
+                                            "completion.latency",
+                                            &[
+                                                &latency.p50.as_millis(),
+                                                &latency.p95.as_millis(),
+                                                &latency.p99.as_millis(),
+                                            ],
+                                        )),
+                                )
+                            })
+                            .when_some(flow_secs, |el, secs| {
+                                el.child(
+                                    div()
+                                        .text_size(px(14.0))
+                                        .text_color(colors.text_secondary)
+                                        .child(self.strings.tf("completion.flow", &[&secs])),
+                                )
+                            })
+                            .when(capitalization_errors > 0, |el| {
+                                // 大小写错误单独提示
+                                el.child(div().text_size(px(14.0)).text_color(colors.error).child(
+                                    self.strings.tf(
+                                        "completion.capitalization_errors",
+                                        &[&capitalization_errors, &sentence_start_errors],
+                                    ),
+                                ))
+                            })
+                            .when_some(snapshot.pace_deviation, |el, deviation| {
+                                // 节奏偏差（开启节拍器时）
+                                el.child(
+                                    div()
+                                        .text_size(px(14.0))
+                                        .text_color(colors.text_secondary)
+                                        .child(self.strings.tf(
+                                            "completion.pace_deviation",
+                                            &[
+                                                &format!("{:+.1}", deviation.mean),
+                                                &format!("{:.1}", deviation.mean_abs),
+                                            ],
+                                        )),
+                                )
+                            }),
                     ),
             )
             .child(
@@ -1283,9 +3327,31 @@ impl KeyzenApp {
                                     .text_size(px(16.0))
                                     .font_weight(FontWeight::MEDIUM)
                                     .text_color(rgb(0x000000))
-                                    .child("重新练习"),
+                                    .child(self.strings.t("completion.restart")),
                             ),
                     )
+                    .when(replayable, |el| {
+                        el.child(
+                            div()
+                                .px_6()
+                                .py_3()
+                                .bg(colors.bg_secondary)
+                                .hover(|style| style.bg(colors.bg_hover))
+                                .rounded(px(8.0))
+                                .cursor_pointer()
+                                .on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _event, _window, cx| this.open_replay(cx)),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(16.0))
+                                        .font_weight(FontWeight::MEDIUM)
+                                        .text_color(colors.text_primary)
+                                        .child(self.strings.t("completion.replay")),
+                                ),
+                        )
+                    })
                     .child(
                         div()
                             .px_6()
@@ -1311,6 +3377,8 @@ impl KeyzenApp {
 
                                     this.session = None;
                                     this.selected_lesson = None;
+                                    this.practiced_exercises =
+                                        practiced_exercises_for(&this.database);
                                     this.focus_handle.focus(window);
                                     cx.notify();
                                 }),
@@ -1320,13 +3388,256 @@ impl KeyzenApp {
                                     .text_size(px(16.0))
                                     .font_weight(FontWeight::MEDIUM)
                                     .text_color(colors.text_primary)
-                                    .child("返回课程列表"),
+                                    .child(self.strings.t("common.back_to_list")),
                             ),
                     ),
             )
             .into_any()
     }
 
+    /// 渲染按键回放：按录制节奏重新输入，输错的字符标红
+    fn render_replay_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        let colors = self.get_colors();
+        let Some(replay) = &self.replay else {
+            return div().into_any();
+        };
+
+        let session = replay.player.session();
+        let (current, total) = session.get_progress();
+        let target_text = session.get_target_text();
+        let input_text = session.get_input_text();
+        let target_chars: Vec<&str> = target_text.graphemes(true).collect();
+        let input_chars: Vec<&str> = input_text.graphemes(true).collect();
+        let is_playing = replay.playback.is_some();
+        let play_key = if is_playing {
+            "replay.pause"
+        } else if replay.player.is_finished() {
+            "replay.again"
+        } else {
+            "replay.play"
+        };
+        let speed = replay.speed;
+
+        div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_6()
+            .w_full()
+            .h_full()
+            .p_8()
+            .child(
+                div()
+                    .text_size(px(18.0))
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(colors.text_primary)
+                    .child(self.strings.t("replay.title")),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .text_size(px(14.0))
+                    .text_color(colors.text_secondary)
+                    .child(self.strings.tf("practice.progress", &[&current, &total]))
+                    .child(self.strings.tf(
+                        "replay.time",
+                        &[
+                            &format!("{:.1}", replay.player.position().as_secs_f32()),
+                            &format!("{:.1}", replay.player.duration().as_secs_f32()),
+                        ],
+                    )),
+            )
+            .child(
+                div()
+                    .w_full()
+                    .max_w(px(800.0))
+                    .font_family("JetBrains Mono")
+                    .text_size(px(24.0))
+                    .line_height(px(36.0))
+                    .flex()
+                    .flex_row()
+                    .flex_wrap()
+                    .children(
+                        target_chars
+                            .iter()
+                            .enumerate()
+                            .flat_map(|(i, &target_char)| {
+                                // 与练习区一致：已输入的正确字符正常显示，错误字符标红，光标高亮
+                                let (color, bg_color) = match input_chars.get(i) {
+                                    Some(&input_char) if input_char == target_char => {
+                                        (colors.text_primary, None)
+                                    }
+                                    Some(_) => (colors.error, Some(colors.error_bg)),
+                                    None if i == input_chars.len() => {
+                                        (rgb(0x000000).into(), Some(colors.cursor))
+                                    }
+                                    None => (colors.text_secondary, None),
+                                };
+                                let show_char = match target_char {
+                                    "\n" => "↵",
+                                    grapheme => grapheme,
+                                };
+
+                                let char_div = div()
+                                    .h(px(36.0))
+                                    .flex()
+                                    .items_center()
+                                    .text_color(color)
+                                    .when_some(bg_color, |el, bg| el.bg(bg))
+                                    .child(show_char.to_string());
+
+                                let mut items = vec![char_div.into_any_element()];
+                                if target_char == "\n" {
+                                    items.push(div().w_full().h(px(0.0)).into_any_element());
+                                }
+                                items
+                            }),
+                    ),
+            )
+            .child(
+                // 播放控制
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(self.render_option_button(
+                        false,
+                        &self.strings.t(play_key),
+                        &colors,
+                        |this, cx| this.toggle_replay_playback(cx),
+                        cx,
+                    ))
+                    .children(REPLAY_SPEEDS.iter().map(|&option| {
+                        self.render_option_button(
+                            speed == option,
+                            &format!("{}×", option),
+                            &colors,
+                            move |this, cx| this.set_replay_speed(option, cx),
+                            cx,
+                        )
+                    })),
+            )
+            .child(
+                div()
+                    .px_6()
+                    .py_3()
+                    .bg(colors.bg_secondary)
+                    .hover(|style| style.bg(colors.bg_hover))
+                    .rounded(px(8.0))
+                    .cursor_pointer()
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.replay = None;
+                            cx.notify();
+                        }),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(16.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(colors.text_primary)
+                            .child(self.strings.t("replay.close")),
+                    ),
+            )
+            .into_any()
+    }
+
+    /// 渲染屏幕键盘：高亮下一个按键（需要 Shift 时同时高亮 Shift），标记最近出错的按键
+    fn render_keyboard(
+        &self,
+        next_char: Option<char>,
+        error_chars: &HashSet<char>,
+        colors: &ThemeColors,
+    ) -> impl IntoElement {
+        let layout = KeyboardLayout::Qwerty;
+        let next = next_char.and_then(|ch| layout.locate(ch));
+        let error_keys: HashSet<KeyId> = error_chars
+            .iter()
+            .filter_map(|&ch| layout.locate(ch))
+            .map(|press| press.key)
+            .collect();
+        let shift_active = next.is_some_and(|press| press.shift);
+
+        let render_key = |label: String, width: f32, highlighted: bool, has_error: bool| {
+            let (bg, text) = if highlighted {
+                (colors.accent, rgb(0x000000).into())
+            } else if has_error {
+                (colors.error_bg, colors.error)
+            } else {
+                (colors.bg_secondary, colors.text_muted)
+            };
+
+            div()
+                .w(px(width))
+                .h(px(32.0))
+                .flex()
+                .items_center()
+                .justify_center()
+                .rounded(px(4.0))
+                .bg(bg)
+                .text_size(px(12.0))
+                .text_color(text)
+                .child(label)
+        };
+        let key_state = |key: KeyId| {
+            (
+                next.is_some_and(|press| press.key == key),
+                error_keys.contains(&key),
+            )
+        };
+
+        let rows = layout.rows();
+        let mut keyboard = div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .font_family("JetBrains Mono");
+
+        for (row, keys) in rows.iter().enumerate() {
+            let mut row_div = div().flex().gap_1();
+
+            // 每行左侧的功能键
+            match row {
+                1 => {
+                    let (hl, err) = key_state(KeyId::Tab);
+                    row_div = row_div.child(render_key("Tab".into(), 52.0, hl, err));
+                }
+                2 => row_div = row_div.child(render_key("Caps".into(), 62.0, false, false)),
+                3 => row_div = row_div.child(render_key("Shift".into(), 80.0, shift_active, false)),
+                _ => {}
+            }
+
+            for (col, cap) in keys.iter().enumerate() {
+                let (hl, err) = key_state(KeyId::Char { row, col });
+                let label = cap.normal.to_uppercase().to_string();
+                row_div = row_div.child(render_key(label, 32.0, hl, err));
+            }
+
+            // 每行右侧的功能键
+            match row {
+                0 => row_div = row_div.child(render_key("⌫".into(), 52.0, false, false)),
+                2 => {
+                    let (hl, err) = key_state(KeyId::Enter);
+                    row_div = row_div.child(render_key("Enter".into(), 60.0, hl, err));
+                }
+                3 => row_div = row_div.child(render_key("Shift".into(), 80.0, shift_active, false)),
+                _ => {}
+            }
+
+            keyboard = keyboard.child(row_div);
+        }
+
+        let (hl, err) = key_state(KeyId::Space);
+        keyboard.child(div().flex().justify_center().child(render_key(
+            String::new(),
+            240.0,
+            hl,
+            err,
+        )))
+    }
+
     /// 渲染词云组件
     fn render_word_cloud(
         &self,
@@ -1474,6 +3785,183 @@ impl KeyzenApp {
             )
     }
 
+    /// 渲染通用的设置选项按钮
+    fn render_option_button(
+        &self,
+        is_selected: bool,
+        label: &str,
+        colors: &ThemeColors,
+        on_select: impl Fn(&mut Self, &mut Context<Self>) + 'static,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let label_owned = label.to_string();
+
+        div()
+            .px_4()
+            .py_2()
+            .bg(if is_selected {
+                colors.accent
+            } else {
+                colors.bg_primary
+            })
+            .when(!is_selected, |el| {
+                el.hover(|style| style.bg(colors.bg_hover))
+            })
+            .rounded(px(6.0))
+            .cursor_pointer()
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| {
+                    if !is_selected {
+                        on_select(this, cx);
+                    }
+                }),
+            )
+            .child(
+                div()
+                    .text_size(px(13.0))
+                    .text_color(if is_selected {
+                        if matches!(self.current_theme, Theme::Light) {
+                            rgb(0xFFFFFF) // 浅色主题选中时白色文字
+                        } else {
+                            rgb(0x000000) // 深色主题选中时黑色文字
+                        }
+                    } else {
+                        colors.text_secondary.into()
+                    })
+                    .child(label_owned),
+            )
+    }
+
+    /// 渲染练习顺序按钮
+    fn render_exercise_order_button(
+        &self,
+        order: ExerciseOrder,
+        label: &str,
+        colors: &ThemeColors,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        self.render_option_button(
+            self.exercise_order == order,
+            label,
+            colors,
+            move |this, cx| this.set_exercise_order(order, cx),
+            cx,
+        )
+    }
+
+    /// 渲染课程排序按钮及该排序方式的说明
+    fn render_lesson_sort_option(
+        &self,
+        sort: LessonSort,
+        label: &str,
+        description: String,
+        colors: &ThemeColors,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .flex()
+            .items_center()
+            .gap_3()
+            .child(self.render_option_button(
+                self.lesson_sort == sort,
+                label,
+                colors,
+                move |this, cx| this.set_lesson_sort(sort, cx),
+                cx,
+            ))
+            .child(
+                div()
+                    .text_size(px(13.0))
+                    .text_color(colors.text_muted)
+                    .child(description),
+            )
+    }
+
+    /// 渲染练习区最大宽度按钮
+    /// 设置卡片：标题 + 说明 + 选项
+    fn render_setting_card(
+        &self,
+        title: String,
+        description: String,
+        options: impl IntoElement,
+        colors: &ThemeColors,
+    ) -> impl IntoElement {
+        div()
+            .w_full()
+            .p_6()
+            .bg(colors.bg_secondary)
+            .rounded(px(12.0))
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_4()
+                    .child(
+                        div()
+                            .text_size(px(16.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(colors.text_primary)
+                            .child(title),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(13.0))
+                            .text_color(colors.text_muted)
+                            .child(description),
+                    )
+                    .child(options),
+            )
+    }
+
+    fn render_metronome_button(
+        &self,
+        wpm: Option<u32>,
+        label: &str,
+        colors: &ThemeColors,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        self.render_option_button(
+            self.metronome_wpm == wpm,
+            label,
+            colors,
+            move |this, cx| this.set_metronome_wpm(wpm, cx),
+            cx,
+        )
+    }
+
+    fn render_visual_beat_button(
+        &self,
+        bpm: Option<u32>,
+        label: &str,
+        colors: &ThemeColors,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        self.render_option_button(
+            self.visual_beat_bpm == bpm,
+            label,
+            colors,
+            move |this, cx| this.set_visual_beat_bpm(bpm, cx),
+            cx,
+        )
+    }
+
+    fn render_content_width_button(
+        &self,
+        width: Option<f32>,
+        label: &str,
+        colors: &ThemeColors,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        self.render_option_button(
+            self.max_content_width == width,
+            label,
+            colors,
+            move |this, cx| this.set_max_content_width(width, cx),
+            cx,
+        )
+    }
+
     fn render_settings_view(&self, cx: &mut Context<Self>) -> AnyElement {
         let colors = self.get_colors();
         let is_dark = self.current_theme == Theme::Dark;
@@ -1496,7 +3984,7 @@ impl KeyzenApp {
                             .text_size(px(20.0))
                             .font_weight(FontWeight::MEDIUM)
                             .text_color(colors.text_primary)
-                            .child("设置"),
+                            .child(self.strings.t("settings.title")),
                     )
                     .child(
                         div()
@@ -1516,13 +4004,15 @@ impl KeyzenApp {
                                 div()
                                     .text_size(px(14.0))
                                     .text_color(colors.accent)
-                                    .child("关闭"),
+                                    .child(self.strings.t("settings.close")),
                             ),
                     ),
             )
             .child(
-                // 设置内容区域
+                // 设置内容区域（可滚动）
                 div()
+                    .id("settings_content")
+                    .overflow_y_scroll()
                     .flex()
                     .flex_col()
                     .gap_6()
@@ -1544,7 +4034,7 @@ impl KeyzenApp {
                                             .text_size(px(16.0))
                                             .font_weight(FontWeight::MEDIUM)
                                             .text_color(colors.text_primary)
-                                            .child("外观"),
+                                            .child(self.strings.t("settings.appearance")),
                                     )
                                     .child(
                                         div()
@@ -1555,7 +4045,7 @@ impl KeyzenApp {
                                                 div()
                                                     .text_size(px(14.0))
                                                     .text_color(colors.text_secondary)
-                                                    .child("主题"),
+                                                    .child(self.strings.t("settings.theme")),
                                             )
                                             .child(
                                                 div()
@@ -1581,10 +4071,7 @@ impl KeyzenApp {
                                                                 cx.listener(|this, _event, _window, cx| {
                                                                     if this.current_theme != Theme::Dark {
                                                                         this.current_theme = Theme::Dark;
-                                                                        // 保存主题配置
-                                                                        if let Err(e) = this.database.save_config("theme", "dark") {
-                                                                            eprintln!("保存主题配置失败: {}", e);
-                                                                        }
+                                                                        this.save_settings();
                                                                         cx.notify();
                                                                     }
                                                                 }),
@@ -1597,7 +4084,7 @@ impl KeyzenApp {
                                                                     } else {
                                                                         colors.text_secondary.into()
                                                                     })
-                                                                    .child("深色"),
+                                                                    .child(self.strings.t("settings.theme_dark")),
                                                             ),
                                                     )
                                                     .child(
@@ -1620,10 +4107,7 @@ impl KeyzenApp {
                                                                 cx.listener(|this, _event, _window, cx| {
                                                                     if this.current_theme != Theme::Light {
                                                                         this.current_theme = Theme::Light;
-                                                                        // 保存主题配置
-                                                                        if let Err(e) = this.database.save_config("theme", "light") {
-                                                                            eprintln!("保存主题配置失败: {}", e);
-                                                                        }
+                                                                        this.save_settings();
                                                                         cx.notify();
                                                                     }
                                                                 }),
@@ -1636,10 +4120,36 @@ impl KeyzenApp {
                                                                     } else {
                                                                         colors.text_secondary.into()
                                                                     })
-                                                                    .child("浅色"),
+                                                                    .child(self.strings.t("settings.theme_light")),
                                                             ),
                                                     ),
                                             ),
+                                    )
+                                    .child(
+                                        div()
+                                            .flex()
+                                            .justify_between()
+                                            .items_center()
+                                            .child(
+                                                div()
+                                                    .text_size(px(14.0))
+                                                    .text_color(colors.text_secondary)
+                                                    .child(self.strings.t("settings.language")),
+                                            )
+                                            .child(
+                                                div()
+                                                    .flex()
+                                                    .gap_2()
+                                                    .children([UiLanguage::Chinese, UiLanguage::English].map(|language| {
+                                                        self.render_option_button(
+                                                            self.strings.language() == language,
+                                                            language.native_name(),
+                                                            &colors,
+                                                            move |this, cx| this.set_ui_language(language, cx),
+                                                            cx,
+                                                        )
+                                                    })),
+                                            ),
                                     ),
                             ),
                     )
@@ -1660,13 +4170,13 @@ impl KeyzenApp {
                                             .text_size(px(16.0))
                                             .font_weight(FontWeight::MEDIUM)
                                             .text_color(colors.text_primary)
-                                            .child("记忆模式"),
+                                            .child(self.strings.t("settings.memory_mode")),
                                     )
                                     .child(
                                         div()
                                             .text_size(px(13.0))
                                             .text_color(colors.text_muted)
-                                            .child("隐藏部分或全部文本以练习记忆打字"),
+                                            .child(self.strings.t("settings.memory_mode_desc")),
                                     )
                                     .child(
                                         // 记忆模式选项
@@ -1674,15 +4184,403 @@ impl KeyzenApp {
                                             .flex()
                                             .flex_wrap()
                                             .gap_2()
-                                            .child(self.render_memory_mode_button(MemoryMode::Off, "关闭", &colors, cx))
-                                            .child(self.render_memory_mode_button(MemoryMode::FirstLetter, "首字母提示", &colors, cx))
-                                            .child(self.render_memory_mode_button(MemoryMode::Partial(PartialLevel::Low), "部分隐藏 (30%)", &colors, cx))
-                                            .child(self.render_memory_mode_button(MemoryMode::Partial(PartialLevel::Medium), "部分隐藏 (50%)", &colors, cx))
-                                            .child(self.render_memory_mode_button(MemoryMode::Partial(PartialLevel::High), "部分隐藏 (70%)", &colors, cx))
-                                            .child(self.render_memory_mode_button(MemoryMode::Complete, "完全隐藏", &colors, cx)),
+                                            .child(self.render_memory_mode_button(MemoryMode::Off, &self.strings.t("settings.memory_off"), &colors, cx))
+                                            .child(self.render_memory_mode_button(MemoryMode::FirstLetter, &self.strings.t("settings.memory_first_letter"), &colors, cx))
+                                            .child(self.render_memory_mode_button(MemoryMode::Partial(PartialLevel::Low), &self.strings.t("settings.memory_partial_low"), &colors, cx))
+                                            .child(self.render_memory_mode_button(MemoryMode::Partial(PartialLevel::Medium), &self.strings.t("settings.memory_partial_medium"), &colors, cx))
+                                            .child(self.render_memory_mode_button(MemoryMode::Partial(PartialLevel::High), &self.strings.t("settings.memory_partial_high"), &colors, cx))
+                                            .child(self.render_memory_mode_button(MemoryMode::Complete, &self.strings.t("settings.memory_complete"), &colors, cx)),
                                     ),
                             ),
                     )
+                    .child(self.render_setting_card(
+                        // 中文课程空格设置
+                        self.strings.t("settings.cjk_space"),
+                        self.strings.t("settings.cjk_space_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.cjk_literal_space, &self.strings.t("settings.on"), &colors, |this, cx| this.set_cjk_literal_space(true, cx), cx))
+                            .child(self.render_option_button(!self.cjk_literal_space, &self.strings.t("settings.off"), &colors, |this, cx| this.set_cjk_literal_space(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 中文课程速度统计设置
+                        self.strings.t("settings.cjk_net_wpm"),
+                        self.strings.t("settings.cjk_net_wpm_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.cjk_net_wpm, &self.strings.t("settings.on"), &colors, |this, cx| this.set_cjk_net_wpm(true, cx), cx))
+                            .child(self.render_option_button(!self.cjk_net_wpm, &self.strings.t("settings.off"), &colors, |this, cx| this.set_cjk_net_wpm(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 渐进隐藏设置
+                        self.strings.t("settings.memory_ramp"),
+                        self.strings.tf("settings.memory_ramp_desc", &[&format!("{:.0}%", MEMORY_RAMP_ACCURACY * 100.0)]),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.memory_ramp, &self.strings.t("settings.on"), &colors, |this, cx| this.set_memory_ramp(true, cx), cx))
+                            .child(self.render_option_button(!self.memory_ramp, &self.strings.t("settings.off"), &colors, |this, cx| this.set_memory_ramp(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 跳过已掌握练习设置
+                        self.strings.t("settings.skip_mastered"),
+                        self.strings.tf("settings.skip_mastered_desc", &[&format!("{:.0}%", MASTERY_ACCURACY * 100.0)]),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.skip_mastered, &self.strings.t("settings.on"), &colors, |this, cx| this.set_skip_mastered(true, cx), cx))
+                            .child(self.render_option_button(!self.skip_mastered, &self.strings.t("settings.off"), &colors, |this, cx| this.set_skip_mastered(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 启动恢复设置
+                        self.strings.t("settings.resume_on_launch"),
+                        self.strings.t("settings.resume_on_launch_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.resume_on_launch, &self.strings.t("settings.on"), &colors, |this, cx| this.set_resume_on_launch(true, cx), cx))
+                            .child(self.render_option_button(!self.resume_on_launch, &self.strings.t("settings.off"), &colors, |this, cx| this.set_resume_on_launch(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 练习顺序设置
+                        self.strings.t("settings.exercise_order"),
+                        self.strings.t("settings.exercise_order_desc"),
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .child(self.render_exercise_order_button(ExerciseOrder::Sequential, &self.strings.t("settings.order_sequential"), &colors, cx))
+                            .child(self.render_exercise_order_button(ExerciseOrder::Adaptive, &self.strings.t("settings.order_adaptive"), &colors, cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 课程排序设置
+                        self.strings.t("settings.lesson_sort"),
+                        self.strings.t("settings.lesson_sort_desc"),
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap_2()
+                            .child(self.render_lesson_sort_option(LessonSort::Id, &self.strings.t("settings.sort_id"), self.strings.t("settings.sort_id_desc"), &colors, cx))
+                            .child(self.render_lesson_sort_option(LessonSort::Title, &self.strings.t("settings.sort_title"), self.strings.t("settings.sort_title_desc"), &colors, cx))
+                            .child(self.render_lesson_sort_option(LessonSort::Difficulty, &self.strings.t("settings.sort_difficulty"), self.strings.t("settings.sort_difficulty_desc"), &colors, cx))
+                            .child(self.render_lesson_sort_option(LessonSort::LastPracticed, &self.strings.t("settings.sort_last_practiced"), self.strings.t("settings.sort_last_practiced_desc"), &colors, cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 练习区宽度设置
+                        self.strings.t("settings.content_width"),
+                        self.strings.t("settings.content_width_desc"),
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .child(self.render_content_width_button(Some(640.0), "640px", &colors, cx))
+                            .child(self.render_content_width_button(Some(800.0), "800px", &colors, cx))
+                            .child(self.render_content_width_button(Some(1000.0), "1000px", &colors, cx))
+                            .child(self.render_content_width_button(None, &self.strings.t("settings.content_width_unlimited"), &colors, cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 屏幕键盘设置
+                        self.strings.t("settings.keyboard"),
+                        self.strings.t("settings.keyboard_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.show_keyboard, &self.strings.t("settings.show"), &colors, |this, cx| this.set_show_keyboard(true, cx), cx))
+                            .child(self.render_option_button(!self.show_keyboard, &self.strings.t("settings.hide"), &colors, |this, cx| this.set_show_keyboard(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 空白字符显示设置
+                        self.strings.t("settings.show_whitespace"),
+                        self.strings.t("settings.show_whitespace_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.show_whitespace, &self.strings.t("settings.show"), &colors, |this, cx| this.set_show_whitespace(true, cx), cx))
+                            .child(self.render_option_button(!self.show_whitespace, &self.strings.t("settings.hide"), &colors, |this, cx| this.set_show_whitespace(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 专注模式设置
+                        self.strings.t("settings.hide_stats"),
+                        self.strings.t("settings.hide_stats_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.hide_stats, &self.strings.t("settings.on"), &colors, |this, cx| this.set_hide_stats(true, cx), cx))
+                            .child(self.render_option_button(!self.hide_stats, &self.strings.t("settings.off"), &colors, |this, cx| this.set_hide_stats(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 退格设置
+                        self.strings.t("settings.backspace"),
+                        self.strings.t("settings.backspace_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.allow_backspace, &self.strings.t("settings.backspace_allow"), &colors, |this, cx| this.set_allow_backspace(true, cx), cx))
+                            .child(self.render_option_button(!self.allow_backspace, &self.strings.t("settings.backspace_forbid"), &colors, |this, cx| this.set_allow_backspace(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 连续空格设置
+                        self.strings.t("settings.whitespace_policy"),
+                        self.strings.t("settings.whitespace_policy_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.whitespace_policy.is_none(), &self.strings.t("settings.whitespace_auto"), &colors, |this, cx| this.set_whitespace_policy(None, cx), cx))
+                            .child(self.render_option_button(self.whitespace_policy == Some(WhitespacePolicy::Exact), &self.strings.t("settings.whitespace_exact"), &colors, |this, cx| this.set_whitespace_policy(Some(WhitespacePolicy::Exact), cx), cx))
+                            .child(self.render_option_button(self.whitespace_policy == Some(WhitespacePolicy::Collapse), &self.strings.t("settings.whitespace_collapse"), &colors, |this, cx| this.set_whitespace_policy(Some(WhitespacePolicy::Collapse), cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 严格模式输错恢复设置
+                        self.strings.t("settings.strict_recovery"),
+                        self.strings.t("settings.strict_recovery_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.strict_recovery == StrictRecovery::RetypeExact, &self.strings.t("settings.strict_recovery_exact"), &colors, |this, cx| this.set_strict_recovery(StrictRecovery::RetypeExact, cx), cx))
+                            .child(self.render_option_button(self.strict_recovery == StrictRecovery::AnyKey, &self.strings.t("settings.strict_recovery_any_key"), &colors, |this, cx| this.set_strict_recovery(StrictRecovery::AnyKey, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 严格完成设置
+                        self.strings.t("settings.strict_completion"),
+                        self.strings.t("settings.strict_completion_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.strict_completion, &self.strings.t("settings.on"), &colors, |this, cx| this.set_strict_completion(true, cx), cx))
+                            .child(self.render_option_button(!self.strict_completion, &self.strings.t("settings.off"), &colors, |this, cx| this.set_strict_completion(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 完美练习设置
+                        self.strings.t("settings.perfect_practice"),
+                        self.strings.t("settings.perfect_practice_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.perfect_practice, &self.strings.t("settings.on"), &colors, |this, cx| this.set_perfect_practice(true, cx), cx))
+                            .child(self.render_option_button(!self.perfect_practice, &self.strings.t("settings.off"), &colors, |this, cx| this.set_perfect_practice(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 练习预览设置
+                        self.strings.t("settings.preview_exercises"),
+                        self.strings.t("settings.preview_exercises_desc"),
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(self.render_option_button(self.preview_exercises, &self.strings.t("settings.on"), &colors, |this, cx| this.set_preview_exercises(true, cx), cx))
+                            .child(self.render_option_button(!self.preview_exercises, &self.strings.t("settings.off"), &colors, |this, cx| this.set_preview_exercises(false, cx), cx)),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 自动跳转停顿设置
+                        self.strings.t("settings.auto_advance"),
+                        self.strings.t("settings.auto_advance_desc"),
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .children(AUTO_ADVANCE_DELAY_OPTIONS.map(|ms| {
+                                let label = if ms == 0 {
+                                    self.strings.t("settings.auto_advance_instant")
+                                } else {
+                                    format!("{} ms", ms)
+                                };
+                                self.render_option_button(
+                                    self.auto_advance == AutoAdvance::OnPerfect
+                                        && self.auto_advance_delay == Duration::from_millis(ms),
+                                    &label,
+                                    &colors,
+                                    move |this, cx| this.set_auto_advance_delay(ms, cx),
+                                    cx,
+                                )
+                            }))
+                            .child(self.render_option_button(
+                                self.auto_advance == AutoAdvance::Manual,
+                                &self.strings.t("settings.auto_advance_manual"),
+                                &colors,
+                                |this, cx| this.set_auto_advance(AutoAdvance::Manual, cx),
+                                cx,
+                            )),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 暂停检测设置
+                        self.strings.t("settings.idle_timeout"),
+                        self.strings.t("settings.idle_timeout_desc"),
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .children(IDLE_TIMEOUT_OPTIONS_SECS.map(|secs| {
+                                let timeout = Some(Duration::from_secs(secs));
+                                self.render_option_button(
+                                    self.idle_timeout == timeout,
+                                    &self.strings.tf("settings.idle_timeout_secs", &[&secs]),
+                                    &colors,
+                                    move |this, cx| this.set_idle_timeout(timeout, cx),
+                                    cx,
+                                )
+                            }))
+                            .child(self.render_option_button(
+                                self.idle_timeout.is_none(),
+                                &self.strings.t("settings.off"),
+                                &colors,
+                                |this, cx| this.set_idle_timeout(None, cx),
+                                cx,
+                            )),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 开始前倒计时设置
+                        self.strings.t("settings.start_countdown"),
+                        self.strings.t("settings.start_countdown_desc"),
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .child(self.render_option_button(
+                                self.start_countdown_secs == 0,
+                                &self.strings.t("settings.off"),
+                                &colors,
+                                |this, cx| this.set_start_countdown(0, cx),
+                                cx,
+                            ))
+                            .children(START_COUNTDOWN_OPTIONS_SECS.map(|secs| {
+                                self.render_option_button(
+                                    self.start_countdown_secs == secs,
+                                    &self.strings.tf("settings.start_countdown_secs", &[&secs]),
+                                    &colors,
+                                    move |this, cx| this.set_start_countdown(secs, cx),
+                                    cx,
+                                )
+                            })),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 最短保存时长设置
+                        self.strings.t("settings.min_session"),
+                        self.strings.t("settings.min_session_desc"),
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .children(MIN_SESSION_OPTIONS_SECS.map(|secs| {
+                                let label = if secs == 0 {
+                                    self.strings.t("settings.off")
+                                } else {
+                                    self.strings.tf("settings.min_session_secs", &[&secs])
+                                };
+                                let duration = Duration::from_secs(secs);
+                                self.render_option_button(
+                                    self.min_session_duration == duration,
+                                    &label,
+                                    &colors,
+                                    move |this, cx| this.set_min_session_duration(duration, cx),
+                                    cx,
+                                )
+                            })),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 预览窗口设置
+                        self.strings.t("settings.preview_window"),
+                        self.strings.t("settings.preview_window_desc"),
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .children(PREVIEW_WINDOW_OPTIONS.map(|n| {
+                                self.render_option_button(
+                                    self.preview_window == Some(n),
+                                    &self.strings.tf("settings.preview_window_chars", &[&n]),
+                                    &colors,
+                                    move |this, cx| this.set_preview_window(Some(n), cx),
+                                    cx,
+                                )
+                            }))
+                            .child(self.render_option_button(
+                                self.preview_window.is_none(),
+                                &self.strings.t("settings.preview_window_all"),
+                                &colors,
+                                |this, cx| this.set_preview_window(None, cx),
+                                cx,
+                            )),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 节拍器设置
+                        self.strings.t("settings.metronome"),
+                        self.strings.t("settings.metronome_desc"),
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .child(self.render_metronome_button(None, &self.strings.t("settings.metronome_off"), &colors, cx))
+                            .children(METRONOME_WPM_OPTIONS.map(|wpm| {
+                                self.render_metronome_button(Some(wpm), &format!("{} WPM", wpm), &colors, cx)
+                            })),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 视觉节拍设置
+                        self.strings.t("settings.visual_beat"),
+                        self.strings.t("settings.visual_beat_desc"),
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .child(self.render_visual_beat_button(None, &self.strings.t("settings.metronome_off"), &colors, cx))
+                            .children(VISUAL_BEAT_BPM_OPTIONS.map(|bpm| {
+                                self.render_visual_beat_button(Some(bpm), &format!("{} BPM", bpm), &colors, cx)
+                            })),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // WPM 着色设置
+                        self.strings.t("settings.wpm_bands"),
+                        self.strings.t("settings.wpm_bands_desc"),
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .child(self.render_option_button(self.wpm_bands.is_none(), &self.strings.t("settings.off"), &colors, |this, cx| this.set_wpm_bands(None, cx), cx))
+                            .children(WPM_BAND_PRESETS.map(|bands| {
+                                let label = format!("{} / {} / {}", bands[0], bands[1], bands[2]);
+                                self.render_option_button(self.wpm_bands == Some(bands), &label, &colors, move |this, cx| this.set_wpm_bands(Some(bands), cx), cx)
+                            })),
+                        &colors,
+                    ))
+                    .child(self.render_setting_card(
+                        // 实时 WPM 统计窗口设置
+                        self.strings.t("settings.wpm_window"),
+                        self.strings.t("settings.wpm_window_desc"),
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .children(WPM_WINDOW_OPTIONS_SECS.map(|secs| {
+                                let window = Duration::from_secs(secs);
+                                self.render_option_button(self.wpm_window == window, &self.strings.tf("settings.wpm_window_secs", &[&secs]), &colors, move |this, cx| this.set_wpm_window(window, cx), cx)
+                            })),
+                        &colors,
+                    ))
                     .child(
                         // 提示文本
                         div()
@@ -1690,7 +4588,7 @@ impl KeyzenApp {
                             .justify_center()
                             .text_xs()
                             .text_color(colors.text_muted)
-                            .child("按 Esc 关闭设置"),
+                            .child(self.strings.t("settings.esc_hint")),
                     ),
             )
             .into_any()
@@ -1730,7 +4628,7 @@ impl EntityInputHandler for KeyzenApp {
     }
 
     fn unmark_text(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
-        // 不需要实现
+        self.ime.unmark();
     }
 
     fn replace_text_in_range(
@@ -1747,22 +4645,17 @@ impl EntityInputHandler for KeyzenApp {
             text
         );
 
+        self.ime.unmark();
+        // 继续输入即取消放弃练习的确认
+        self.confirm_abort = false;
+
         if let Some(session) = &self.session {
-            // 遍历文本中的每个字符并处理
-            for ch in text.chars() {
-                debug!("  ↳ 处理字符: {:?} (U+{:04X})", ch, ch as u32);
+            // 按字素簇遍历文本（组合附加符号、emoji 序列作为一个整体）
+            for grapheme in text.graphemes(true) {
+                debug!("  ↳ 处理字素簇: {:?}", grapheme);
                 session.update(cx, |session_model, cx| {
-                    session_model.handle_keystroke(&ch.to_string(), cx);
-
-                    // 检查当前练习是否完成且无错误，才自动跳转
-                    if session_model.session.is_current_exercise_complete()
-                        && !session_model.session.current_exercise_has_errors()
-                    {
-                        if session_model.session.has_next_exercise() {
-                            session_model.session.advance_to_next_exercise();
-                            debug!("✅ 练习无错误，自动跳转到下一个练习");
-                        }
-                    }
+                    // 完成后的自动跳转由 SessionModel 处理
+                    session_model.handle_keystroke(grapheme, cx);
                 });
             }
         }
@@ -1771,13 +4664,14 @@ impl EntityInputHandler for KeyzenApp {
     fn replace_and_mark_text_in_range(
         &mut self,
         _range: Option<Range<usize>>,
-        _new_text: &str,
+        new_text: &str,
         _new_selected_range: Option<Range<usize>>,
         _window: &mut Window,
         _cx: &mut Context<Self>,
     ) {
         // 这个方法在 IME 输入过程中（还未确认）会被调用
-        // 我们不处理中间状态，只等待最终确认
+        // 我们不处理中间状态，只等待最终确认；这里只记录是否正在组字
+        self.ime.mark(new_text);
     }
 
     fn bounds_for_range(
@@ -1802,31 +4696,21 @@ impl EntityInputHandler for KeyzenApp {
 }
 
 impl Render for KeyzenApp {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         // 检查是否需要重新加载课程
-        let should_reload = if let Ok(mut needs_reload) = self.needs_reload.lock() {
-            let should = *needs_reload;
-            *needs_reload = false;
-            should
-        } else {
-            false
-        };
-
-        if should_reload {
+        if self.needs_reload.take() {
             self.reload_lessons(cx);
         }
 
-        // 订阅 session 的变化
-        if let Some(session) = &self.session {
-            let session_clone = session.clone();
-            cx.observe(&session_clone, |_, _, cx| {
-                cx.notify();
-            })
-            .detach();
+        // 开启启动恢复时直接进入上次练习的课程
+        if let Some((lesson_index, exercise_index)) = self.pending_resume.take() {
+            self.resume_lesson(lesson_index, exercise_index, window, cx);
         }
 
         let content = if self.show_settings {
             self.render_settings_view(cx)
+        } else if self.replay.is_some() {
+            self.render_replay_view(cx)
         } else if let Some(session) = &self.session {
             let is_completed = session.read(cx).is_completed();
             if is_completed {
@@ -1838,6 +4722,12 @@ impl Render for KeyzenApp {
                 let snapshot = self.completion_snapshot.clone().unwrap();
                 self.render_completion_stats(snapshot, cx)
             } else {
+                // 节拍器运行中需要逐帧刷新（视觉节拍由会话按拍刷新）
+                if self.metronome_wpm.is_some()
+                    && session.read(cx).session.exercise_elapsed().is_some()
+                {
+                    window.request_animation_frame();
+                }
                 self.render_practice_area(cx)
             }
         } else if self.show_history {
@@ -1860,6 +4750,14 @@ impl Render for KeyzenApp {
             .on_action(cx.listener(Self::back_to_list))
             .on_action(cx.listener(Self::show_history))
             .on_action(cx.listener(Self::show_settings))
+            .on_action(cx.listener(Self::toggle_theme))
+            .on_action(cx.listener(Self::random_lesson))
+            .on_action(cx.listener(Self::abort_session))
+            .on_action(cx.listener(Self::toggle_mastered))
+            .on_action(cx.listener(Self::copy_debug_dump))
+            .on_action(cx.listener(Self::export_lesson_text))
+            .on_action(cx.listener(Self::toggle_pause))
+            .on_action(cx.listener(Self::peek_ahead))
             .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
                 // 只处理功能键，不处理可打印字符
                 // 可打印字符（包括 IME 输入的汉字）由 InputHandler::replace_text_in_range 处理
@@ -1869,13 +4767,38 @@ impl Render for KeyzenApp {
 
                 if let Some(session) = &this.session {
                     // 只处理特殊功能键
-                    // 注意：Space 键不在这里处理！
+                    // 注意：Space 键一般不在这里处理！
                     // Space 在 IME 输入时用于选择候选词，最终字符由 InputHandler 提交
+                    // 例外：中文课程中没有正在组字时，Space 直接作为空格输入
+                    // （输入法提交的文本不一定包含句间空格）
+                    let literal_space = key == "space"
+                        && this.ime.space_is_literal(
+                            this.cjk_literal_space,
+                            session.read(cx).session.is_cjk_language(),
+                        );
+                    if literal_space || matches!(key, "backspace" | "enter" | "tab") {
+                        this.confirm_abort = false;
+                    }
+
                     match key {
+                        _ if literal_space => {
+                            debug!("  ↳ 中文课程未组字，Space 作为空格输入");
+                            session.update(cx, |session, cx| {
+                                session.handle_keystroke(" ", cx);
+                            });
+                            // 阻止输入法再提交一次空格
+                            cx.stop_propagation();
+                        }
                         "backspace" => {
                             debug!("  ↳ 处理功能键: Backspace");
+                            let modifiers = &event.keystroke.modifiers;
+                            let key = if modifiers.control || modifiers.alt {
+                                "word-backspace"
+                            } else {
+                                "backspace"
+                            };
                             session.update(cx, |session, cx| {
-                                session.handle_keystroke("backspace", cx);
+                                session.handle_keystroke(key, cx);
                             });
                         }
                         "enter" => {
@@ -1904,6 +4827,75 @@ fn quit(_: &Quit, cx: &mut App) {
     cx.quit();
 }
 
+/// 按最近练习排序时读取每个课程最近一次练习的时间（其他排序方式不需要，返回空表）
+/// 按界面语言创建应用菜单（切换语言时重新创建）
+fn set_app_menus(strings: &Strings, cx: &mut App) {
+    cx.set_menus(vec![Menu {
+        name: "Keyzen".into(),
+        items: vec![
+            MenuItem::action(strings.t("menu.settings"), ShowSettings),
+            MenuItem::separator(),
+            MenuItem::action(strings.t("menu.quit"), Quit),
+        ],
+    }]);
+}
+
+fn last_practiced_for(database: &Database, sort: LessonSort) -> HashMap<u32, i64> {
+    if sort != LessonSort::LastPracticed {
+        return HashMap::new();
+    }
+    database.get_last_practiced().unwrap_or_else(|e| {
+        eprintln!("读取最近练习时间失败: {}", e);
+        HashMap::new()
+    })
+}
+
+/// 读取每个课程练习过的练习索引（课程列表重建时加载一次）
+fn practiced_exercises_for(database: &Database) -> HashMap<u32, HashSet<usize>> {
+    database.get_practiced_exercises().unwrap_or_else(|e| {
+        eprintln!("读取练习进度失败: {}", e);
+        HashMap::new()
+    })
+}
+
+/// 部分练习过的课程从第一个未练习的练习继续（练习顺序可能被打乱，不能按最大索引推算）
+/// 没有练习记录或全部练习过时返回 None
+fn resume_exercise(practiced: Option<&HashSet<usize>>, exercise_count: usize) -> Option<usize> {
+    let practiced = practiced.filter(|practiced| !practiced.is_empty())?;
+    (0..exercise_count).find(|index| !practiced.contains(index))
+}
+
+/// 视觉节拍在 `elapsed` 时是否处于亮起状态（每拍开始后亮起 VISUAL_BEAT_FLASH_MS）
+fn visual_beat_lit(elapsed: Duration, bpm: u32) -> bool {
+    let period_ms = 60_000 / u128::from(bpm.max(1));
+    elapsed.as_millis() % period_ms < VISUAL_BEAT_FLASH_MS
+}
+
+/// 预览中的一行（含行末换行符）：显示空白时用 · 标出空格，只在确实换行的行末标出 ↵
+fn preview_line(line: &str, show_whitespace: bool) -> String {
+    let (text, newline) = match line.strip_suffix('\n') {
+        Some(text) => (text, "↵"),
+        None => (line, ""),
+    };
+    if show_whitespace {
+        format!("{}{}", text.replace(' ', "·"), newline)
+    } else {
+        text.to_string()
+    }
+}
+
+/// 从 `elapsed` 到视觉节拍下一次亮起或熄灭的时长
+fn visual_beat_next_change(elapsed: Duration, bpm: u32) -> Duration {
+    let period_ms = 60_000 / u128::from(bpm.max(1));
+    let phase_ms = elapsed.as_millis() % period_ms;
+    let wait_ms = if phase_ms < VISUAL_BEAT_FLASH_MS {
+        VISUAL_BEAT_FLASH_MS - phase_ms
+    } else {
+        period_ms - phase_ms
+    };
+    Duration::from_millis(wait_ms as u64)
+}
+
 fn main() {
     // 初始化日志系统
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
@@ -1918,6 +4910,13 @@ fn main() {
             KeyBinding::new("escape", BackToList, Some("KeyzenApp")),
             KeyBinding::new("cmd-h", ShowHistory, Some("KeyzenApp")),
             KeyBinding::new("cmd-,", ShowSettings, Some("KeyzenApp")),
+            KeyBinding::new("cmd-r", RandomLesson, Some("KeyzenApp")),
+            KeyBinding::new("cmd-d", AbortSession, Some("KeyzenApp")),
+            KeyBinding::new("cmd-k", ToggleMastered, Some("KeyzenApp")),
+            KeyBinding::new("cmd-shift-d", CopyDebugDump, Some("KeyzenApp")),
+            KeyBinding::new("cmd-shift-e", ExportLessonText, Some("KeyzenApp")),
+            KeyBinding::new("cmd-p", TogglePause, Some("KeyzenApp")),
+            KeyBinding::new("cmd-j", PeekAhead, Some("KeyzenApp")),
             KeyBinding::new("cmd-q", Quit, None),
         ]);
 
@@ -1932,16 +4931,6 @@ fn main() {
         })
         .detach();
 
-        // 创建应用菜单
-        cx.set_menus(vec![Menu {
-            name: "Keyzen".into(),
-            items: vec![
-                MenuItem::action("设置", ShowSettings),
-                MenuItem::separator(),
-                MenuItem::action("退出", Quit),
-            ],
-        }]);
-
         // 打开窗口
         let bounds = Bounds::centered(None, size(px(900.0), px(650.0)), cx);
         let window = cx
@@ -1957,7 +4946,7 @@ fn main() {
                     }),
                     ..Default::default()
                 },
-                |_, cx| cx.new(|cx| KeyzenApp::new(cx)),
+                |_, cx| cx.new(KeyzenApp::new),
             )
             .unwrap();
 
@@ -0,0 +1,46 @@
+//! 课程重新加载标记
+//!
+//! 文件监听线程在课程变化时设置，界面线程渲染时取出并清除。
+//! 用原子布尔值代替 `Mutex<bool>`，不存在锁中毒导致热重载失效的问题。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Debug, Default)]
+pub struct ReloadFlag(AtomicBool);
+
+impl ReloadFlag {
+    /// 标记需要重新加载
+    pub fn mark(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// 取出标记并清除；返回是否需要重新加载
+    pub fn take(&self) -> bool {
+        self.0.swap(false, Ordering::AcqRel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_mark_from_other_thread_and_take_once() {
+        let flag = Arc::new(ReloadFlag::default());
+        assert!(!flag.take());
+
+        let watcher_flag = flag.clone();
+        thread::spawn(move || {
+            watcher_flag.mark();
+            watcher_flag.mark();
+        })
+        .join()
+        .unwrap();
+
+        // 多次标记只触发一次重新加载
+        assert!(flag.take());
+        assert!(!flag.take());
+    }
+}
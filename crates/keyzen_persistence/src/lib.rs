@@ -1,9 +1,42 @@
 use anyhow::Result;
-use keyzen_core::{SessionStats, UnitType, WeakUnit};
-use rusqlite::{params, Connection};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use keyzen_core::{ExerciseStats, SessionStats, UnitType, WeakUnit};
+use rusqlite::{params, Connection, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use thiserror::Error;
 
+mod settings;
+
+pub use settings::{
+    LastSession, Settings, DEFAULT_AUTO_ADVANCE_DELAY_MS, DEFAULT_IDLE_TIMEOUT_SECS,
+    DEFAULT_MAX_CONTENT_WIDTH, DEFAULT_MIN_SESSION_SECS, DEFAULT_PREVIEW_WINDOW,
+    DEFAULT_WPM_WINDOW_SECS, LAST_SESSION_KEY, SETTINGS_KEY,
+};
+
+/// 薄弱单元汇总的默认错误率下限
+pub const DEFAULT_WEAK_UNIT_THRESHOLD: f32 = 0.10;
+
+/// 视为已掌握的准确率：练习达到时自动标记为已掌握，课程达到时随机选课优先跳过
+pub const MASTERY_ACCURACY: f64 = 0.95;
+
+/// 跨课程复习参考的最近会话数
+const REVIEW_RECENT_SESSIONS: usize = 20;
+/// 准确率低于该值的练习视为表现不佳，进入跨课程复习
+const REVIEW_STRUGGLE_ACCURACY: f64 = 0.9;
+
+/// 估算掌握所需次数时参考的最近会话数
+const MASTERY_TREND_WINDOW: usize = 10;
+/// 估算掌握所需次数至少需要的会话数
+const MASTERY_TREND_MIN_SESSIONS: usize = 3;
+/// 估算结果的上限（超过时视为无法预测）
+const MASTERY_ESTIMATE_MAX_SESSIONS: u32 = 100;
+
+/// SQLite 在数据库文件旁创建的日志文件后缀
+const SQLITE_SIDECAR_SUFFIXES: [&str; 3] = ["-journal", "-wal", "-shm"];
+
 #[derive(Error, Debug)]
 pub enum PersistenceError {
     #[error("Database error: {0}")]
@@ -34,12 +67,61 @@ impl Database {
         Ok(db)
     }
 
+    /// 打开数据库；文件损坏时将其改名移到一旁（`<文件名>.corrupt-<时间戳>`）并新建数据库
+    /// 残留的日志文件（`-journal`、`-wal`、`-shm`）一并移走，以免套用到新数据库上
+    /// 返回的路径为被移走的损坏文件，供界面提示用户；其他错误（如无权限）照常返回
+    pub fn open_or_recover<P: AsRef<Path>>(path: P) -> Result<(Self, Option<PathBuf>)> {
+        let path = path.as_ref();
+        match Self::new(path) {
+            Ok(db) if db.is_intact()? => return Ok((db, None)),
+            Ok(_) => {}
+            Err(e) if is_corruption(&e) => {}
+            Err(e) => return Err(e),
+        }
+
+        let quarantined = PathBuf::from(format!(
+            "{}.corrupt-{}",
+            path.display(),
+            Utc::now().timestamp()
+        ));
+        std::fs::rename(path, &quarantined)?;
+        for suffix in SQLITE_SIDECAR_SUFFIXES {
+            let sidecar = PathBuf::from(format!("{}{}", path.display(), suffix));
+            if sidecar.exists() {
+                std::fs::rename(&sidecar, format!("{}{}", quarantined.display(), suffix))?;
+            }
+        }
+        Ok((Self::new(path)?, Some(quarantined)))
+    }
+
+    /// 完整性检查（PRAGMA integrity_check）是否通过
+    fn is_intact(&self) -> Result<bool> {
+        match self
+            .conn
+            .query_row("PRAGMA integrity_check", [], |row| row.get::<_, String>(0))
+        {
+            Ok(result) => Ok(result == "ok"),
+            Err(e) if e.sqlite_error_code() == Some(ErrorCode::DatabaseCorrupt) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// 使用默认路径创建数据库
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> Result<Self> {
+        Self::new(Self::default_path()?)
+    }
+
+    /// 使用默认路径打开数据库，文件损坏时自动恢复（见 `open_or_recover`）
+    pub fn default_or_recover() -> Result<(Self, Option<PathBuf>)> {
+        Self::open_or_recover(Self::default_path()?)
+    }
+
+    /// 默认数据库文件路径（会创建数据目录）
+    fn default_path() -> Result<PathBuf> {
         let data_dir = Self::get_data_dir()?;
         std::fs::create_dir_all(&data_dir)?;
-        let db_path = data_dir.join("keyzen.db");
-        Self::new(db_path)
+        Ok(data_dir.join("keyzen.db"))
     }
 
     /// 获取数据目录路径
@@ -63,10 +145,17 @@ impl Database {
                 error_count INTEGER NOT NULL,
                 duration_secs INTEGER NOT NULL,
                 completed_at INTEGER NOT NULL,
-                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+                consistency REAL,
+                raw_wpm REAL,
+                peak_wpm REAL
             )",
             [],
         )?;
+        // 旧数据库补充节奏稳定度、原始 WPM 和峰值 WPM 列
+        self.add_column_if_missing("sessions", "consistency", "REAL")?;
+        self.add_column_if_missing("sessions", "raw_wpm", "REAL")?;
+        self.add_column_if_missing("sessions", "peak_wpm", "REAL")?;
 
         // 薄弱单元表（新表结构）
         self.conn.execute(
@@ -83,6 +172,70 @@ impl Database {
             [],
         )?;
 
+        // 单个练习统计表
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS exercise_stats (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id INTEGER NOT NULL,
+                lesson_id INTEGER NOT NULL,
+                exercise_index INTEGER NOT NULL,
+                wpm REAL NOT NULL,
+                accuracy REAL NOT NULL,
+                total_keystrokes INTEGER NOT NULL,
+                error_count INTEGER NOT NULL,
+                duration_secs INTEGER NOT NULL,
+                FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // 字符按键次数表
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS char_counts (
+                session_id INTEGER NOT NULL,
+                ch TEXT NOT NULL,
+                count INTEGER NOT NULL,
+                FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // 双字母延迟表（每个会话中每个双字母的总延迟和次数）
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS bigram_latency (
+                session_id INTEGER NOT NULL,
+                bigram TEXT NOT NULL,
+                latency_ms INTEGER NOT NULL,
+                count INTEGER NOT NULL DEFAULT 1,
+                FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        // 旧数据库每行只保存一个平均延迟，按出现一次计
+        self.add_column_if_missing("bigram_latency", "count", "INTEGER NOT NULL DEFAULT 1")?;
+
+        // 已掌握练习表
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS mastered_exercises (
+                lesson_id INTEGER NOT NULL,
+                exercise_index INTEGER NOT NULL,
+                marked_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+                PRIMARY KEY(lesson_id, exercise_index)
+            )",
+            [],
+        )?;
+
+        // 手动取消掌握标记的练习（不再自动标记）
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS unmarked_exercises (
+                lesson_id INTEGER NOT NULL,
+                exercise_index INTEGER NOT NULL,
+                unmarked_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+                PRIMARY KEY(lesson_id, exercise_index)
+            )",
+            [],
+        )?;
+
         // 配置表
         self.conn.execute(
             "CREATE TABLE IF NOT EXISTS config (
@@ -113,6 +266,31 @@ impl Database {
             [],
         )?;
 
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_exercise_stats_lesson_id ON exercise_stats(lesson_id, exercise_index)",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 表中缺少某列时补充（用于升级旧数据库）
+    fn add_column_if_missing(&self, table: &str, column: &str, decl: &str) -> Result<()> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("PRAGMA table_info({})", table))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?
+            .iter()
+            .any(|name| name == column);
+
+        if !exists {
+            self.conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl),
+                [],
+            )?;
+        }
         Ok(())
     }
 
@@ -121,8 +299,9 @@ impl Database {
         self.conn.execute(
             "INSERT INTO sessions (
                 lesson_id, lesson_title, wpm, cpm, accuracy,
-                total_keystrokes, error_count, duration_secs, completed_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                total_keystrokes, error_count, duration_secs, completed_at, consistency,
+                raw_wpm, peak_wpm
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
             params![
                 stats.lesson_id,
                 lesson_title,
@@ -133,6 +312,9 @@ impl Database {
                 stats.error_count,
                 stats.duration_secs as i64,
                 stats.timestamp,
+                stats.consistency,
+                stats.raw_wpm,
+                stats.peak_wpm,
             ],
         )?;
 
@@ -141,9 +323,251 @@ impl Database {
         // 保存薄弱单元
         self.save_weak_units(session_id, &stats.weak_units)?;
 
+        // 保存每个练习的统计
+        self.save_exercise_stats(session_id, stats.lesson_id, &stats.exercise_stats)?;
+
+        // 保存字符按键次数
+        self.save_char_counts(session_id, &stats.char_counts)?;
+
+        // 保存双字母延迟
+        self.save_bigram_latency(session_id, &stats.bigram_latency)?;
+
+        // 准确率足够高的练习自动标记为已掌握（手动取消过的除外）
+        for exercise in &stats.exercise_stats {
+            if exercise.accuracy >= MASTERY_ACCURACY {
+                self.conn.execute(
+                    "INSERT OR IGNORE INTO mastered_exercises (lesson_id, exercise_index)
+                     SELECT ?1, ?2
+                     WHERE NOT EXISTS (
+                         SELECT 1 FROM unmarked_exercises
+                         WHERE lesson_id = ?1 AND exercise_index = ?2
+                     )",
+                    params![stats.lesson_id, exercise.exercise_index],
+                )?;
+            }
+        }
+
         Ok(session_id)
     }
 
+    /// 保存单个练习的统计
+    pub fn save_exercise_stats(
+        &self,
+        session_id: i64,
+        lesson_id: u32,
+        exercise_stats: &[ExerciseStats],
+    ) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "INSERT INTO exercise_stats (
+                session_id, lesson_id, exercise_index, wpm, accuracy,
+                total_keystrokes, error_count, duration_secs
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+
+        for stats in exercise_stats {
+            stmt.execute(params![
+                session_id,
+                lesson_id,
+                stats.exercise_index,
+                stats.wpm,
+                stats.accuracy,
+                stats.total_keystrokes,
+                stats.error_count,
+                stats.duration_secs as i64,
+            ])?;
+        }
+
+        Ok(())
+    }
+
+    /// 节奏稳定度在历史会话中的百分位：稳定度低于该值的会话占比（0-1）
+    /// 没有记录稳定度的历史会话时返回 None；返回 1.0 表示比以往都稳定
+    pub fn get_consistency_percentile(&self, consistency: f64) -> Result<Option<f64>> {
+        let (total, lower): (i64, i64) = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(consistency < ?1), 0)
+             FROM sessions
+             WHERE consistency IS NOT NULL",
+            [consistency],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        Ok((total > 0).then(|| lower as f64 / total as f64))
+    }
+
+    /// 获取课程中每个练习的历史平均准确率（练习索引 -> 准确率）
+    pub fn get_exercise_accuracy(&self, lesson_id: u32) -> Result<HashMap<usize, f64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT exercise_index, AVG(accuracy)
+             FROM exercise_stats
+             WHERE lesson_id = ?1
+             GROUP BY exercise_index",
+        )?;
+
+        let accuracy = stmt
+            .query_map([lesson_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        Ok(accuracy)
+    }
+
+    /// 每个课程最近一次练习的完成时间（课程 ID → 时间戳）
+    pub fn get_last_practiced(&self) -> Result<HashMap<u32, i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT lesson_id, MAX(completed_at)
+             FROM sessions
+             GROUP BY lesson_id",
+        )?;
+
+        let last_practiced = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        Ok(last_practiced)
+    }
+
+    /// 课程中练习过的最大练习索引（用于从上次进度继续），没有记录时返回 None
+    pub fn get_furthest_exercise(&self, lesson_id: u32) -> Result<Option<usize>> {
+        let furthest = self.conn.query_row(
+            "SELECT MAX(exercise_index) FROM exercise_stats WHERE lesson_id = ?1",
+            [lesson_id],
+            |row| row.get(0),
+        )?;
+
+        Ok(furthest)
+    }
+
+    /// 每个课程练习过的练习索引（一次查询所有课程，用于课程列表的继续进度）
+    pub fn get_practiced_exercises(&self) -> Result<HashMap<u32, HashSet<usize>>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT lesson_id, exercise_index FROM exercise_stats")?;
+
+        let mut practiced: HashMap<u32, HashSet<usize>> = HashMap::new();
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        for row in rows {
+            let (lesson_id, exercise_index) = row?;
+            practiced
+                .entry(lesson_id)
+                .or_default()
+                .insert(exercise_index);
+        }
+
+        Ok(practiced)
+    }
+
+    /// 保存会话中每个字符的按键次数
+    pub fn save_char_counts(&self, session_id: i64, counts: &HashMap<char, u64>) -> Result<()> {
+        let mut stmt = self
+            .conn
+            .prepare("INSERT INTO char_counts (session_id, ch, count) VALUES (?1, ?2, ?3)")?;
+
+        for (ch, count) in counts {
+            stmt.execute(params![session_id, ch.to_string(), *count as i64])?;
+        }
+
+        Ok(())
+    }
+
+    /// 获取所有会话的字符按键次数汇总（按次数降序）
+    pub fn get_character_frequency(&self) -> Result<Vec<(char, u64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ch, SUM(count) AS total
+             FROM char_counts
+             GROUP BY ch
+             ORDER BY total DESC, ch",
+        )?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(ch, total)| ch.chars().next().map(|ch| (ch, total as u64)))
+            .collect())
+    }
+
+    /// 保存会话中每个双字母的总延迟和次数
+    pub fn save_bigram_latency(
+        &self,
+        session_id: i64,
+        bigrams: &[(String, Duration, u32)],
+    ) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "INSERT INTO bigram_latency (session_id, bigram, latency_ms, count)
+             VALUES (?1, ?2, ?3, ?4)",
+        )?;
+
+        for (bigram, total, count) in bigrams {
+            stmt.execute(params![session_id, bigram, total.as_millis() as i64, count])?;
+        }
+
+        Ok(())
+    }
+
+    /// 获取历史平均延迟最长的双字母（按出现次数加权，按延迟降序）
+    pub fn get_slow_bigrams(&self, limit: usize) -> Result<Vec<(String, Duration)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT bigram, CAST(SUM(latency_ms) AS REAL) / SUM(count) AS latency
+             FROM bigram_latency
+             GROUP BY bigram
+             ORDER BY latency DESC, bigram
+             LIMIT ?1",
+        )?;
+
+        let rows = stmt
+            .query_map([limit as i64], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    Duration::from_millis(row.get::<_, f64>(1)? as u64),
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows)
+    }
+
+    /// 手动标记练习为已掌握（恢复自动标记）
+    pub fn mark_exercise_mastered(&self, lesson_id: u32, exercise_index: usize) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM unmarked_exercises WHERE lesson_id = ?1 AND exercise_index = ?2",
+            params![lesson_id, exercise_index],
+        )?;
+        self.conn.execute(
+            "INSERT OR IGNORE INTO mastered_exercises (lesson_id, exercise_index) VALUES (?1, ?2)",
+            params![lesson_id, exercise_index],
+        )?;
+        Ok(())
+    }
+
+    /// 手动取消练习的已掌握标记（之后不再自动标记，直到再次手动标记）
+    pub fn unmark_exercise_mastered(&self, lesson_id: u32, exercise_index: usize) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM mastered_exercises WHERE lesson_id = ?1 AND exercise_index = ?2",
+            params![lesson_id, exercise_index],
+        )?;
+        self.conn.execute(
+            "INSERT OR IGNORE INTO unmarked_exercises (lesson_id, exercise_index) VALUES (?1, ?2)",
+            params![lesson_id, exercise_index],
+        )?;
+        Ok(())
+    }
+
+    /// 获取课程中已掌握的练习索引
+    pub fn get_mastered_exercises(&self, lesson_id: u32) -> Result<HashSet<usize>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT exercise_index FROM mastered_exercises WHERE lesson_id = ?1")?;
+
+        let mastered = stmt
+            .query_map([lesson_id], |row| row.get(0))?
+            .collect::<Result<HashSet<usize>, _>>()?;
+
+        Ok(mastered)
+    }
+
     /// 保存薄弱单元
     pub fn save_weak_units(&self, session_id: i64, units: &[WeakUnit]) -> Result<()> {
         let mut stmt = self.conn.prepare(
@@ -169,27 +593,15 @@ impl Database {
     pub fn get_recent_sessions(&self, limit: usize) -> Result<Vec<SessionRecord>> {
         let mut stmt = self.conn.prepare(
             "SELECT id, lesson_id, lesson_title, wpm, cpm, accuracy,
-                    total_keystrokes, error_count, duration_secs, completed_at
+                    total_keystrokes, error_count, duration_secs, completed_at, raw_wpm,
+                    peak_wpm
              FROM sessions
              ORDER BY completed_at DESC
              LIMIT ?1",
         )?;
 
         let sessions = stmt
-            .query_map([limit], |row| {
-                Ok(SessionRecord {
-                    id: row.get(0)?,
-                    lesson_id: row.get(1)?,
-                    lesson_title: row.get(2)?,
-                    wpm: row.get(3)?,
-                    cpm: row.get(4)?,
-                    accuracy: row.get(5)?,
-                    total_keystrokes: row.get(6)?,
-                    error_count: row.get(7)?,
-                    duration_secs: row.get(8)?,
-                    completed_at: row.get(9)?,
-                })
-            })?
+            .query_map([limit], SessionRecord::from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(sessions)
@@ -199,7 +611,8 @@ impl Database {
     pub fn get_lesson_history(&self, lesson_id: i32, limit: usize) -> Result<Vec<SessionRecord>> {
         let mut stmt = self.conn.prepare(
             "SELECT id, lesson_id, lesson_title, wpm, cpm, accuracy,
-                    total_keystrokes, error_count, duration_secs, completed_at
+                    total_keystrokes, error_count, duration_secs, completed_at, raw_wpm,
+                    peak_wpm
              FROM sessions
              WHERE lesson_id = ?1
              ORDER BY completed_at DESC
@@ -207,20 +620,7 @@ impl Database {
         )?;
 
         let sessions = stmt
-            .query_map(params![lesson_id, limit], |row| {
-                Ok(SessionRecord {
-                    id: row.get(0)?,
-                    lesson_id: row.get(1)?,
-                    lesson_title: row.get(2)?,
-                    wpm: row.get(3)?,
-                    cpm: row.get(4)?,
-                    accuracy: row.get(5)?,
-                    total_keystrokes: row.get(6)?,
-                    error_count: row.get(7)?,
-                    duration_secs: row.get(8)?,
-                    completed_at: row.get(9)?,
-                })
-            })?
+            .query_map(params![lesson_id, limit], SessionRecord::from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(sessions)
@@ -251,8 +651,68 @@ impl Database {
         Ok(units)
     }
 
+    /// 对比两次会话（`id_b` 相对 `id_a` 的变化），任一会话不存在时返回 NotFound
+    /// 不同课程的会话也可对比，但 `cross_lesson` 为 true，界面应提示对比意义有限
+    pub fn compare_sessions(&self, id_a: i64, id_b: i64) -> Result<SessionComparison> {
+        let (lesson_a, wpm_a, accuracy_a, consistency_a) = self.get_session_summary(id_a)?;
+        let (lesson_b, wpm_b, accuracy_b, consistency_b) = self.get_session_summary(id_b)?;
+
+        let weak_a: HashSet<String> = self
+            .get_weak_units(id_a)?
+            .into_iter()
+            .map(|unit| unit.content)
+            .collect();
+        let weak_b: HashSet<String> = self
+            .get_weak_units(id_b)?
+            .into_iter()
+            .map(|unit| unit.content)
+            .collect();
+        let mut resolved_weak_units: Vec<String> = weak_a.difference(&weak_b).cloned().collect();
+        let mut new_weak_units: Vec<String> = weak_b.difference(&weak_a).cloned().collect();
+        resolved_weak_units.sort();
+        new_weak_units.sort();
+
+        Ok(SessionComparison {
+            session_a: id_a,
+            session_b: id_b,
+            cross_lesson: lesson_a != lesson_b,
+            wpm_delta: wpm_b - wpm_a,
+            accuracy_delta: accuracy_b - accuracy_a,
+            consistency_delta: consistency_a.zip(consistency_b).map(|(a, b)| b - a),
+            resolved_weak_units,
+            new_weak_units,
+        })
+    }
+
+    /// 会话的 (课程 ID, WPM, 准确率, 节奏稳定度)
+    fn get_session_summary(&self, session_id: i64) -> Result<(i32, f64, f64, Option<f64>)> {
+        let mut stmt = self.conn.prepare(
+            "SELECT lesson_id, wpm, accuracy, consistency
+             FROM sessions
+             WHERE id = ?1",
+        )?;
+
+        let summary = stmt
+            .query_map([session_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .next()
+            .ok_or(PersistenceError::NotFound)??;
+
+        Ok(summary)
+    }
+
     /// 获取所有会话的薄弱单元汇总（按错误率排序）
     pub fn get_overall_weak_units(&self, limit: usize) -> Result<Vec<WeakUnit>> {
+        self.get_overall_weak_units_above(limit, DEFAULT_WEAK_UNIT_THRESHOLD)
+    }
+
+    /// 获取平均错误率高于 `min_error_rate` 的薄弱单元汇总（按错误率排序）
+    pub fn get_overall_weak_units_above(
+        &self,
+        limit: usize,
+        min_error_rate: f32,
+    ) -> Result<Vec<WeakUnit>> {
         let mut stmt = self.conn.prepare(
             "SELECT content, unit_type,
                     SUM(error_count) as total_errors,
@@ -260,13 +720,13 @@ impl Database {
                     CAST(SUM(error_count) AS REAL) / CAST(SUM(total_count) AS REAL) as avg_error_rate
              FROM weak_units
              GROUP BY content, unit_type
-             HAVING total_occurrences >= 1 AND avg_error_rate > 0.10
+             HAVING total_occurrences >= 1 AND avg_error_rate > ?2
              ORDER BY avg_error_rate DESC
              LIMIT ?1",
         )?;
 
         let units = stmt
-            .query_map([limit], |row| {
+            .query_map(params![limit, min_error_rate], |row| {
                 let unit_type_str: String = row.get(1)?;
                 Ok(WeakUnit {
                     content: row.get(0)?,
@@ -335,10 +795,223 @@ impl Database {
 
         Ok(stats)
     }
+
+    /// 获取累计练习时长（所有会话的时长之和）
+    pub fn get_total_practice_time(&self) -> Result<Duration> {
+        let total_secs: Option<i64> =
+            self.conn
+                .query_row("SELECT SUM(duration_secs) FROM sessions", [], |row| {
+                    row.get(0)
+                })?;
+
+        Ok(Duration::from_secs(total_secs.unwrap_or(0).max(0) as u64))
+    }
+
+    /// 练习次数最多的课程 (课程 ID, 最近一次的课程标题, 会话次数)，次数相同时最近练习的在前
+    pub fn get_most_practiced_lessons(&self, limit: usize) -> Result<Vec<(i32, String, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT lesson_id, lesson_title, COUNT(*) AS count, MAX(completed_at) AS last
+             FROM sessions
+             GROUP BY lesson_id
+             ORDER BY count DESC, last DESC
+             LIMIT ?1",
+        )?;
+
+        let lessons = stmt
+            .query_map([limit], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(lessons)
+    }
+
+    /// 获取某个本地日期内完成的所有会话（按完成时间升序）
+    pub fn get_sessions_on_day(&self, date: NaiveDate) -> Result<Vec<SessionRecord>> {
+        self.get_sessions_between(
+            local_day_start(date),
+            local_day_start(date.succ_opt().unwrap()),
+        )
+    }
+
+    /// 获取最近 `days` 天（含今天）每天的会话数和练习秒数，按日期升序，没有练习的日期计为 0
+    pub fn get_activity_calendar(&self, days: usize) -> Result<Vec<(NaiveDate, usize, u64)>> {
+        if days == 0 {
+            return Ok(Vec::new());
+        }
+
+        let today = Local::now().date_naive();
+        let first_day = today - chrono::Days::new(days as u64 - 1);
+        let mut calendar: Vec<(NaiveDate, usize, u64)> = first_day
+            .iter_days()
+            .take(days)
+            .map(|date| (date, 0, 0))
+            .collect();
+
+        let sessions = self.get_sessions_between(
+            local_day_start(first_day),
+            local_day_start(today.succ_opt().unwrap()),
+        )?;
+        for session in sessions {
+            let Some(date) = Local.timestamp_opt(session.completed_at, 0).single() else {
+                continue;
+            };
+            let index = (date.date_naive() - first_day).num_days() as usize;
+            if let Some(day) = calendar.get_mut(index) {
+                day.1 += 1;
+                day.2 += session.duration_secs.max(0) as u64;
+            }
+        }
+
+        Ok(calendar)
+    }
+
+    /// 获取完成时间在 [start, end) 内的会话
+    fn get_sessions_between(&self, start: i64, end: i64) -> Result<Vec<SessionRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, lesson_id, lesson_title, wpm, cpm, accuracy,
+                    total_keystrokes, error_count, duration_secs, completed_at, raw_wpm,
+                    peak_wpm
+             FROM sessions
+             WHERE completed_at >= ?1 AND completed_at < ?2
+             ORDER BY completed_at ASC",
+        )?;
+
+        let sessions = stmt
+            .query_map(params![start, end], SessionRecord::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(sessions)
+    }
+
+    /// 根据最近会话的进步趋势估算还需练习多少次才能达到目标
+    ///
+    /// 对 WPM 和准确率分别做线性拟合，取两者中所需次数较多的一个；
+    /// 已达到目标时返回 Some(0)，数据不足、没有进步或预测次数过多时返回 None
+    pub fn estimate_sessions_to_mastery(
+        &self,
+        lesson_id: u32,
+        target_wpm: f64,
+        target_accuracy: f64,
+    ) -> Result<Option<u32>> {
+        let mut history = self.get_lesson_history(lesson_id as i32, MASTERY_TREND_WINDOW)?;
+        if history.len() < MASTERY_TREND_MIN_SESSIONS {
+            return Ok(None);
+        }
+        history.reverse(); // 从旧到新
+
+        let wpm: Vec<f64> = history.iter().map(|s| s.wpm).collect();
+        let accuracy: Vec<f64> = history.iter().map(|s| s.accuracy).collect();
+
+        let estimate = sessions_to_reach(&wpm, target_wpm)
+            .zip(sessions_to_reach(&accuracy, target_accuracy))
+            .map(|(a, b)| a.max(b))
+            .filter(|n| *n <= MASTERY_ESTIMATE_MAX_SESSIONS);
+        Ok(estimate)
+    }
+
+    /// 跨课程复习：从最近的会话中挑选准确率较低的练习，返回最多 `limit` 个 (课程 ID, 练习索引)
+    ///
+    /// 每个课程的练习按最低准确率从低到高，各课程轮流取一个，使复习覆盖所有表现不佳的课程
+    pub fn build_recent_struggles_review(&self, limit: usize) -> Result<Vec<(u32, usize)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT e.lesson_id, e.exercise_index, MIN(e.accuracy) AS worst
+             FROM exercise_stats e
+             JOIN (
+                 SELECT id FROM sessions
+                 ORDER BY completed_at DESC
+                 LIMIT ?1
+             ) s ON e.session_id = s.id
+             WHERE e.accuracy < ?2
+             GROUP BY e.lesson_id, e.exercise_index
+             ORDER BY worst, e.lesson_id, e.exercise_index",
+        )?;
+
+        let rows = stmt
+            .query_map(
+                params![REVIEW_RECENT_SESSIONS, REVIEW_STRUGGLE_ACCURACY],
+                |row| Ok((row.get::<_, u32>(0)?, row.get::<_, usize>(1)?)),
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // 按课程分组（课程顺序按其最差练习的准确率）
+        let mut by_lesson: Vec<(u32, Vec<usize>)> = Vec::new();
+        for (lesson_id, exercise_index) in rows {
+            match by_lesson.iter_mut().find(|(id, _)| *id == lesson_id) {
+                Some((_, exercises)) => exercises.push(exercise_index),
+                None => by_lesson.push((lesson_id, vec![exercise_index])),
+            }
+        }
+
+        let mut review = Vec::new();
+        for round in 0.. {
+            let before = review.len();
+            for (lesson_id, exercises) in &by_lesson {
+                if review.len() >= limit {
+                    return Ok(review);
+                }
+                if let Some(&index) = exercises.get(round) {
+                    review.push((*lesson_id, index));
+                }
+            }
+            if review.len() == before {
+                break;
+            }
+        }
+        Ok(review)
+    }
+}
+
+/// CSV 字段：含逗号、引号或换行时加引号，引号写两次
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 本地日期零点的时间戳
+fn local_day_start(date: NaiveDate) -> i64 {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .unwrap_or_else(|| midnight.and_utc().timestamp())
+}
+
+/// 按线性趋势推算达到目标还需的次数（已达到返回 Some(0)，没有进步返回 None）
+fn sessions_to_reach(values: &[f64], target: f64) -> Option<u32> {
+    let n = values.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (i, y) in values.iter().enumerate() {
+        let dx = i as f64 - mean_x;
+        cov += dx * (y - mean_y);
+        var += dx * dx;
+    }
+    let slope = cov / var;
+
+    // 以拟合值作为当前水平，减少单次波动的影响
+    let current = mean_y + slope * (n - 1.0 - mean_x);
+    if current >= target {
+        return Some(0);
+    }
+    if slope <= f64::EPSILON {
+        return None;
+    }
+    Some(((target - current) / slope).ceil() as u32)
+}
+
+/// 错误是否表示数据库文件损坏（不是 SQLite 数据库或内容损坏）
+fn is_corruption(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<rusqlite::Error>()
+        .and_then(rusqlite::Error::sqlite_error_code)
+        .is_some_and(|code| matches!(code, ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase))
 }
 
 /// 会话记录
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SessionRecord {
     pub id: i64,
     pub lesson_id: i32,
@@ -350,6 +1023,47 @@ pub struct SessionRecord {
     pub error_count: usize,
     pub duration_secs: i64,
     pub completed_at: i64,
+    /// 含输错按键的 WPM（旧记录为 None）
+    pub raw_wpm: Option<f64>,
+    /// 最快的 5 秒爆发 WPM（旧记录为 None）
+    pub peak_wpm: Option<f64>,
+}
+
+impl SessionRecord {
+    /// 从 sessions 表的查询结果构建（列顺序与 SELECT 语句一致）
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            lesson_id: row.get(1)?,
+            lesson_title: row.get(2)?,
+            wpm: row.get(3)?,
+            cpm: row.get(4)?,
+            accuracy: row.get(5)?,
+            total_keystrokes: row.get(6)?,
+            error_count: row.get(7)?,
+            duration_secs: row.get(8)?,
+            completed_at: row.get(9)?,
+            raw_wpm: row.get(10)?,
+            peak_wpm: row.get(11)?,
+        })
+    }
+}
+
+/// 两次会话的对比（差值均为 b 减 a）
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionComparison {
+    pub session_a: i64,
+    pub session_b: i64,
+    /// 两次会话属于不同课程
+    pub cross_lesson: bool,
+    pub wpm_delta: f64,
+    pub accuracy_delta: f64,
+    /// 节奏稳定度的变化（任一会话未记录稳定度时为 None）
+    pub consistency_delta: Option<f64>,
+    /// 只在 a 中出现的薄弱单元（已克服）
+    pub resolved_weak_units: Vec<String>,
+    /// 只在 b 中出现的薄弱单元（新出现）
+    pub new_weak_units: Vec<String>,
 }
 
 /// 薄弱按键
@@ -360,7 +1074,7 @@ pub struct WeakKey {
 }
 
 /// 总体统计
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OverallStats {
     pub total_sessions: i64,
     pub total_keystrokes: i64,
@@ -399,13 +1113,271 @@ impl Database {
             .execute("DELETE FROM config WHERE key = ?1", params![key])?;
         Ok(())
     }
+
+    /// 获取所有配置项
+    pub fn get_all_config(&self) -> Result<BTreeMap<String, String>> {
+        let mut stmt = self.conn.prepare("SELECT key, value FROM config")?;
+        let config = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        Ok(config)
+    }
+}
+
+/// 用户档案：汇总统计、连续练习天数、设置和薄弱单元（用于跨设备迁移）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserProfile {
+    pub overall: OverallStats,
+    pub total_practice_secs: u64,
+    /// 截至今天（或昨天）连续练习的天数
+    pub streak_days: u32,
+    pub settings: BTreeMap<String, String>,
+    pub weak_units: Vec<WeakUnit>,
+}
+
+/// 档案中保留的薄弱单元数
+const PROFILE_WEAK_UNITS: usize = 20;
+
+impl Database {
+    /// 获取连续练习天数（按本地日期，今天或昨天有练习才算连续）
+    pub fn get_practice_streak(&self) -> Result<u32> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT completed_at FROM sessions ORDER BY completed_at DESC")?;
+        let mut days: Vec<NaiveDate> = stmt
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .filter_map(|ts| ts.ok().and_then(|ts| Local.timestamp_opt(ts, 0).single()))
+            .map(|dt| dt.date_naive())
+            .collect();
+        days.dedup();
+
+        let today = Local::now().date_naive();
+        let Some(&latest) = days.first() else {
+            return Ok(0);
+        };
+        if (today - latest).num_days() > 1 {
+            return Ok(0);
+        }
+
+        let streak = days
+            .windows(2)
+            .take_while(|pair| (pair[0] - pair[1]).num_days() == 1)
+            .count();
+        Ok(streak as u32 + 1)
+    }
+
+    /// 生成用户档案
+    pub fn build_profile(&self) -> Result<UserProfile> {
+        Ok(UserProfile {
+            overall: self.get_overall_stats()?,
+            total_practice_secs: self.get_total_practice_time()?.as_secs(),
+            streak_days: self.get_practice_streak()?,
+            settings: self.get_all_config()?,
+            weak_units: self.get_overall_weak_units(PROFILE_WEAK_UNITS)?,
+        })
+    }
+
+    /// 导出用户档案为 JSON
+    pub fn export_profile(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.build_profile()?)?)
+    }
+
+    /// 从 JSON 导入用户档案：校验并恢复其中的设置，返回解析出的档案
+    /// 汇总统计只用于展示，不会写入会话记录
+    pub fn import_profile(&self, json: &str) -> Result<UserProfile> {
+        let profile: UserProfile = serde_json::from_str(json)?;
+        self.import_config(&profile.settings)?;
+        Ok(profile)
+    }
+}
+
+/// 导出的会话：sessions 表的记录及其薄弱单元
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionExport {
+    #[serde(flatten)]
+    pub record: SessionRecord,
+    /// 完成时间的 ISO-8601 形式（UTC，便于阅读；导入时以 completed_at 为准）
+    pub completed_at_iso: String,
+    pub consistency: Option<f64>,
+    pub weak_units: Vec<WeakUnit>,
+}
+
+impl Database {
+    /// 读取一个会话及其薄弱单元，会话不存在时返回 NotFound
+    pub fn get_session_export(&self, session_id: i64) -> Result<SessionExport> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, lesson_id, lesson_title, wpm, cpm, accuracy,
+                    total_keystrokes, error_count, duration_secs, completed_at, raw_wpm,
+                    peak_wpm, consistency
+             FROM sessions
+             WHERE id = ?1",
+        )?;
+
+        let (record, consistency) = stmt
+            .query_map([session_id], |row| {
+                Ok((SessionRecord::from_row(row)?, row.get(12)?))
+            })?
+            .next()
+            .ok_or(PersistenceError::NotFound)??;
+
+        Ok(SessionExport {
+            completed_at_iso: DateTime::from_timestamp(record.completed_at, 0)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default(),
+            record,
+            consistency,
+            weak_units: self.get_weak_units(session_id)?,
+        })
+    }
+
+    /// 导出一个会话为 JSON
+    pub fn export_session_json(&self, session_id: i64) -> Result<String> {
+        Ok(serde_json::to_string_pretty(
+            &self.get_session_export(session_id)?,
+        )?)
+    }
+
+    /// 导出全部会话为 JSON 数组（按完成时间排序，用于备份）
+    pub fn export_all_sessions_json(&self) -> Result<String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id FROM sessions ORDER BY completed_at, id")?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let sessions = ids
+            .into_iter()
+            .map(|id| self.get_session_export(id))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(serde_json::to_string_pretty(&sessions)?)
+    }
+
+    /// 导出全部会话为 CSV（按完成时间排序，首行为表头，完成时间为 UTC 的 ISO-8601）
+    pub fn export_sessions_csv(&self, mut writer: impl std::io::Write) -> Result<()> {
+        writeln!(
+            writer,
+            "id,lesson_title,wpm,cpm,accuracy,keystrokes,errors,duration,completed_at"
+        )?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT id, lesson_id, lesson_title, wpm, cpm, accuracy,
+                    total_keystrokes, error_count, duration_secs, completed_at, raw_wpm,
+                    peak_wpm
+             FROM sessions
+             ORDER BY completed_at, id",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let record = SessionRecord::from_row(row)?;
+            let completed_at = DateTime::from_timestamp(record.completed_at, 0)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default();
+            writeln!(
+                writer,
+                "{},{},{:.2},{:.2},{:.4},{},{},{},{}",
+                record.id,
+                csv_field(&record.lesson_title),
+                record.wpm,
+                record.cpm,
+                record.accuracy,
+                record.total_keystrokes,
+                record.error_count,
+                record.duration_secs,
+                completed_at,
+            )?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// 从 JSON 导入一个会话（export_session_json 的输出），返回新的会话 ID
+    /// 原会话 ID 不保留；练习统计、按键次数等明细不在导出中
+    pub fn import_session_json(&self, json: &str) -> Result<i64> {
+        let export: SessionExport = serde_json::from_str(json)?;
+        let record = &export.record;
+        self.conn.execute(
+            "INSERT INTO sessions (
+                lesson_id, lesson_title, wpm, cpm, accuracy,
+                total_keystrokes, error_count, duration_secs, completed_at, consistency,
+                raw_wpm, peak_wpm
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                record.lesson_id,
+                record.lesson_title,
+                record.wpm,
+                record.cpm,
+                record.accuracy,
+                record.total_keystrokes,
+                record.error_count,
+                record.duration_secs,
+                record.completed_at,
+                export.consistency,
+                record.raw_wpm,
+                record.peak_wpm,
+            ],
+        )?;
+
+        let session_id = self.conn.last_insert_rowid();
+        self.save_weak_units(session_id, &export.weak_units)?;
+        Ok(session_id)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Utc;
-    use std::time::Duration;
+
+    /// 测试用的会话统计，其余字段按需用结构体更新语法覆盖
+    fn test_stats(lesson_id: u32, accuracy: f64) -> SessionStats {
+        SessionStats {
+            lesson_id,
+            exercise_stats: vec![],
+            overall_wpm: 40.0,
+            overall_cpm: 200.0,
+            overall_accuracy: accuracy,
+            total_keystrokes: 20,
+            error_count: 1,
+            duration_secs: 20,
+            timestamp: Utc::now().timestamp(),
+            weak_units: vec![],
+            error_categories: HashMap::new(),
+            pace_deviation: None,
+            char_counts: HashMap::new(),
+            slow_bigrams: Vec::new(),
+            bigram_latency: Vec::new(),
+            consistency: None,
+            peak_wpm: 0.0,
+            raw_wpm: 0.0,
+            finger_stats: HashMap::new(),
+            longest_flow: Duration::ZERO,
+            latency: None,
+        }
+    }
+
+    /// 测试用的练习统计
+    fn test_exercise(index: usize, accuracy: f64) -> ExerciseStats {
+        ExerciseStats {
+            exercise_index: index,
+            content_preview: String::new(),
+            wpm: 40.0,
+            accuracy,
+            total_keystrokes: 10,
+            error_count: 0,
+            duration_secs: 10,
+            error_categories: HashMap::new(),
+            capitalization_errors: 0,
+            attempts_to_perfect: None,
+            raw_wpm: 0.0,
+            peak_wpm: 0.0,
+            idle_secs: 0,
+            finger_stats: HashMap::new(),
+            excluded_from_wpm: 0,
+            correct_keystrokes: 10,
+        }
+    }
 
     #[test]
     fn test_database_creation() {
@@ -414,19 +1386,43 @@ mod tests {
         assert_eq!(stats.total_sessions, 0);
     }
 
+    #[test]
+    fn test_recover_corrupt_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keyzen.db");
+        std::fs::write(&path, "this is not a sqlite database, just garbage bytes").unwrap();
+        // 残留的日志文件（无效的 -journal/-wal 在检查时会被 SQLite 自行丢弃，-shm 不会）
+        let shm = dir.path().join("keyzen.db-shm");
+        std::fs::write(&shm, "leftover shm").unwrap();
+
+        let (db, quarantined) = Database::open_or_recover(&path).unwrap();
+        let quarantined = quarantined.expect("corrupt file should be moved aside");
+        assert!(quarantined.to_string_lossy().contains("keyzen.db.corrupt-"));
+        assert!(std::fs::read_to_string(&quarantined)
+            .unwrap()
+            .starts_with("this is not"));
+        // 残留的日志文件随损坏文件一起移走
+        let moved_shm = format!("{}-shm", quarantined.display());
+        assert_eq!(std::fs::read_to_string(moved_shm).unwrap(), "leftover shm");
+        assert!(!shm.exists());
+        assert_eq!(db.get_overall_stats().unwrap().total_sessions, 0);
+        drop(db);
+
+        // 新建的数据库再次打开时无需恢复
+        let (_, quarantined) = Database::open_or_recover(&path).unwrap();
+        assert!(quarantined.is_none());
+    }
+
     #[test]
     fn test_save_and_retrieve_session() {
         let db = Database::new(":memory:").unwrap();
 
         let stats = SessionStats {
-            lesson_id: 1,
-            wpm: 45.5,
-            cpm: 227.5,
-            accuracy: 0.95,
+            overall_wpm: 45.5,
+            overall_cpm: 227.5,
             total_keystrokes: 100,
             error_count: 5,
-            duration: Duration::from_secs(60),
-            timestamp: Utc::now().timestamp(),
+            duration_secs: 60,
             weak_units: vec![
                 WeakUnit {
                     content: "a".to_string(),
@@ -443,6 +1439,15 @@ mod tests {
                     error_rate: 0.2,
                 },
             ],
+            char_counts: HashMap::from([('a', 3), ('s', 5)]),
+            slow_bigrams: vec![("th".to_string(), Duration::from_millis(250))],
+            bigram_latency: vec![
+                ("th".to_string(), Duration::from_millis(750), 3),
+                ("er".to_string(), Duration::from_millis(100), 1),
+            ],
+            peak_wpm: 72.0,
+            raw_wpm: 48.0,
+            ..test_stats(1, 0.95)
         };
 
         let session_id = db.save_session(&stats, "Test Lesson").unwrap();
@@ -452,8 +1457,472 @@ mod tests {
         assert_eq!(sessions.len(), 1);
         assert_eq!(sessions[0].lesson_title, "Test Lesson");
         assert_eq!(sessions[0].wpm, 45.5);
+        assert_eq!(sessions[0].raw_wpm, Some(48.0));
+        assert_eq!(sessions[0].peak_wpm, Some(72.0));
 
         let weak_units = db.get_weak_units(session_id).unwrap();
         assert_eq!(weak_units.len(), 2);
+
+        // 字符按键次数跨会话累计
+        db.save_session(&stats, "Test Lesson").unwrap();
+        assert_eq!(
+            db.get_character_frequency().unwrap(),
+            vec![('s', 10), ('a', 6)]
+        );
+        assert_eq!(
+            db.get_slow_bigrams(5).unwrap(),
+            vec![
+                ("th".to_string(), Duration::from_millis(250)),
+                ("er".to_string(), Duration::from_millis(100)),
+            ]
+        );
+
+        // 跨会话按出现次数加权：(750 * 2 + 1250) / 7
+        let mut slow = stats.clone();
+        slow.bigram_latency = vec![("th".to_string(), Duration::from_millis(1250), 1)];
+        db.save_session(&slow, "Test Lesson").unwrap();
+        assert_eq!(
+            db.get_slow_bigrams(1).unwrap(),
+            vec![("th".to_string(), Duration::from_millis(392))]
+        );
+    }
+
+    #[test]
+    fn test_exercise_accuracy_history() {
+        let db = Database::new(":memory:").unwrap();
+
+        let session = |accuracy: f64| SessionStats {
+            exercise_stats: vec![test_exercise(0, 1.0), test_exercise(1, accuracy)],
+            ..test_stats(7, 0.9)
+        };
+        for accuracy in [0.5, 0.7] {
+            db.save_session(&session(accuracy), "Lesson 7").unwrap();
+        }
+
+        let accuracy = db.get_exercise_accuracy(7).unwrap();
+        assert_eq!(accuracy.len(), 2);
+        assert_eq!(db.get_furthest_exercise(7).unwrap(), Some(1));
+        assert_eq!(db.get_furthest_exercise(8).unwrap(), None);
+        assert_eq!(
+            db.get_practiced_exercises().unwrap(),
+            HashMap::from([(7, HashSet::from([0, 1]))])
+        );
+        assert!((accuracy[&0] - 1.0).abs() < 1e-9);
+        assert!((accuracy[&1] - 0.6).abs() < 1e-9);
+        assert!(db.get_exercise_accuracy(8).unwrap().is_empty());
+
+        // 准确率 100% 的练习自动标记为已掌握，也可以手动标记和取消
+        assert_eq!(db.get_mastered_exercises(7).unwrap(), HashSet::from([0]));
+        db.mark_exercise_mastered(7, 1).unwrap();
+        db.unmark_exercise_mastered(7, 0).unwrap();
+        assert_eq!(db.get_mastered_exercises(7).unwrap(), HashSet::from([1]));
+
+        // 手动取消的练习再次达到准确率也不会自动标记，手动标记后恢复
+        db.save_session(&session(0.5), "Lesson 7").unwrap();
+        assert_eq!(db.get_mastered_exercises(7).unwrap(), HashSet::from([1]));
+        db.mark_exercise_mastered(7, 0).unwrap();
+        assert_eq!(db.get_mastered_exercises(7).unwrap(), HashSet::from([0, 1]));
+    }
+
+    #[test]
+    fn test_export_sessions_csv() {
+        let db = Database::new(":memory:").unwrap();
+        let stats = SessionStats {
+            overall_wpm: 48.25,
+            overall_cpm: 241.25,
+            total_keystrokes: 200,
+            error_count: 7,
+            duration_secs: 50,
+            timestamp: 1_700_000_000,
+            ..test_stats(4, 0.965)
+        };
+        let id = db.save_session(&stats, "Quotes, \"commas\"").unwrap();
+
+        let mut out = Vec::new();
+        db.export_sessions_csv(&mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "id,lesson_title,wpm,cpm,accuracy,keystrokes,errors,duration,completed_at",
+                &format!(
+                    "{},\"Quotes, \"\"commas\"\"\",48.25,241.25,0.9650,200,7,50,2023-11-14T22:13:20+00:00",
+                    id
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recent_struggles_review() {
+        let db = Database::new(":memory:").unwrap();
+        assert!(db.build_recent_struggles_review(10).unwrap().is_empty());
+
+        // 按练习的准确率挑选：课程 9 整体达标但有一个练习较差，课程 3 的练习 1 达标不复习
+        for (lesson_id, overall_accuracy, exercises) in [
+            (
+                3,
+                0.8,
+                vec![
+                    test_exercise(0, 0.7),
+                    test_exercise(1, 0.95),
+                    test_exercise(2, 0.6),
+                ],
+            ),
+            (5, 0.85, vec![test_exercise(0, 0.75)]),
+            (9, 0.98, vec![test_exercise(0, 0.5)]),
+        ] {
+            let stats = SessionStats {
+                exercise_stats: exercises,
+                ..test_stats(lesson_id, overall_accuracy)
+            };
+            db.save_session(&stats, "Lesson").unwrap();
+        }
+
+        let review = db.build_recent_struggles_review(3).unwrap();
+        assert_eq!(review, [(9, 0), (3, 2), (5, 0)]);
+        let review = db.build_recent_struggles_review(10).unwrap();
+        assert_eq!(review, [(9, 0), (3, 2), (5, 0), (3, 0)]);
+    }
+
+    #[test]
+    fn test_total_practice_time() {
+        let db = Database::new(":memory:").unwrap();
+        assert_eq!(db.get_total_practice_time().unwrap(), Duration::ZERO);
+
+        for duration_secs in [60, 90] {
+            let stats = SessionStats {
+                duration_secs,
+                ..test_stats(1, 0.9)
+            };
+            db.save_session(&stats, "Test Lesson").unwrap();
+        }
+
+        assert_eq!(
+            db.get_total_practice_time().unwrap(),
+            Duration::from_secs(150)
+        );
+    }
+
+    #[test]
+    fn test_most_practiced_lessons() {
+        let db = Database::new(":memory:").unwrap();
+        let now = Utc::now().timestamp();
+
+        let save = |lesson_id: u32, title: &str, i: i64| {
+            let stats = SessionStats {
+                duration_secs: 30,
+                timestamp: now + i,
+                ..test_stats(lesson_id, 0.9)
+            };
+            db.save_session(&stats, title).unwrap();
+        };
+
+        save(1, "Home Row", 0);
+        for i in 1..=3 {
+            save(2, "Numbers", i);
+        }
+        save(3, "Symbols", 4);
+        save(3, "Symbols (renamed)", 5);
+
+        let lessons = db.get_most_practiced_lessons(10).unwrap();
+        assert_eq!(
+            lessons,
+            vec![
+                (2, "Numbers".to_string(), 3),
+                (3, "Symbols (renamed)".to_string(), 2),
+                (1, "Home Row".to_string(), 1),
+            ]
+        );
+        assert_eq!(db.get_most_practiced_lessons(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_compare_sessions() {
+        let db = Database::new(":memory:").unwrap();
+
+        let save = |lesson_id: u32, wpm: f64, accuracy: f64, weak: &[&str]| {
+            let stats = SessionStats {
+                overall_wpm: wpm,
+                overall_cpm: wpm * 5.0,
+                total_keystrokes: 100,
+                error_count: 5,
+                duration_secs: 60,
+                weak_units: weak
+                    .iter()
+                    .map(|content| WeakUnit {
+                        content: content.to_string(),
+                        unit_type: UnitType::Character,
+                        error_count: 3,
+                        total_count: 10,
+                        error_rate: 0.3,
+                    })
+                    .collect(),
+                consistency: Some(accuracy - 0.2),
+                ..test_stats(lesson_id, accuracy)
+            };
+            db.save_session(&stats, "Lesson").unwrap()
+        };
+
+        let a = save(1, 40.0, 0.90, &["a", "s"]);
+        let b = save(1, 45.5, 0.95, &["s", "d"]);
+        let other = save(2, 50.0, 0.80, &[]);
+
+        let comparison = db.compare_sessions(a, b).unwrap();
+        assert!(!comparison.cross_lesson);
+        assert!((comparison.wpm_delta - 5.5).abs() < 1e-9);
+        assert!((comparison.accuracy_delta - 0.05).abs() < 1e-9);
+        assert!((comparison.consistency_delta.unwrap() - 0.05).abs() < 1e-9);
+        assert_eq!(comparison.resolved_weak_units, vec!["a".to_string()]);
+        assert_eq!(comparison.new_weak_units, vec!["d".to_string()]);
+
+        assert!(db.compare_sessions(a, other).unwrap().cross_lesson);
+        assert!(db.compare_sessions(a, 999).is_err());
+    }
+
+    #[test]
+    fn test_consistency_percentile() {
+        let db = Database::new(":memory:").unwrap();
+        assert_eq!(db.get_consistency_percentile(0.5).unwrap(), None);
+
+        for consistency in [Some(0.5), Some(0.6), Some(0.7), None] {
+            let stats = SessionStats {
+                duration_secs: 30,
+                consistency,
+                ..test_stats(1, 0.9)
+            };
+            db.save_session(&stats, "Test Lesson").unwrap();
+        }
+
+        // 未记录稳定度的会话不参与计算
+        let percentile = db.get_consistency_percentile(0.65).unwrap().unwrap();
+        assert!((percentile - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(db.get_consistency_percentile(0.8).unwrap(), Some(1.0));
+        assert_eq!(db.get_consistency_percentile(0.5).unwrap(), Some(0.0));
+    }
+
+    #[test]
+    fn test_weak_units_threshold() {
+        let db = Database::new(":memory:").unwrap();
+
+        let unit = |content: &str, error_count: usize| WeakUnit {
+            content: content.to_string(),
+            unit_type: UnitType::Character,
+            error_count,
+            total_count: 10,
+            error_rate: error_count as f32 / 10.0,
+        };
+        let stats = SessionStats {
+            weak_units: vec![unit("a", 2), unit("s", 5)],
+            ..test_stats(1, 0.9)
+        };
+        db.save_session(&stats, "Test Lesson").unwrap();
+
+        assert_eq!(db.get_overall_weak_units(10).unwrap().len(), 2);
+
+        let worst = db.get_overall_weak_units_above(10, 0.3).unwrap();
+        assert_eq!(worst.len(), 1);
+        assert_eq!(worst[0].content, "s");
+    }
+
+    #[test]
+    fn test_estimate_sessions_to_mastery() {
+        let db = Database::new(":memory:").unwrap();
+        let now = Utc::now().timestamp();
+
+        let save = |lesson_id: u32, i: i64, wpm: f64, accuracy: f64| {
+            let stats = SessionStats {
+                overall_wpm: wpm,
+                overall_cpm: wpm * 5.0,
+                timestamp: now + i,
+                ..test_stats(lesson_id, accuracy)
+            };
+            db.save_session(&stats, "Trend").unwrap();
+        };
+
+        // 每次提升 5 WPM：30, 35, 40 -> 还需 2 次到 50
+        for (i, wpm) in [30.0, 35.0, 40.0].into_iter().enumerate() {
+            save(1, i as i64, wpm, 0.99);
+        }
+        assert_eq!(
+            db.estimate_sessions_to_mastery(1, 50.0, 0.98).unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            db.estimate_sessions_to_mastery(1, 40.0, 0.98).unwrap(),
+            Some(0)
+        );
+
+        // 没有进步
+        for i in 0..3 {
+            save(2, i, 40.0, 0.95);
+        }
+        assert_eq!(
+            db.estimate_sessions_to_mastery(2, 50.0, 0.98).unwrap(),
+            None
+        );
+
+        // 数据不足
+        assert_eq!(
+            db.estimate_sessions_to_mastery(3, 50.0, 0.98).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_profile_round_trip() {
+        let db = Database::new(":memory:").unwrap();
+        let stats = SessionStats {
+            duration_secs: 30,
+            weak_units: vec![WeakUnit {
+                content: "q".to_string(),
+                unit_type: UnitType::Character,
+                error_count: 4,
+                total_count: 10,
+                error_rate: 0.4,
+            }],
+            ..test_stats(1, 0.9)
+        };
+        db.save_session(&stats, "Test Lesson").unwrap();
+        db.save_config("theme", "light").unwrap();
+
+        let json = db.export_profile().unwrap();
+        let profile = db.build_profile().unwrap();
+        assert_eq!(profile.overall.total_sessions, 1);
+        assert_eq!(profile.total_practice_secs, 30);
+        assert_eq!(profile.streak_days, 1);
+        assert_eq!(profile.weak_units.len(), 1);
+
+        let other = Database::new(":memory:").unwrap();
+        let imported = other.import_profile(&json).unwrap();
+        assert_eq!(imported, profile);
+        // 旧版逐项设置导入时迁移为整体设置
+        assert_eq!(other.load_settings().unwrap().theme, "light");
+        assert_eq!(other.get_config("theme").unwrap(), None);
+    }
+
+    #[test]
+    fn test_import_profile_rejects_unknown_config() {
+        let db = Database::new(":memory:").unwrap();
+        let mut profile = db.build_profile().unwrap();
+        let settings = Settings {
+            theme: "light".to_string(),
+            ..Settings::default()
+        };
+        profile.settings = BTreeMap::from([(
+            SETTINGS_KEY.to_string(),
+            serde_json::to_string(&settings).unwrap(),
+        )]);
+        let import = |profile: &UserProfile| {
+            let other = Database::new(":memory:").unwrap();
+            let result = other.import_profile(&serde_json::to_string(profile).unwrap());
+            (result, other.get_all_config().unwrap())
+        };
+
+        let (result, config) = import(&profile);
+        assert!(result.is_ok());
+        assert_eq!(config.len(), 1);
+
+        // 未知的配置项
+        let mut unknown_key = profile.clone();
+        unknown_key
+            .settings
+            .insert("evil".to_string(), "1".to_string());
+        let (result, config) = import(&unknown_key);
+        assert!(result.is_err());
+        assert!(config.is_empty());
+
+        // 未知的设置字段和无法解析的值
+        for json in [
+            r#"{"theme":"light","evil":1}"#,
+            r#"{"theme":"light","show_keyboard":"yes"}"#,
+        ] {
+            let mut bad = profile.clone();
+            bad.settings
+                .insert(SETTINGS_KEY.to_string(), json.to_string());
+            let (result, config) = import(&bad);
+            assert!(result.is_err(), "{}", json);
+            assert!(config.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_session_json_round_trip() {
+        let db = Database::new(":memory:").unwrap();
+        let stats = SessionStats {
+            overall_wpm: 52.5,
+            overall_cpm: 262.5,
+            total_keystrokes: 300,
+            error_count: 9,
+            duration_secs: 75,
+            timestamp: 1_700_000_000,
+            weak_units: vec![WeakUnit {
+                content: "th".to_string(),
+                unit_type: UnitType::Word,
+                error_count: 3,
+                total_count: 12,
+                error_rate: 0.25,
+            }],
+            consistency: Some(0.8),
+            peak_wpm: 70.0,
+            raw_wpm: 55.0,
+            ..test_stats(2, 0.97)
+        };
+        let id = db.save_session(&stats, "Bigrams").unwrap();
+        assert!(db.export_session_json(id + 1).is_err());
+
+        let json = db.export_session_json(id).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["completed_at"], 1_700_000_000);
+        assert_eq!(value["completed_at_iso"], "2023-11-14T22:13:20+00:00");
+        assert_eq!(value["weak_units"][0]["content"], "th");
+
+        let other = Database::new(":memory:").unwrap();
+        let new_id = other.import_session_json(&json).unwrap();
+        let mut original = db.get_session_export(id).unwrap();
+        let imported = other.get_session_export(new_id).unwrap();
+        original.record.id = new_id;
+        assert_eq!(imported, original);
+
+        db.save_session(&stats, "Bigrams").unwrap();
+        let all: Vec<SessionExport> =
+            serde_json::from_str(&db.export_all_sessions_json().unwrap()).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].record.id, id);
+    }
+
+    #[test]
+    fn test_sessions_by_day_and_calendar() {
+        let db = Database::new(":memory:").unwrap();
+        let today = Local::now().date_naive();
+        let yesterday = today.pred_opt().unwrap();
+        let noon = |date: NaiveDate| local_day_start(date) + 12 * 3600;
+
+        for (timestamp, duration_secs) in [
+            (noon(yesterday), 60),
+            (noon(today), 30),
+            (noon(today) + 60, 45),
+        ] {
+            let stats = SessionStats {
+                duration_secs,
+                timestamp,
+                ..test_stats(1, 0.9)
+            };
+            db.save_session(&stats, "Test Lesson").unwrap();
+        }
+
+        assert_eq!(db.get_sessions_on_day(today).unwrap().len(), 2);
+        assert_eq!(db.get_sessions_on_day(yesterday).unwrap().len(), 1);
+
+        let calendar = db.get_activity_calendar(3).unwrap();
+        assert_eq!(
+            calendar,
+            vec![
+                (yesterday.pred_opt().unwrap(), 0, 0),
+                (yesterday, 1, 60),
+                (today, 2, 75),
+            ]
+        );
     }
 }
@@ -0,0 +1,396 @@
+//! 界面与练习设置（整体以 JSON 保存在配置表的一个键下）
+
+use crate::{Database, DEFAULT_WEAK_UNIT_THRESHOLD};
+use anyhow::{bail, Result};
+use keyzen_core::{
+    AutoAdvance, ExerciseOrder, InputMode, LessonSort, MemoryMode, PartialLevel, StrictRecovery,
+    WhitespacePolicy,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// 配置表中保存整体设置的键
+pub const SETTINGS_KEY: &str = "settings";
+
+/// 配置表中保存上次练习位置的键
+pub const LAST_SESSION_KEY: &str = "last_session";
+
+/// 练习区默认最大宽度（像素），类似阅读栏宽度
+pub const DEFAULT_MAX_CONTENT_WIDTH: f32 = 800.0;
+
+/// 默认预览窗口（字符数）
+pub const DEFAULT_PREVIEW_WINDOW: usize = 500;
+
+/// 默认暂停检测时长（秒）
+pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 10;
+
+/// 默认自动跳转停顿（毫秒）
+pub const DEFAULT_AUTO_ADVANCE_DELAY_MS: u64 = 400;
+
+/// 默认实时 WPM 统计窗口（秒）
+pub const DEFAULT_WPM_WINDOW_SECS: u64 = 10;
+
+/// 默认最短保存时长（秒）：更短的会话视为误触，不写入历史
+pub const DEFAULT_MIN_SESSION_SECS: u64 = 5;
+
+/// 旧版逐项保存的设置键（迁移后删除）
+const LEGACY_KEYS: [&str; 18] = [
+    "theme",
+    "ui_language",
+    "memory_mode",
+    "exercise_order",
+    "max_content_width",
+    "show_keyboard",
+    "metronome_wpm",
+    "visual_beat_bpm",
+    "weak_unit_threshold",
+    "preview_window",
+    "idle_timeout_secs",
+    "auto_advance",
+    "auto_advance_delay_ms",
+    "allow_backspace",
+    "strict_completion",
+    "memory_ramp",
+    "skip_mastered",
+    "cjk_literal_space",
+];
+
+/// 全部偏好设置（缺失的字段使用默认值，便于新增设置）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// 主题（"dark" / "light"）
+    pub theme: String,
+    /// 界面语言代码（"zh-CN" / "en-US"）
+    pub ui_language: String,
+    pub memory_mode: MemoryMode,
+    pub exercise_order: ExerciseOrder,
+    /// 课程列表的排序方式
+    pub lesson_sort: LessonSort,
+    pub input_mode: InputMode,
+    /// 严格模式下输错后的恢复方式
+    pub strict_recovery: StrictRecovery,
+    /// 练习区最大宽度（None 表示不限制）
+    pub max_content_width: Option<f32>,
+    pub show_keyboard: bool,
+    /// 练习区用 · 和 ↵ 显示空格与换行
+    pub show_whitespace: bool,
+    /// 练习中隐藏统计信息（专注模式），完成后才显示
+    pub hide_stats: bool,
+    /// 节拍器目标速度（None 表示关闭）
+    pub metronome_wpm: Option<u32>,
+    /// 视觉节拍速度（None 表示关闭）
+    pub visual_beat_bpm: Option<u32>,
+    /// WPM 按速度分段着色的三个分界值（None 表示关闭）
+    pub wpm_bands: Option<[u32; 3]>,
+    /// 实时 WPM 统计最近多少秒的按键
+    pub wpm_window_secs: u64,
+    pub weak_unit_threshold: f32,
+    /// 光标后至少渲染的字符数（None 表示渲染全部）
+    pub preview_window: Option<usize>,
+    /// 暂停检测时长（None 表示关闭）
+    pub idle_timeout_secs: Option<u64>,
+    /// 短于该时长（秒）的会话不保存
+    pub min_session_secs: u64,
+    /// 开始前倒计时秒数（0 表示关闭），倒计时结束后才接受输入
+    pub start_countdown_secs: u64,
+    pub auto_advance: AutoAdvance,
+    pub auto_advance_delay_ms: u64,
+    pub allow_backspace: bool,
+    /// 覆盖课程类型默认的空格匹配方式（None 按课程类型）
+    pub whitespace_policy: Option<WhitespacePolicy>,
+    /// 最后一个字符必须输对才算完成
+    pub strict_completion: bool,
+    /// 有错完成时重来当前练习，直到无错完成
+    pub perfect_practice: bool,
+    /// 每个练习开始前显示预览，点击开始后才计时
+    pub preview_exercises: bool,
+    pub memory_ramp: bool,
+    pub skip_mastered: bool,
+    pub cjk_literal_space: bool,
+    /// 中文课程的速度只计汉字（不计标点、空格）
+    pub cjk_net_wpm: bool,
+    /// 启动时直接回到上次练习的课程
+    pub resume_on_launch: bool,
+}
+
+/// 上次练习的位置（用于启动时恢复）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LastSession {
+    pub lesson_id: u32,
+    /// 课程中的练习索引
+    pub exercise_index: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: "dark".to_string(),
+            ui_language: "zh-CN".to_string(),
+            memory_mode: MemoryMode::Off,
+            exercise_order: ExerciseOrder::default(),
+            lesson_sort: LessonSort::default(),
+            input_mode: InputMode::default(),
+            strict_recovery: StrictRecovery::default(),
+            max_content_width: Some(DEFAULT_MAX_CONTENT_WIDTH),
+            show_keyboard: false,
+            show_whitespace: false,
+            hide_stats: false,
+            metronome_wpm: None,
+            visual_beat_bpm: None,
+            wpm_bands: None,
+            wpm_window_secs: DEFAULT_WPM_WINDOW_SECS,
+            weak_unit_threshold: DEFAULT_WEAK_UNIT_THRESHOLD,
+            preview_window: Some(DEFAULT_PREVIEW_WINDOW),
+            idle_timeout_secs: Some(DEFAULT_IDLE_TIMEOUT_SECS),
+            min_session_secs: DEFAULT_MIN_SESSION_SECS,
+            start_countdown_secs: 0,
+            auto_advance: AutoAdvance::OnPerfect,
+            auto_advance_delay_ms: DEFAULT_AUTO_ADVANCE_DELAY_MS,
+            allow_backspace: true,
+            whitespace_policy: None,
+            strict_completion: false,
+            perfect_practice: false,
+            preview_exercises: false,
+            memory_ramp: false,
+            skip_mastered: false,
+            cjk_literal_space: true,
+            cjk_net_wpm: false,
+            resume_on_launch: false,
+        }
+    }
+}
+
+impl Settings {
+    /// 从旧版逐项保存的配置构建（缺失或无法解析的项使用默认值）
+    pub fn from_legacy(config: &BTreeMap<String, String>) -> Self {
+        let defaults = Self::default();
+        let get = |key: &str| config.get(key).map(String::as_str);
+        let flag = |key: &str, default: bool| match get(key) {
+            Some("true") => true,
+            Some("false") => false,
+            _ => default,
+        };
+
+        Self {
+            theme: match get("theme") {
+                Some(theme @ ("dark" | "light")) => theme.to_string(),
+                _ => defaults.theme,
+            },
+            ui_language: get("ui_language")
+                .map(str::to_string)
+                .unwrap_or(defaults.ui_language),
+            memory_mode: match get("memory_mode") {
+                Some("complete") => MemoryMode::Complete,
+                Some("first_letter") => MemoryMode::FirstLetter,
+                Some("partial_low") => MemoryMode::Partial(PartialLevel::Low),
+                Some("partial_medium") => MemoryMode::Partial(PartialLevel::Medium),
+                Some("partial_high") => MemoryMode::Partial(PartialLevel::High),
+                _ => MemoryMode::Off,
+            },
+            exercise_order: match get("exercise_order") {
+                Some("adaptive") => ExerciseOrder::Adaptive,
+                Some("sequential") => ExerciseOrder::Sequential,
+                _ => defaults.exercise_order,
+            },
+            lesson_sort: defaults.lesson_sort,
+            input_mode: defaults.input_mode,
+            strict_recovery: defaults.strict_recovery,
+            max_content_width: legacy_optional(
+                get("max_content_width"),
+                defaults.max_content_width,
+            ),
+            show_keyboard: flag("show_keyboard", defaults.show_keyboard),
+            show_whitespace: defaults.show_whitespace,
+            hide_stats: defaults.hide_stats,
+            metronome_wpm: legacy_optional(get("metronome_wpm"), defaults.metronome_wpm),
+            visual_beat_bpm: legacy_optional(get("visual_beat_bpm"), defaults.visual_beat_bpm),
+            wpm_bands: defaults.wpm_bands,
+            wpm_window_secs: defaults.wpm_window_secs,
+            weak_unit_threshold: get("weak_unit_threshold")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.weak_unit_threshold),
+            preview_window: legacy_optional(get("preview_window"), defaults.preview_window),
+            idle_timeout_secs: legacy_optional(
+                get("idle_timeout_secs"),
+                defaults.idle_timeout_secs,
+            ),
+            min_session_secs: defaults.min_session_secs,
+            start_countdown_secs: defaults.start_countdown_secs,
+            auto_advance: match get("auto_advance") {
+                Some("manual") => AutoAdvance::Manual,
+                _ => AutoAdvance::OnPerfect,
+            },
+            auto_advance_delay_ms: get("auto_advance_delay_ms")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.auto_advance_delay_ms),
+            allow_backspace: flag("allow_backspace", defaults.allow_backspace),
+            whitespace_policy: defaults.whitespace_policy,
+            strict_completion: flag("strict_completion", defaults.strict_completion),
+            perfect_practice: defaults.perfect_practice,
+            preview_exercises: defaults.preview_exercises,
+            memory_ramp: flag("memory_ramp", defaults.memory_ramp),
+            skip_mastered: flag("skip_mastered", defaults.skip_mastered),
+            cjk_literal_space: flag("cjk_literal_space", defaults.cjk_literal_space),
+            cjk_net_wpm: defaults.cjk_net_wpm,
+            resume_on_launch: defaults.resume_on_launch,
+        }
+    }
+}
+
+/// 解析旧版可关闭的数值设置：缺失时使用默认值，"none" 或非正数表示关闭
+fn legacy_optional<T>(value: Option<&str>, default: Option<T>) -> Option<T>
+where
+    T: FromStr + PartialOrd + Default,
+{
+    match value {
+        Some(s) => s.parse::<T>().ok().filter(|v| *v > T::default()),
+        None => default,
+    }
+}
+
+impl Database {
+    /// 加载设置；首次加载时从旧版逐项保存的配置迁移
+    pub fn load_settings(&self) -> Result<Settings> {
+        if let Some(json) = self.get_config(SETTINGS_KEY)? {
+            return Ok(serde_json::from_str(&json)?);
+        }
+
+        let settings = Settings::from_legacy(&self.get_all_config()?);
+        self.save_settings(&settings)?;
+        for key in LEGACY_KEYS {
+            self.delete_config(key)?;
+        }
+        Ok(settings)
+    }
+
+    /// 保存设置
+    pub fn save_settings(&self, settings: &Settings) -> Result<()> {
+        self.save_config(SETTINGS_KEY, &serde_json::to_string(settings)?)
+    }
+
+    /// 记录上次练习的位置
+    pub fn save_last_session(&self, last: &LastSession) -> Result<()> {
+        self.save_config(LAST_SESSION_KEY, &serde_json::to_string(last)?)
+    }
+
+    /// 读取上次练习的位置（从未练习过或无法解析时返回 None）
+    pub fn load_last_session(&self) -> Result<Option<LastSession>> {
+        Ok(self
+            .get_config(LAST_SESSION_KEY)?
+            .and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// 清除上次练习的位置（如课程已被删除）
+    pub fn clear_last_session(&self) -> Result<()> {
+        self.delete_config(LAST_SESSION_KEY)
+    }
+
+    /// 导入档案中的配置：先完整校验再写入，出现未知的键或字段、无法解析的值时报错且不做修改
+    /// 只有旧版逐项设置时按迁移规则转换为整体设置
+    pub(crate) fn import_config(&self, config: &BTreeMap<String, String>) -> Result<()> {
+        let known = |key: &str| {
+            key == SETTINGS_KEY || key == LAST_SESSION_KEY || LEGACY_KEYS.contains(&key)
+        };
+        if let Some(key) = config.keys().find(|key| !known(key)) {
+            bail!("Unknown config key: {}", key);
+        }
+
+        let settings = match config.get(SETTINGS_KEY) {
+            Some(json) => parse_settings_strict(json)?,
+            None => Settings::from_legacy(config),
+        };
+        let last_session: Option<LastSession> = config
+            .get(LAST_SESSION_KEY)
+            .map(|json| serde_json::from_str(json))
+            .transpose()?;
+
+        self.save_settings(&settings)?;
+        if let Some(last) = last_session {
+            self.save_last_session(&last)?;
+        }
+        Ok(())
+    }
+}
+
+/// 解析设置 JSON，拒绝 Settings 中不存在的字段（缺失的字段仍使用默认值）
+fn parse_settings_strict(json: &str) -> Result<Settings> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let known = serde_json::to_value(Settings::default())?;
+    if let (Some(fields), Some(known)) = (value.as_object(), known.as_object()) {
+        if let Some(field) = fields.keys().find(|field| !known.contains_key(*field)) {
+            bail!("Unknown settings field: {}", field);
+        }
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let db = Database::new(":memory:").unwrap();
+        let settings = Settings {
+            theme: "light".to_string(),
+            memory_mode: MemoryMode::Partial(PartialLevel::Medium),
+            input_mode: InputMode::Invisible,
+            max_content_width: None,
+            metronome_wpm: Some(45),
+            idle_timeout_secs: None,
+            auto_advance: AutoAdvance::Manual,
+            skip_mastered: true,
+            ..Settings::default()
+        };
+
+        db.save_settings(&settings).unwrap();
+        assert_eq!(db.load_settings().unwrap(), settings);
+    }
+
+    #[test]
+    fn test_migrate_legacy_keys() {
+        let db = Database::new(":memory:").unwrap();
+        db.save_config("theme", "light").unwrap();
+        db.save_config("memory_mode", "first_letter").unwrap();
+        db.save_config("max_content_width", "none").unwrap();
+        db.save_config("metronome_wpm", "60").unwrap();
+        db.save_config("allow_backspace", "false").unwrap();
+        db.save_config("unrelated", "kept").unwrap();
+
+        let settings = db.load_settings().unwrap();
+        assert_eq!(settings.theme, "light");
+        assert_eq!(settings.memory_mode, MemoryMode::FirstLetter);
+        assert_eq!(settings.max_content_width, None);
+        assert_eq!(settings.metronome_wpm, Some(60));
+        assert!(!settings.allow_backspace);
+        assert_eq!(settings.preview_window, Some(DEFAULT_PREVIEW_WINDOW));
+
+        // 迁移后旧键被删除，设置保存在单个键下
+        assert_eq!(db.get_config("theme").unwrap(), None);
+        assert!(db.get_config(SETTINGS_KEY).unwrap().is_some());
+        assert_eq!(db.get_config("unrelated").unwrap().as_deref(), Some("kept"));
+        assert_eq!(db.load_settings().unwrap(), settings);
+    }
+
+    #[test]
+    fn test_last_session_round_trip() {
+        let db = Database::new(":memory:").unwrap();
+        assert_eq!(db.load_last_session().unwrap(), None);
+
+        let last = LastSession {
+            lesson_id: 3,
+            exercise_index: 2,
+        };
+        db.save_last_session(&last).unwrap();
+        assert_eq!(db.load_last_session().unwrap(), Some(last));
+
+        db.clear_last_session().unwrap();
+        assert_eq!(db.load_last_session().unwrap(), None);
+
+        // 无法解析的记录视为没有
+        db.save_config(LAST_SESSION_KEY, "garbage").unwrap();
+        assert_eq!(db.load_last_session().unwrap(), None);
+    }
+}